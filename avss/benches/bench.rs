@@ -9,31 +9,35 @@ use util::algebra::polynomial::MultilinearPolynomial;
 use util::random_oracle::RandomOracle;
 
 use util::algebra::coset::Coset;
-use util::algebra::field::mersenne61_ext::Mersenne61Ext;
+use util::algebra::field::{
+    babybear::BabyBear,
+    extension::{QuadExt, QuarticExt},
+    fp64::Fp64,
+    koalabear::KoalaBear,
+    mersenne31_ext::Mersenne31Ext,
+    mersenne61_ext::Mersenne61Ext,
+};
+use util::params::ProtocolParams;
 use util::split_n;
-use util::{CODE_RATE, SECURITY_BITS};
 
-fn avss_deal(log_n: usize, terminate_round: usize) {
+fn avss_deal<T: Field + 'static>(log_n: usize, terminate_round: usize) {
     let log_t = log_n - 2;
     let log_d = log_t * 2;
-    let oracle = RandomOracle::new(log_d - terminate_round, SECURITY_BITS / CODE_RATE);
-    let mut interpolate_cosets = vec![Coset::new(
-        1 << (log_t * 2 + CODE_RATE),
-        Mersenne61Ext::random_element(),
-    )];
-    for i in 1..log_d {
-        interpolate_cosets.push(interpolate_cosets[i - 1].pow(2));
-    }
+    let params = ProtocolParams::new(3, 100, terminate_round, 0, T::FIELD_CHOICE).unwrap();
+    let interpolate_cosets = params
+        .interpolate_cosets(log_d, T::random_element())
+        .unwrap();
+    let oracle = RandomOracle::from_params(log_d - terminate_round, &params);
     let polynomial = MultilinearPolynomial::random_polynomial(log_d);
 
-    let x_shift = Mersenne61Ext::random_element();
+    let x_shift = T::random_element();
     let coset_x = Coset::new(1 << log_n, x_shift);
     let mut folding_parameter = vec![];
     let v = split_n((1 << log_t) - 1);
     for i in &v {
         folding_parameter.push(coset_x.pow(*i).all_elements());
     }
-    let y_shift = Mersenne61Ext::random_element();
+    let y_shift = T::random_element();
     let coset_y = Coset::new(1 << log_n, y_shift);
     let last_len = folding_parameter.last().unwrap().len();
     for i in &v {
@@ -47,65 +51,65 @@ fn avss_deal(log_n: usize, terminate_round: usize) {
                 .collect(),
         );
     }
-    let mut parties = vec![];
+    let mut parties: Vec<AvssParty<_>> = vec![];
     for i in 0..(1 << (log_n * 2)) {
         let mut open_point = vec![];
         for j in 0..log_d {
             open_point.push(folding_parameter[j][i % folding_parameter[j].len()]);
         }
-        parties.push(AvssParty::new(
-            log_d - terminate_round,
-            &interpolate_cosets,
-            open_point,
-            &oracle,
-        ));
+        parties.push(AvssParty::new(&params, &interpolate_cosets, open_point, &oracle).unwrap());
     }
     let mut dealer = Dealer::new(
-        log_d - terminate_round,
+        &params,
         &polynomial,
         &interpolate_cosets,
         &oracle,
         &folding_parameter,
-    );
+    )
+    .unwrap();
     dealer.send_evaluations(&mut parties);
-    dealer.commit_functions(&parties);
+    dealer.commit_functions(&mut parties);
     dealer.prove();
-    dealer.commit_foldings(&parties);
+    dealer.commit_foldings(&mut parties);
     dealer.query();
 }
 
-fn bench_avss_deal(c: &mut Criterion) {
+fn bench_avss_deal<T: Field + 'static>(c: &mut Criterion) {
     for i in 5..12 {
         let terminate_round = 1;
-        c.bench_function(&format!("avss prove {}", i), move |b| {
-            b.iter(|| {
-                avss_deal(i, terminate_round);
-            })
-        });
+        c.bench_function(
+            &format!("avss prove {:?} {}", T::FIELD_CHOICE, i),
+            move |b| {
+                b.iter(|| {
+                    avss_deal::<T>(i, terminate_round);
+                })
+            },
+        );
     }
 }
 
-fn avss_verify(criterion: &mut Criterion, log_n: usize, terminate_round: usize) {
+fn avss_verify<T: Field + 'static>(
+    criterion: &mut Criterion,
+    log_n: usize,
+    terminate_round: usize,
+) {
     let log_t = log_n - 2;
     let log_d = log_t * 2;
-    let oracle = RandomOracle::new(log_d - terminate_round, SECURITY_BITS / CODE_RATE);
-    let mut interpolate_cosets = vec![Coset::new(
-        1 << (log_t * 2 + CODE_RATE),
-        Mersenne61Ext::random_element(),
-    )];
-    for i in 1..log_d {
-        interpolate_cosets.push(interpolate_cosets[i - 1].pow(2));
-    }
+    let params = ProtocolParams::new(3, 100, terminate_round, 0, T::FIELD_CHOICE).unwrap();
+    let interpolate_cosets = params
+        .interpolate_cosets(log_d, T::random_element())
+        .unwrap();
+    let oracle = RandomOracle::from_params(log_d - terminate_round, &params);
     let polynomial = MultilinearPolynomial::random_polynomial(log_d);
 
-    let x_shift = Mersenne61Ext::random_element();
+    let x_shift = T::random_element();
     let coset_x = Coset::new(1 << log_n, x_shift);
     let mut folding_parameter = vec![];
     let v = split_n((1 << log_t) - 1);
     for i in &v {
         folding_parameter.push(coset_x.pow(*i).all_elements());
     }
-    let y_shift = Mersenne61Ext::random_element();
+    let y_shift = T::random_element();
     let coset_y = Coset::new(1 << log_n, y_shift);
     let last_len = folding_parameter.last().unwrap().len();
     for i in &v {
@@ -119,30 +123,26 @@ fn avss_verify(criterion: &mut Criterion, log_n: usize, terminate_round: usize)
                 .collect(),
         );
     }
-    let mut parties = vec![];
+    let mut parties: Vec<AvssParty<_>> = vec![];
     for i in 0..(1 << (log_n * 2)) {
         let mut open_point = vec![];
         for j in 0..log_d {
             open_point.push(folding_parameter[j][i % folding_parameter[j].len()]);
         }
-        parties.push(AvssParty::new(
-            log_d - terminate_round,
-            &interpolate_cosets,
-            open_point,
-            &oracle,
-        ));
+        parties.push(AvssParty::new(&params, &interpolate_cosets, open_point, &oracle).unwrap());
     }
     let mut dealer = Dealer::new(
-        log_d - terminate_round,
+        &params,
         &polynomial,
         &interpolate_cosets,
         &oracle,
         &folding_parameter,
-    );
+    )
+    .unwrap();
     dealer.send_evaluations(&mut parties);
-    dealer.commit_functions(&parties);
+    dealer.commit_functions(&mut parties);
     dealer.prove();
-    dealer.commit_foldings(&parties);
+    dealer.commit_foldings(&mut parties);
     let (folding, function) = dealer.query();
     let mut folding0 = vec![];
     let mut function0 = vec![];
@@ -152,23 +152,36 @@ fn avss_verify(criterion: &mut Criterion, log_n: usize, terminate_round: usize)
         }
         function0.push(function[i][0].clone());
     }
-    criterion.bench_function(&format!("avss verify {}", log_n), move |b| {
-        b.iter(|| {
-            assert!(parties[0].verify(&folding0, &function0));
-        })
-    });
+    criterion.bench_function(
+        &format!("avss verify {:?} {}", T::FIELD_CHOICE, log_n),
+        move |b| {
+            b.iter(|| {
+                parties[0].verify(&folding0, &function0).unwrap();
+            })
+        },
+    );
 }
 
-fn bench_avss_verify(c: &mut Criterion) {
+fn bench_avss_verify<T: Field + 'static>(c: &mut Criterion) {
     for i in 5..12 {
         let terminate_round = 1;
-        avss_verify(c, i, terminate_round);
+        avss_verify::<T>(c, i, terminate_round);
     }
 }
 
 criterion_group!(
     name = benches;
     config = Criterion::default().sample_size(10);
-    targets = bench_avss_deal, bench_avss_verify
+    targets =
+        bench_avss_deal::<Mersenne61Ext>,
+        bench_avss_deal::<QuadExt<Fp64>>,
+        bench_avss_deal::<QuarticExt<BabyBear>>,
+        bench_avss_deal::<QuarticExt<KoalaBear>>,
+        bench_avss_deal::<Mersenne31Ext>,
+        bench_avss_verify::<Mersenne61Ext>,
+        bench_avss_verify::<QuadExt<Fp64>>,
+        bench_avss_verify::<QuarticExt<BabyBear>>,
+        bench_avss_verify::<QuarticExt<KoalaBear>>,
+        bench_avss_verify::<Mersenne31Ext>
 );
 criterion_main!(benches);
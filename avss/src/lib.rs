@@ -1,8 +1,144 @@
 pub mod one2many {
     pub mod prover;
     pub mod verifier;
+
+    use util::params::Capabilities;
+
+    /// Features of `ProtocolParams` the one-to-many prover and verifiers
+    /// implement.
+    pub const CAPABILITIES: Capabilities = Capabilities {
+        hiding: false,
+        shared_trees: true,
+        max_folding_arity: 2,
+        terminate_round: true,
+    };
 }
 pub mod avss {
     pub mod dealer;
     pub mod party;
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::avss::dealer::Dealer;
+    use crate::avss::party::AvssParty;
+    use util::algebra::coset::Coset;
+    use util::algebra::field::mersenne61_ext::Mersenne61Ext;
+    use util::algebra::field::Field;
+    use util::algebra::polynomial::MultilinearPolynomial;
+    use util::dispute::{self, Complaint, DisputeParty, Disqualification, VerifyingKey};
+    use util::params::ProtocolParams;
+    use util::random_oracle::RandomOracle;
+    use util::split_n;
+
+    const LOG_T: usize = 4;
+    const THRESHOLD: usize = (1 << LOG_T) - 1;
+
+    /// Shares a random polynomial, replacing the shares of `wrong` by random
+    /// ones as a faulty dealer, and returns the complaints.
+    fn deal(
+        wrong: &[usize],
+    ) -> (
+        Dealer<Mersenne61Ext>,
+        Vec<AvssParty<Mersenne61Ext>>,
+        Vec<Complaint>,
+    ) {
+        let params = ProtocolParams::default();
+        let interpolate_cosets = params
+            .interpolate_cosets(LOG_T, Mersenne61Ext::random_element())
+            .unwrap();
+        let oracle = RandomOracle::from_params(LOG_T - 1, &params);
+        let coset_x = Coset::new(1 << (LOG_T + 1), Mersenne61Ext::random_element());
+        let folding_parameter: Vec<_> = split_n((1 << LOG_T) - 1)
+            .iter()
+            .map(|i| coset_x.pow(*i).all_elements())
+            .collect();
+        let mut parties: Vec<AvssParty<_>> = (0..(1 << (LOG_T + 1)))
+            .map(|i| {
+                let open_point = folding_parameter.iter().map(|x| x[i % x.len()]).collect();
+                AvssParty::new(&params, &interpolate_cosets, open_point, &oracle).unwrap()
+            })
+            .collect();
+        let mut dealer = Dealer::new(
+            &params,
+            &MultilinearPolynomial::random_polynomial(LOG_T),
+            &interpolate_cosets,
+            &oracle,
+            &folding_parameter,
+        )
+        .unwrap();
+        dealer.send_evaluations(&mut parties);
+        let final_variables = LOG_T - params.total_round(LOG_T).unwrap();
+        for i in wrong {
+            parties[*i].set_share(&MultilinearPolynomial::random_polynomial(final_variables));
+        }
+        dealer.commit_functions(&mut parties);
+        dealer.prove();
+        dealer.commit_foldings(&mut parties);
+        let (folding, function) = dealer.query();
+        let complaints = dispute::complaints(&parties, &folding, &function);
+        (dealer, parties, complaints)
+    }
+
+    fn verifying_keys(parties: &[AvssParty<Mersenne61Ext>]) -> Vec<VerifyingKey> {
+        parties.iter().map(|x| x.verifying_key()).collect()
+    }
+
+    #[test]
+    fn honest_dealer() {
+        let (dealer, mut parties, complaints) = deal(&[]);
+        assert!(complaints.is_empty());
+        let responses = dealer.respond(&complaints, &verifying_keys(&parties));
+        assert_eq!(
+            dispute::resolve(&mut parties, &complaints, &responses, THRESHOLD),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn faulty_dealer() {
+        let (dealer, mut parties, complaints) = deal(&[2, 7]);
+        assert_eq!(
+            complaints.iter().map(|x| x.party).collect::<Vec<_>>(),
+            vec![2, 7]
+        );
+        let responses = dealer.respond(&complaints, &verifying_keys(&parties));
+        assert_eq!(
+            dispute::resolve(&mut parties, &complaints, &responses, THRESHOLD),
+            Ok(())
+        );
+        let (folding, function) = dealer.query();
+        assert!(dispute::complaints(&parties, &folding, &function).is_empty());
+    }
+
+    #[test]
+    fn malicious_dealer() {
+        let (dealer, parties, complaints) = deal(&[2, 7]);
+        let mut responses = dealer.respond(&complaints, &verifying_keys(&parties));
+        responses[0].final_poly =
+            MultilinearPolynomial::random_polynomial(responses[0].final_poly.variable_num());
+        assert!(matches!(
+            dispute::resolve(&mut parties.clone(), &complaints, &responses, THRESHOLD),
+            Err(Disqualification::InvalidResponse(2, _))
+        ));
+        assert_eq!(
+            dispute::resolve(
+                &mut parties.clone(),
+                &complaints,
+                &responses[1..],
+                THRESHOLD
+            ),
+            Err(Disqualification::MissingResponse(2))
+        );
+
+        let (dealer, mut parties, complaints) = deal(&(0..=THRESHOLD).collect::<Vec<_>>());
+        let responses = dealer.respond(&complaints, &verifying_keys(&parties));
+        assert_eq!(
+            dispute::resolve(&mut parties, &complaints, &responses, THRESHOLD),
+            Err(Disqualification::TooManyComplaints {
+                complaints: THRESHOLD + 1,
+                threshold: THRESHOLD
+            })
+        );
+    }
+}
@@ -1,62 +1,82 @@
-use std::{cell::RefCell, rc::Rc};
-
 use super::verifier::One2ManyVerifier;
+use super::CAPABILITIES;
 use util::algebra::polynomial::Polynomial;
 
-use util::merkle_tree::MERKLE_ROOT_SIZE;
 use util::query_result::QueryResult;
 use util::{
     algebra::{
         coset::Coset,
         field::{as_bytes_vec, Field},
     },
-    merkle_tree::MerkleTreeProver,
+    merkle_tree::{Blake3Algorithm, MerkleHasher, MerkleTreeProver},
+    parallel,
+    params::{ParamsError, ProtocolParams},
     random_oracle::RandomOracle,
 };
 
-struct InterpolateValue<T: Field> {
+struct InterpolateValue<T: Field, H: MerkleHasher> {
     value: Vec<T>,
-    merkle_tree: MerkleTreeProver,
+    width: usize,
+    merkle_tree: MerkleTreeProver<H>,
 }
 
-impl<T: Field> InterpolateValue<T> {
+impl<T: Field, H: MerkleHasher> InterpolateValue<T, H> {
     fn new(value: Vec<T>) -> Self {
-        let len = value.len() / 2;
-        let merkle_tree = MerkleTreeProver::new(
-            (0..len)
-                .map(|i| as_bytes_vec(&[value[i], value[i + len]]))
-                .collect(),
-        );
-        Self { value, merkle_tree }
+        Self::new_batch(vec![value])
+    }
+
+    /// Commits to several functions on the same domain with one tree, each
+    /// leaf holding the values of all of them.
+    fn new_batch(values: Vec<Vec<T>>) -> Self {
+        let width = values.len();
+        let value = values.concat();
+        let len = value.len() / width / 2;
+        let merkle_tree = MerkleTreeProver::new(parallel::map(len, |i| {
+            let leaf = (0..width)
+                .flat_map(|j| [value[j * 2 * len + i], value[j * 2 * len + i + len]])
+                .collect::<Vec<_>>();
+            as_bytes_vec(&leaf)
+        }));
+        Self {
+            value,
+            width,
+            merkle_tree,
+        }
+    }
+
+    /// Values of the first function of the leaves.
+    fn first(&self) -> &[T] {
+        &self.value[..self.value.len() / self.width]
     }
 
     fn leave_num(&self) -> usize {
         self.merkle_tree.leave_num()
     }
 
-    fn commit(&self) -> [u8; MERKLE_ROOT_SIZE] {
+    fn commit(&self) -> H::Hash {
         self.merkle_tree.commit()
     }
 
     fn query(&self, leaf_indices: &Vec<usize>) -> QueryResult<T> {
         let len = self.merkle_tree.leave_num();
-        let proof_values = leaf_indices
-            .iter()
-            .flat_map(|j| [(*j, self.value[*j]), (*j + len, self.value[*j + len])])
+        let proof_values = (0..self.width)
+            .flat_map(|k| leaf_indices.iter().map(move |j| k * 2 * len + j))
+            .flat_map(|j| [(j, self.value[j]), (j + len, self.value[j + len])])
             .collect();
         let proof_bytes = self.merkle_tree.open(&leaf_indices);
         QueryResult {
             proof_bytes,
             proof_values,
+            salts: vec![],
         }
     }
 }
 
-struct CosetInterpolate<T: Field> {
-    interpolates: Vec<InterpolateValue<T>>,
+struct CosetInterpolate<T: Field, H: MerkleHasher> {
+    interpolates: Vec<InterpolateValue<T, H>>,
 }
 
-impl<T: Field> CosetInterpolate<T> {
+impl<T: Field, H: MerkleHasher> CosetInterpolate<T, H> {
     fn len(&self) -> usize {
         self.interpolates.len()
     }
@@ -68,74 +88,92 @@ impl<T: Field> CosetInterpolate<T> {
                 .collect(),
         }
     }
-    fn field_size(&self) -> usize {
-        self.interpolates[0].value.len()
-    }
-    fn from_interpolates(interpolates: Vec<InterpolateValue<T>>) -> Self {
+    fn from_interpolates(interpolates: Vec<InterpolateValue<T, H>>) -> Self {
         CosetInterpolate { interpolates }
     }
 
-    fn get_interpolation(&self, index: usize) -> &InterpolateValue<T> {
+    fn get_interpolation(&self, index: usize) -> &InterpolateValue<T, H> {
         let len = self.interpolates.len();
         assert!((len & (len - 1)) == 0);
         &self.interpolates[index & (len - 1)]
     }
 }
 
-pub struct One2ManyProver<T: Field> {
+pub struct One2ManyProver<T: Field, H: MerkleHasher = Blake3Algorithm> {
+    params: ProtocolParams,
     total_round: usize,
     interpolate_cosets: Vec<Coset<T>>,
-    functions: Vec<CosetInterpolate<T>>,
-    foldings: Vec<CosetInterpolate<T>>,
+    functions: Vec<CosetInterpolate<T, H>>,
+    /// With `shared_trees`, the functions of rounds after the first are
+    /// only committed in the trees of the foldings of the previous round.
+    shared_functions: Vec<Vec<Vec<T>>>,
+    foldings: Vec<CosetInterpolate<T, H>>,
     oracle: RandomOracle<T>,
+    pow_nonce: u64,
     final_value: Vec<Polynomial<T>>,
 }
 
-impl<T: Field> One2ManyProver<T> {
+impl<T: Field, H: MerkleHasher> One2ManyProver<T, H> {
     pub fn new(
-        total_round: usize,
+        params: &ProtocolParams,
         interpolate_coset: &Vec<Coset<T>>,
-        functions: Vec<Vec<Vec<T>>>,
+        mut functions: Vec<Vec<Vec<T>>>,
         oracle: &RandomOracle<T>,
-    ) -> One2ManyProver<T> {
-        assert_eq!(total_round, functions.len());
-        let functions: Vec<CosetInterpolate<T>> = functions
+    ) -> Result<One2ManyProver<T, H>, ParamsError> {
+        params.check_field::<T>()?;
+        params.require(CAPABILITIES)?;
+        let total_round = functions.len();
+        let shared_functions = if params.shared_trees {
+            functions.split_off(1)
+        } else {
+            vec![]
+        };
+        let functions: Vec<CosetInterpolate<T, H>> = functions
             .into_iter()
             .map(|x| CosetInterpolate::new(x))
             .collect();
 
-        One2ManyProver {
+        Ok(One2ManyProver {
+            params: *params,
             total_round,
             interpolate_cosets: interpolate_coset.clone(),
             functions,
+            shared_functions,
             foldings: vec![],
             oracle: oracle.clone(),
+            pow_nonce: 0,
             final_value: vec![],
-        }
+        })
     }
 
-    pub fn commit_functions(&self, verifiers: &Vec<Rc<RefCell<One2ManyVerifier<T>>>>) {
-        for i in 0..self.total_round {
-            for (idx, j) in verifiers.into_iter().enumerate() {
-                let function = self.functions[i].get_interpolation(idx);
-                j.borrow_mut()
-                    .set_function(function.leave_num(), &function.commit());
+    /// Root of the first function sent to the `index`-th verifier by
+    /// `commit_functions`.
+    pub fn share_commitment(&self, index: usize) -> H::Hash {
+        self.functions[0].get_interpolation(index).commit()
+    }
+
+    pub fn commit_functions(&self, mut verifiers: Vec<&mut One2ManyVerifier<T, H>>) {
+        for j in verifiers.iter_mut() {
+            j.receive_params(&self.params);
+        }
+        for coset_interpolate in &self.functions {
+            for (idx, j) in verifiers.iter_mut().enumerate() {
+                let function = coset_interpolate.get_interpolation(idx);
+                j.set_function(function.leave_num(), &function.commit());
             }
         }
     }
 
-    pub fn commit_foldings(&self, verifiers: &Vec<Rc<RefCell<One2ManyVerifier<T>>>>) {
+    pub fn commit_foldings(&self, mut verifiers: Vec<&mut One2ManyVerifier<T, H>>) {
         for i in 0..(self.total_round - 1) {
-            for (idx, j) in verifiers.into_iter().enumerate() {
+            for (idx, j) in verifiers.iter_mut().enumerate() {
                 let interpolation = self.foldings[i].get_interpolation(idx);
-                j.borrow_mut()
-                    .receive_folding_root(interpolation.leave_num(), interpolation.commit());
+                j.receive_folding_root(interpolation.leave_num(), interpolation.commit());
             }
         }
-        for i in 0..verifiers.len() {
-            verifiers[i]
-                .borrow_mut()
-                .set_final_value(&self.final_value[i % self.final_value.len()]);
+        for (i, j) in verifiers.iter_mut().enumerate() {
+            j.set_final_value(&self.final_value[i % self.final_value.len()]);
+            j.set_pow_nonce(self.pow_nonce);
         }
     }
 
@@ -145,52 +183,82 @@ impl<T: Field> One2ManyProver<T> {
         rolling_function_index: usize,
         challenge: T,
     ) -> Vec<T> {
-        let mut res = vec![];
-        let len = self.functions[round].field_size();
+        let len = self.interpolate_cosets[round].size();
         let get_folding_value = if round == 0 {
-            self.functions[round].get_interpolation(rolling_function_index)
+            self.functions[round]
+                .get_interpolation(rolling_function_index)
+                .first()
         } else {
-            self.foldings[round - 1].get_interpolation(rolling_function_index)
+            self.foldings[round - 1]
+                .get_interpolation(rolling_function_index)
+                .first()
         };
-        let coset = &self.interpolate_cosets[round];
-        for i in 0..(len / 2) {
-            let x = get_folding_value.value[i];
-            let nx = get_folding_value.value[i + len / 2];
-            let new_v = (x + nx) + challenge * (x - nx) * coset.element_inv_at(i);
+        let function = self.function_value(round, rolling_function_index);
+        let elements_inv = self.interpolate_cosets[round].elements_inv();
+        parallel::map(len / 2, |i| {
+            let x = get_folding_value[i];
+            let nx = get_folding_value[i + len / 2];
+            let new_v = (x + nx) + challenge * (x - nx) * elements_inv[i];
             if round == 0 {
-                res.push(new_v);
+                new_v
             } else {
-                let fv = &self.functions[round].interpolates[rolling_function_index];
-                let x = fv.value[i];
-                let nx = fv.value[i + len / 2];
-                let new_v =
-                    (new_v * challenge + (x + nx)) * challenge + (x - nx) * coset.element_inv_at(i);
-                res.push(new_v);
+                let x = function[i];
+                let nx = function[i + len / 2];
+                (new_v * challenge + (x + nx)) * challenge + (x - nx) * elements_inv[i]
             }
+        })
+    }
+
+    /// Values of the `index`-th function of `round`, which in the shared
+    /// layout follow the folding in the trees of the previous round.
+    fn function_value(&self, round: usize, index: usize) -> &[T] {
+        if round == 0 || !self.params.shared_trees {
+            &self.functions[round].get_interpolation(index).value
+        } else {
+            let folding = &self.foldings[round - 1].get_interpolation(index).value;
+            &folding[folding.len() / 2..]
+        }
+    }
+
+    /// Number of functions folded in `round`, one per distinct opening.
+    fn function_num(&self, round: usize) -> usize {
+        if round == 0 || !self.params.shared_trees {
+            self.functions[round].len()
+        } else {
+            self.foldings[round - 1].len()
         }
-        res
     }
 
     pub fn prove(&mut self) {
         for i in 0..self.total_round {
             let challenge = self.oracle.folding_challenges[i];
             if i < self.total_round - 1 {
-                let mut interpolates = vec![];
-                for j in 0..self.functions[i].len() {
-                    let next_evalutation = self.evaluation_next_domain(i, j, challenge);
-                    let interpolate_value = InterpolateValue::new(next_evalutation);
-                    interpolates.push(interpolate_value);
-                }
+                let foldings = parallel::map(self.function_num(i), |j| {
+                    self.evaluation_next_domain(i, j, challenge)
+                });
+                let interpolates = if self.params.shared_trees {
+                    let functions = &self.shared_functions[i];
+                    let len = foldings.len().max(functions.len());
+                    parallel::map(len, |k| {
+                        InterpolateValue::new_batch(vec![
+                            foldings[k % foldings.len()].clone(),
+                            functions[k % functions.len()].clone(),
+                        ])
+                    })
+                } else {
+                    foldings.into_iter().map(InterpolateValue::new).collect()
+                };
                 self.foldings
                     .push(CosetInterpolate::from_interpolates(interpolates));
             } else {
-                for j in 0..self.functions[i].len() {
+                for j in 0..self.function_num(i) {
                     let next_evalutation = self.evaluation_next_domain(i, j, challenge);
                     let coefficients = self.interpolate_cosets[i + 1].ifft(next_evalutation);
                     self.final_value.push(Polynomial::new(coefficients));
                 }
             }
         }
+        self.pow_nonce = self.oracle.grind();
     }
 
     pub fn query(&self) -> (Vec<Vec<QueryResult<T>>>, Vec<Vec<QueryResult<T>>>) {
@@ -199,7 +267,7 @@ impl<T: Field> One2ManyProver<T> {
         let mut leaf_indices = self.oracle.query_list.clone();
 
         for i in 0..self.total_round {
-            let len = self.functions[i].field_size();
+            let len = self.interpolate_cosets[i].size();
             leaf_indices = leaf_indices.iter_mut().map(|v| *v % (len >> 1)).collect();
             leaf_indices.sort();
             leaf_indices.dedup();
@@ -207,7 +275,7 @@ impl<T: Field> One2ManyProver<T> {
             if i == 0 {
                 let query_result = self.functions[0].get_interpolation(0).query(&leaf_indices);
                 functions_res.push(vec![query_result]);
-            } else {
+            } else if !self.params.shared_trees {
                 let query_result = self.functions[i]
                     .interpolates
                     .iter()
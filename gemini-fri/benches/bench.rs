@@ -5,105 +5,146 @@ use criterion::*;
 use gemini_fri::{prover::FriProver, verifier::FriVerifier};
 use util::{
     algebra::{
-        coset::Coset, field::mersenne61_ext::Mersenne61Ext, field::Field,
+        field::{
+            babybear::BabyBear,
+            extension::{QuadExt, QuarticExt},
+            fp64::Fp64,
+            koalabear::KoalaBear,
+            mersenne31_ext::Mersenne31Ext,
+            mersenne61_ext::Mersenne61Ext,
+            Field, OddCharacteristicField,
+        },
         polynomial::MultilinearPolynomial,
     },
     random_oracle::RandomOracle,
 };
 
-use util::{CODE_RATE, SECURITY_BITS};
+use util::params::ProtocolParams;
 
-fn commit(variable_num: usize) {
-    let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
-    let mut interpolate_cosets = vec![Coset::new(
-        1 << (variable_num + CODE_RATE),
-        Mersenne61Ext::random_element(),
-    )];
-    for i in 1..variable_num {
-        interpolate_cosets.push(interpolate_cosets[i - 1].pow(2));
-    }
-    let oracle = RandomOracle::new(variable_num, SECURITY_BITS / CODE_RATE);
-    let prover = FriProver::new(variable_num, &interpolate_cosets, polynomial, &oracle);
-    prover.commit_first_polynomial();
+fn commit<T: Field>(criterion: &mut Criterion, variable_num: usize) {
+    let polynomial = MultilinearPolynomial::<T>::random_polynomial(variable_num);
+    let params = ProtocolParams::new(3, 100, 0, 0, T::FIELD_CHOICE).unwrap();
+    let interpolate_cosets = params
+        .interpolate_cosets(variable_num, T::random_element())
+        .unwrap();
+    let oracle = RandomOracle::from_params(variable_num, &params);
+    let name = format!("gemini commit {:?} {}", params.field, variable_num);
+    criterion.bench_function(&name, move |b| {
+        b.iter_batched(
+            || polynomial.clone(),
+            |p| {
+                let prover: FriProver<_> =
+                    FriProver::new(&params, &interpolate_cosets, p, &oracle).unwrap();
+                prover.commit_first_polynomial();
+            },
+            BatchSize::SmallInput,
+        )
+    });
 }
 
 fn bench_commit(c: &mut Criterion) {
     for i in 5..20 {
-        c.bench_function(&format!("bench gemini commit {}", i), |b| {
-            b.iter(|| commit(i))
-        });
+        commit::<Mersenne61Ext>(c, i);
+        commit::<QuadExt<Fp64>>(c, i);
+        commit::<QuarticExt<BabyBear>>(c, i);
+        commit::<QuarticExt<KoalaBear>>(c, i);
+        commit::<Mersenne31Ext>(c, i);
     }
 }
 
-fn open(criterion: &mut Criterion, variable_num: usize) {
+fn open<T: Field>(criterion: &mut Criterion, variable_num: usize) {
     let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
-    let mut interpolate_cosets = vec![Coset::new(
-        1 << (variable_num + CODE_RATE),
-        Mersenne61Ext::random_element(),
-    )];
-    for i in 1..variable_num {
-        interpolate_cosets.push(interpolate_cosets[i - 1].pow(2));
-    }
-    let oracle = RandomOracle::new(variable_num, SECURITY_BITS / CODE_RATE);
-    let prover = FriProver::new(variable_num, &interpolate_cosets, polynomial, &oracle);
+    let params = ProtocolParams::new(3, 100, 0, 0, T::FIELD_CHOICE).unwrap();
+    let interpolate_cosets = params
+        .interpolate_cosets(variable_num, T::random_element())
+        .unwrap();
+    let oracle = RandomOracle::from_params(variable_num, &params);
+    let prover: FriProver<_> =
+        FriProver::new(&params, &interpolate_cosets, polynomial, &oracle).unwrap();
     let commitment = prover.commit_first_polynomial();
-    let mut verifier = FriVerifier::new(variable_num, &interpolate_cosets, commitment, &oracle);
+    let mut verifier = FriVerifier::new(
+        &params,
+        variable_num,
+        &interpolate_cosets,
+        commitment,
+        &oracle,
+    )
+    .unwrap();
     let open_point = verifier.get_open_point();
-    criterion.bench_function(&format!("gemini prove {}", variable_num), |b| {
-        b.iter_batched(
-            || (prover.clone(), verifier.clone()),
-            |(mut p, mut v)| {
-                p.commit_functions(&mut v, &open_point);
-                p.compute_tuples();
-                p.prove();
-                p.commit_foldings(&mut v);
-                p.query();
-            },
-            BatchSize::SmallInput,
-        )
-    });
+    criterion.bench_function(
+        &format!("gemini prove {:?} {}", params.field, variable_num),
+        |b| {
+            b.iter_batched(
+                || (prover.clone(), verifier.clone()),
+                |(mut p, mut v)| {
+                    p.commit_functions(&mut v, &open_point);
+                    p.compute_tuples();
+                    p.prove();
+                    p.commit_foldings(&mut v);
+                    p.query();
+                },
+                BatchSize::SmallInput,
+            )
+        },
+    );
 }
 
 fn bench_open(c: &mut Criterion) {
     for i in 5..20 {
-        open(c, i);
+        open::<Mersenne61Ext>(c, i);
+        open::<QuadExt<Fp64>>(c, i);
+        open::<QuarticExt<BabyBear>>(c, i);
+        open::<QuarticExt<KoalaBear>>(c, i);
+        open::<Mersenne31Ext>(c, i);
     }
 }
 
-fn verify(criterion: &mut Criterion, variable_num: usize) {
+fn verify<T: OddCharacteristicField>(criterion: &mut Criterion, variable_num: usize) {
     let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
-    let mut interpolate_cosets = vec![Coset::new(
-        1 << (variable_num + CODE_RATE),
-        Mersenne61Ext::random_element(),
-    )];
-    for i in 1..variable_num {
-        interpolate_cosets.push(interpolate_cosets[i - 1].pow(2));
-    }
-    let oracle = RandomOracle::new(variable_num, SECURITY_BITS / CODE_RATE);
-    let mut prover = FriProver::new(variable_num, &interpolate_cosets, polynomial, &oracle);
+    let params = ProtocolParams::new(3, 100, 0, 0, T::FIELD_CHOICE).unwrap();
+    let interpolate_cosets = params
+        .interpolate_cosets(variable_num, T::random_element())
+        .unwrap();
+    let oracle = RandomOracle::from_params(variable_num, &params);
+    let mut prover: FriProver<_> =
+        FriProver::new(&params, &interpolate_cosets, polynomial, &oracle).unwrap();
     let commitment = prover.commit_first_polynomial();
-    let mut verifier = FriVerifier::new(variable_num, &interpolate_cosets, commitment, &oracle);
+    let mut verifier = FriVerifier::new(
+        &params,
+        variable_num,
+        &interpolate_cosets,
+        commitment,
+        &oracle,
+    )
+    .unwrap();
     let open_point = verifier.get_open_point();
     prover.commit_functions(&mut verifier, &open_point);
     let tuples = prover.compute_tuples();
     prover.prove();
     prover.commit_foldings(&mut verifier);
     let (folding_proofs, function_proofs) = prover.query();
-    criterion.bench_function(&format!("gemini verify {}", variable_num), |b| {
-        b.iter_batched(
-            || verifier.clone(),
-            |mut v| {
-                v.set_tuples(&tuples);
-                assert!(v.verify(&folding_proofs, &function_proofs));
-            },
-            BatchSize::SmallInput,
-        )
-    });
+    criterion.bench_function(
+        &format!("gemini verify {:?} {}", params.field, variable_num),
+        |b| {
+            b.iter_batched(
+                || verifier.clone(),
+                |mut v| {
+                    v.set_tuples(&tuples).unwrap();
+                    v.verify(&folding_proofs, &function_proofs).unwrap();
+                },
+                BatchSize::SmallInput,
+            )
+        },
+    );
 }
 
 fn bench_verify(c: &mut Criterion) {
     for i in 5..20 {
-        verify(c, i);
+        verify::<Mersenne61Ext>(c, i);
+        verify::<QuadExt<Fp64>>(c, i);
+        verify::<QuarticExt<BabyBear>>(c, i);
+        verify::<QuarticExt<KoalaBear>>(c, i);
+        verify::<Mersenne31Ext>(c, i);
     }
 }
 
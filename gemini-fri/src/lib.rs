@@ -1,7 +1,21 @@
 pub mod prover;
 pub mod verifier;
 
-use util::algebra::field::Field;
+use prover::FriProver;
+use std::mem::size_of;
+use util::algebra::{
+    coset::Coset,
+    field::{Field, OddCharacteristicField},
+    polynomial::MultilinearPolynomial,
+};
+use util::merkle_tree::{Blake3Algorithm, MerkleHasher};
+use util::multilinear_pcs::MultilinearPCS;
+use util::params::{Capabilities, ParamsError, ProtocolParams};
+use util::query_result::QueryResult;
+use util::random_oracle::RandomOracle;
+use util::transcript::Transcript;
+use util::verification_error::VerificationError;
+use verifier::FriVerifier;
 
 #[derive(Debug, Clone)]
 pub struct Tuple<T: Field> {
@@ -11,50 +25,198 @@ pub struct Tuple<T: Field> {
 }
 
 impl<T: Field> Tuple<T> {
+    /// The values of `tuples`, in the order the transcript absorbs them.
+    pub fn flatten(tuples: &[Tuple<T>]) -> Vec<T> {
+        tuples.iter().flat_map(|x| [x.a, x.b, x.c]).collect()
+    }
+}
+
+/// Halving the folded value needs `2` to be invertible: in characteristic 2
+/// the check would hold whenever the folded value is zero.
+impl<T: OddCharacteristicField> Tuple<T> {
     pub fn verify(&self, beta: T, folding_param: T) -> bool {
         let v = beta * (self.a + self.b) + folding_param * (self.a - self.b);
         v * T::INVERSE_2 == self.c * beta
     }
 }
 
+/// Features of `ProtocolParams` the Gemini prover and verifier implement.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    hiding: false,
+    shared_trees: false,
+    max_folding_arity: 2,
+    terminate_round: false,
+};
+
+const TRANSCRIPT_LABEL: &[u8] = b"Gemini";
+
+/// Public parameters of Gemini openings. The challenges of every opening are
+/// derived from its Fiat-Shamir transcript.
+#[derive(Clone)]
+pub struct GeminiParams<T: Field> {
+    pub variable_num: usize,
+    pub protocol: ProtocolParams,
+    pub interpolate_cosets: Vec<Coset<T>>,
+}
+
+/// Everything the prover sends after the polynomial commitment.
+#[derive(Clone)]
+pub struct GeminiProof<T: Field, H: MerkleHasher = Blake3Algorithm> {
+    pub params: ProtocolParams,
+    pub function_roots: Vec<H::Hash>,
+    pub tuples: Vec<Tuple<T>>,
+    pub folding_roots: Vec<H::Hash>,
+    pub final_value: T,
+    pub pow_nonce: u64,
+    pub folding_proofs: Vec<QueryResult<T>>,
+    pub function_proofs: Vec<QueryResult<T>>,
+}
+
+impl<T: Field, H: MerkleHasher> GeminiProof<T, H> {
+    pub fn proof_size(&self) -> usize {
+        self.tuples.len() * size_of::<Tuple<T>>()
+            + (self.function_roots.len() + self.folding_roots.len()) * H::DIGEST_SIZE
+            + size_of::<T>()
+            + size_of::<u64>()
+            + self
+                .folding_proofs
+                .iter()
+                .chain(&self.function_proofs)
+                .map(|x| x.proof_size())
+                .sum::<usize>()
+    }
+}
+
+impl<T: Field> GeminiParams<T> {
+    /// Fiat-Shamir oracle used for non-interactive openings.
+    pub fn oracle(&self) -> RandomOracle<T> {
+        RandomOracle::from_transcript(Transcript::new(TRANSCRIPT_LABEL), self.protocol.query_num)
+            .with_grinding(self.protocol.grinding_bits)
+    }
+}
+
+impl<T: OddCharacteristicField, H: MerkleHasher> MultilinearPCS<T, H> for GeminiParams<T> {
+    type Prover = FriProver<T, H>;
+    type Proof = GeminiProof<T, H>;
+
+    /// The polynomial is folded down to a constant, so the terminate round
+    /// of `protocol` must be 0.
+    fn setup(variable_num: usize, protocol: ProtocolParams) -> Result<Self, ParamsError> {
+        protocol.require(CAPABILITIES)?;
+        Ok(GeminiParams {
+            variable_num,
+            protocol,
+            interpolate_cosets: protocol.interpolate_cosets(variable_num, T::random_element())?,
+        })
+    }
+
+    fn commit(
+        &self,
+        polynomial: MultilinearPolynomial<T>,
+    ) -> Result<(H::Hash, Self::Prover), ParamsError> {
+        if polynomial.variable_num() != self.variable_num {
+            return Err(ParamsError::VariableNumMismatch);
+        }
+        let prover = FriProver::new(
+            &self.protocol,
+            &self.interpolate_cosets,
+            polynomial,
+            &self.oracle(),
+        )?;
+        Ok((prover.commit_first_polynomial(), prover))
+    }
+
+    fn open(&self, prover: &Self::Prover, point: &[T]) -> Self::Proof {
+        prover.open(&point.to_vec())
+    }
+
+    fn verify(
+        &self,
+        commitment: &H::Hash,
+        point: &[T],
+        value: T,
+        proof: &Self::Proof,
+    ) -> Result<(), VerificationError> {
+        if point.len() != self.variable_num || proof.folding_roots.len() + 1 != self.variable_num {
+            return Err(VerificationError::WrongProofShape);
+        }
+        let mut verifier = FriVerifier::<T, H>::new(
+            &self.protocol,
+            self.variable_num,
+            &self.interpolate_cosets,
+            *commitment,
+            &self.oracle(),
+        )
+        .map_err(|_| VerificationError::ParameterMismatch)?;
+        verifier.set_open_point(point.to_vec());
+        verifier.receive_params(&proof.params);
+        for root in &proof.function_roots {
+            verifier.append_function(*root);
+        }
+        verifier.set_tuples(&proof.tuples)?;
+        for (i, root) in proof.folding_roots.iter().enumerate() {
+            verifier.receive_folding_root(self.interpolate_cosets[i + 1].size() / 2, *root);
+        }
+        verifier.set_final_value(proof.final_value);
+        verifier.set_pow_nonce(proof.pow_nonce);
+        verifier.verify(&proof.folding_proofs, &proof.function_proofs)?;
+        // The last tuple folds the last variable, leaving the evaluation.
+        if proof.tuples.last().unwrap().c != value {
+            return Err(VerificationError::EvaluationMismatch);
+        }
+        Ok(())
+    }
+
+    fn proof_size(proof: &Self::Proof) -> usize {
+        proof.proof_size()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::mem::size_of;
 
-    use crate::{prover::FriProver, verifier::FriVerifier, Tuple};
+    use crate::{prover::FriProver, verifier::FriVerifier, GeminiParams, Tuple};
     use util::{
         algebra::{
-            coset::Coset, field::mersenne61_ext::Mersenne61Ext, field::Field,
-            polynomial::MultilinearPolynomial,
+            field::mersenne61_ext::Mersenne61Ext, field::Field, polynomial::MultilinearPolynomial,
         },
-        merkle_tree::MERKLE_ROOT_SIZE,
+        merkle_tree::{Blake3Algorithm, Keccak256Algorithm, MerkleHasher, Sha256Algorithm},
+        multilinear_pcs::MultilinearPCS,
+        params::{FieldChoice, ParamsError, ProtocolParams},
+        poseidon::PoseidonAlgorithm,
         random_oracle::RandomOracle,
-        CODE_RATE, SECURITY_BITS,
     };
 
-    fn output_proof_size(variable_num: usize) -> usize {
+    fn output_proof_size<H: MerkleHasher>(variable_num: usize, grinding_bits: usize) -> usize {
         let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
-        let mut interpolate_cosets = vec![Coset::new(
-            1 << (variable_num + CODE_RATE),
-            Mersenne61Ext::random_element(),
-        )];
-        for i in 1..variable_num {
-            interpolate_cosets.push(interpolate_cosets[i - 1].pow(2));
-        }
-        let oracle = RandomOracle::new(variable_num, SECURITY_BITS / CODE_RATE);
-        let mut prover = FriProver::new(variable_num, &interpolate_cosets, polynomial, &oracle);
+        let params =
+            ProtocolParams::new(3, 100, 0, grinding_bits, FieldChoice::Mersenne61Ext).unwrap();
+        let interpolate_cosets = params
+            .interpolate_cosets(variable_num, Mersenne61Ext::random_element())
+            .unwrap();
+        let oracle = RandomOracle::from_params(variable_num, &params).with_query_seed(0);
+        let mut prover: FriProver<_, H> =
+            FriProver::new(&params, &interpolate_cosets, polynomial, &oracle).unwrap();
         let commitment = prover.commit_first_polynomial();
-        let mut verifier = FriVerifier::new(variable_num, &interpolate_cosets, commitment, &oracle);
+        let mut verifier = FriVerifier::new(
+            &params,
+            variable_num,
+            &interpolate_cosets,
+            commitment,
+            &oracle,
+        )
+        .unwrap();
         let open_point = verifier.get_open_point();
         prover.commit_functions(&mut verifier, &open_point);
         let tuples = prover.compute_tuples();
         prover.prove();
         prover.commit_foldings(&mut verifier);
         let (folding_proofs, function_proofs) = prover.query();
-        verifier.set_tuples(&tuples);
-        assert!(verifier.verify(&folding_proofs, &function_proofs));
+        assert_eq!(verifier.set_tuples(&tuples), Ok(()));
+        assert_eq!(verifier.verify(&folding_proofs, &function_proofs), Ok(()));
         tuples.len() * size_of::<Tuple<Mersenne61Ext>>()
-            + variable_num * MERKLE_ROOT_SIZE * 2
+            + variable_num * H::DIGEST_SIZE * 2
             + 2 * size_of::<Mersenne61Ext>()
             + folding_proofs.iter().map(|x| x.proof_size()).sum::<usize>()
             + function_proofs
@@ -66,11 +228,83 @@ mod tests {
     #[test]
     fn test_proof_size() {
         for i in 5..20 {
-            let proof_size = output_proof_size(i);
+            let proof_size = output_proof_size::<Blake3Algorithm>(i, 0);
             println!(
                 "gemini proof size of {} variables is {} bytes",
                 i, proof_size
             );
         }
     }
+
+    #[test]
+    fn pluggable_hasher() {
+        output_proof_size::<Sha256Algorithm>(8, 0);
+        output_proof_size::<Keccak256Algorithm>(8, 0);
+        output_proof_size::<PoseidonAlgorithm<Mersenne61Ext>>(8, 0);
+    }
+
+    #[test]
+    fn grinding() {
+        let with_grinding = output_proof_size::<Blake3Algorithm>(10, 12);
+        assert!(with_grinding < output_proof_size::<Blake3Algorithm>(10, 0));
+    }
+
+    #[test]
+    fn send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<FriProver<Mersenne61Ext>>();
+        assert_send_sync::<FriVerifier<Mersenne61Ext>>();
+    }
+
+    #[test]
+    fn multilinear_pcs() {
+        type Scheme = GeminiParams<Mersenne61Ext>;
+        let variable_num = 8;
+        let params = <Scheme as MultilinearPCS<_, Blake3Algorithm>>::setup(
+            variable_num,
+            ProtocolParams::new(3, 100, 0, 0, FieldChoice::Mersenne61Ext).unwrap(),
+        )
+        .unwrap();
+        let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
+        let (commitment, prover) = params.commit(polynomial.clone()).unwrap();
+        for _ in 0..2 {
+            let point: Vec<_> = (0..variable_num)
+                .map(|_| Mersenne61Ext::random_element())
+                .collect();
+            let value = polynomial.evaluate(&point);
+            let proof = params.open(&prover, &point);
+            assert_eq!(params.verify(&commitment, &point, value, &proof), Ok(()));
+            assert_ne!(
+                params.verify(
+                    &commitment,
+                    &point,
+                    value + Mersenne61Ext::from_int(1),
+                    &proof
+                ),
+                Ok(())
+            );
+            assert!(<Scheme as MultilinearPCS<_, Blake3Algorithm>>::proof_size(&proof) > 0);
+        }
+        let unsupported = [
+            (ProtocolParams::default(), "terminate round"),
+            (
+                ProtocolParams::default().with_hiding(true).unwrap(),
+                "hiding mode",
+            ),
+            (
+                ProtocolParams::default().with_shared_trees(true).unwrap(),
+                "shared trees",
+            ),
+            (
+                ProtocolParams::default().with_folding_arity(4).unwrap(),
+                "folding arity",
+            ),
+        ];
+        for (protocol, feature) in unsupported {
+            assert_eq!(
+                <Scheme as MultilinearPCS<_, Blake3Algorithm>>::setup(variable_num, protocol).err(),
+                Some(ParamsError::Unsupported(feature))
+            );
+        }
+    }
 }
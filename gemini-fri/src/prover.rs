@@ -1,7 +1,7 @@
 use super::verifier::FriVerifier;
 use util::{
     algebra::{field, polynomial::MultilinearPolynomial},
-    merkle_tree::MERKLE_ROOT_SIZE,
+    params::{ParamsError, ProtocolParams},
     random_oracle::RandomOracle,
 };
 
@@ -11,25 +11,24 @@ use util::{
         coset::Coset,
         field::{as_bytes_vec, Field},
     },
-    merkle_tree::MerkleTreeProver,
+    merkle_tree::{Blake3Algorithm, MerkleHasher, MerkleTreeProver},
+    parallel,
 };
 
-use super::Tuple;
+use super::{GeminiProof, Tuple, CAPABILITIES};
 
 #[derive(Clone)]
-struct InterpolateValue<T: Field> {
+struct InterpolateValue<T: Field, H: MerkleHasher> {
     value: Vec<T>,
-    merkle_tree: MerkleTreeProver,
+    merkle_tree: MerkleTreeProver<H>,
 }
 
-impl<T: Field> InterpolateValue<T> {
+impl<T: Field, H: MerkleHasher> InterpolateValue<T, H> {
     fn new(value: Vec<T>) -> Self {
         let len = value.len() / 2;
-        let merkle_tree = MerkleTreeProver::new(
-            (0..len)
-                .map(|i| as_bytes_vec(&[value[i], value[i + len]]))
-                .collect(),
-        );
+        let merkle_tree = MerkleTreeProver::new(parallel::map(len, |i| {
+            as_bytes_vec(&[value[i], value[i + len]])
+        }));
         Self { value, merkle_tree }
     }
 
@@ -37,7 +36,7 @@ impl<T: Field> InterpolateValue<T> {
         self.merkle_tree.leave_num()
     }
 
-    fn commit(&self) -> [u8; MERKLE_ROOT_SIZE] {
+    fn commit(&self) -> H::Hash {
         self.merkle_tree.commit()
     }
 
@@ -51,17 +50,18 @@ impl<T: Field> InterpolateValue<T> {
         QueryResult {
             proof_bytes,
             proof_values,
+            salts: vec![],
         }
     }
 }
 
 #[derive(Clone)]
-pub struct Function<T: Field> {
-    interpolation: InterpolateValue<T>,
+pub struct Function<T: Field, H: MerkleHasher = Blake3Algorithm> {
+    interpolation: InterpolateValue<T, H>,
     evaluations: Vec<(T, T)>,
 }
 
-impl<T: Field> Function<T> {
+impl<T: Field, H: MerkleHasher> Function<T, H> {
     pub fn new(value: Vec<T>, evaluations: Vec<(T, T)>) -> Self {
         Function {
             interpolation: InterpolateValue::new(value),
@@ -71,37 +71,46 @@ impl<T: Field> Function<T> {
 }
 
 #[derive(Clone)]
-pub struct FriProver<T: Field> {
+pub struct FriProver<T: Field, H: MerkleHasher = Blake3Algorithm> {
+    params: ProtocolParams,
     total_round: usize,
     interpolate_cosets: Vec<Coset<T>>,
-    functions: Vec<Function<T>>,
+    functions: Vec<Function<T, H>>,
     polynomials: Vec<MultilinearPolynomial<T>>,
-    foldings: Vec<InterpolateValue<T>>,
+    foldings: Vec<InterpolateValue<T, H>>,
     oracle: RandomOracle<T>,
+    pow_nonce: u64,
     final_value: Option<T>,
 }
 
-impl<T: Field> FriProver<T> {
+impl<T: Field, H: MerkleHasher> FriProver<T, H> {
+    /// The polynomial is folded down to a constant, so
+    /// `params.terminate_round` must be 0.
     pub fn new(
-        total_round: usize,
+        params: &ProtocolParams,
         interpolate_coset: &Vec<Coset<T>>,
         polynomial: MultilinearPolynomial<T>,
         oracle: &RandomOracle<T>,
-    ) -> FriProver<T> {
+    ) -> Result<FriProver<T, H>, ParamsError> {
+        params.check_field::<T>()?;
+        params.require(CAPABILITIES)?;
+        let total_round = params.total_round(polynomial.variable_num())?;
         let interpolation = interpolate_coset[0].fft(polynomial.coefficients().clone());
-        FriProver {
+        Ok(FriProver {
+            params: *params,
             total_round,
             interpolate_cosets: interpolate_coset.clone(),
             functions: vec![Function::new(interpolation, vec![])],
             polynomials: vec![polynomial],
             foldings: vec![],
             oracle: oracle.clone(),
+            pow_nonce: 0,
             final_value: None,
-        }
+        })
     }
 
     pub fn compute_tuples(&mut self) -> Vec<Tuple<T>> {
-        let beta = self.oracle.beta;
+        let beta = self.oracle.beta_challenge();
         let mut tuples = vec![];
         for i in 0..self.total_round {
             tuples.push(Tuple {
@@ -110,6 +119,7 @@ impl<T: Field> FriProver<T> {
                 c: self.polynomials[i + 1].evaluate_as_polynomial(beta * beta),
             });
         }
+        self.oracle.absorb_field_elements(&Tuple::flatten(&tuples));
         self.functions[0].evaluations = vec![(beta, tuples[0].a), (-beta, tuples[0].b)];
         for i in 1..tuples.len() {
             self.functions[i].evaluations = vec![
@@ -121,31 +131,43 @@ impl<T: Field> FriProver<T> {
         tuples
     }
 
-    pub fn commit_first_polynomial(&self) -> [u8; MERKLE_ROOT_SIZE] {
+    pub fn commit_first_polynomial(&self) -> H::Hash {
         self.functions[0].interpolation.commit()
     }
 
-    pub fn commit_functions(&mut self, verifier: &mut FriVerifier<T>, open_point: &Vec<T>) {
+    fn fold_functions(&mut self, open_point: &Vec<T>) {
         assert_eq!(open_point.len(), self.total_round);
+        self.oracle.absorb_root(&self.commit_first_polynomial());
+        self.oracle.absorb_field_elements(open_point);
         for i in 0..self.total_round {
             let last = self.polynomials.last().unwrap();
             let next_polynomial = last.folding(open_point[i]);
             self.polynomials.push(next_polynomial);
         }
         for i in 1..self.total_round {
-            self.functions.push(Function::new(
+            let function = Function::new(
                 self.interpolate_cosets[0].fft(self.polynomials[i].coefficients().clone()),
                 vec![],
-            ));
-            verifier.append_function(self.functions[i].interpolation.commit());
+            );
+            self.oracle.absorb_root(&function.interpolation.commit());
+            self.functions.push(function);
         }
     }
 
-    pub fn commit_foldings(&self, verifier: &mut FriVerifier<T>) {
+    pub fn commit_functions(&mut self, verifier: &mut FriVerifier<T, H>, open_point: &Vec<T>) {
+        self.fold_functions(open_point);
+        verifier.receive_params(&self.params);
+        for function in &self.functions[1..] {
+            verifier.append_function(function.interpolation.commit());
+        }
+    }
+
+    pub fn commit_foldings(&self, verifier: &mut FriVerifier<T, H>) {
         for i in 0..(self.total_round - 1) {
             verifier.receive_folding_root(self.foldings[i].leave_num(), self.foldings[i].commit());
         }
-        verifier.set_final_value(self.final_value.unwrap())
+        verifier.set_final_value(self.final_value.unwrap());
+        verifier.set_pow_nonce(self.pow_nonce);
     }
 
     fn initial_interpolation(&self) -> Vec<T> {
@@ -178,40 +200,39 @@ impl<T: Field> FriProver<T> {
     }
 
     fn evaluation_next_domain(&self, round: usize, challenge: T) -> Vec<T> {
-        let mut res = vec![];
         let coset = &self.interpolate_cosets[round];
         let len = coset.size();
-        if round == 0 {
-            let function = self.initial_interpolation();
-            for i in 0..(len / 2) {
-                let x = function[i];
-                let nx = function[i + len / 2];
-                let new_v = (x + nx) + challenge * (x - nx) * coset.element_inv_at(i);
-                res.push(new_v);
-            }
+        let function;
+        let values = if round == 0 {
+            function = self.initial_interpolation();
+            &function
         } else {
-            let last_folding = &self.foldings.last().unwrap().value;
-            for i in 0..(len / 2) {
-                let x = last_folding[i];
-                let nx = last_folding[i + len / 2];
-                let new_v = (x + nx) + challenge * (x - nx) * coset.element_inv_at(i);
-                res.push(new_v);
-            }
-        }
-        res
+            &self.foldings.last().unwrap().value
+        };
+        let elements_inv = coset.elements_inv();
+        parallel::map(len / 2, |i| {
+            let x = values[i];
+            let nx = values[i + len / 2];
+            (x + nx) + challenge * (x - nx) * elements_inv[i]
+        })
     }
 
     pub fn prove(&mut self) {
+        self.oracle.rlc_challenge();
         for i in 0..self.total_round {
-            let challenge = self.oracle.folding_challenges[i];
+            let challenge = self.oracle.folding_challenge(i);
             let next_evalutation = self.evaluation_next_domain(i, challenge);
             if i < self.total_round - 1 {
                 let interpolate_value = InterpolateValue::new(next_evalutation);
+                self.oracle.absorb_root(&interpolate_value.commit());
                 self.foldings.push(interpolate_value);
             } else {
+                self.oracle.absorb_field_elements(&next_evalutation[..1]);
                 self.final_value = Some(next_evalutation[0]);
             }
         }
+        self.pow_nonce = self.oracle.grind();
+        self.oracle.sample_queries();
     }
 
     pub fn query(&self) -> (Vec<QueryResult<T>>, Vec<QueryResult<T>>) {
@@ -239,4 +260,28 @@ impl<T: Field> FriProver<T> {
         }
         (folding_res, functions_res.unwrap())
     }
+
+    /// Opening at `open_point` with the challenges of the prover's oracle.
+    /// It does not change the prover, but must not be mixed with the
+    /// interactive methods above.
+    pub fn open(&self, open_point: &Vec<T>) -> GeminiProof<T, H> {
+        let mut session = self.clone();
+        session.fold_functions(open_point);
+        let tuples = session.compute_tuples();
+        session.prove();
+        let (folding_proofs, function_proofs) = session.query();
+        GeminiProof {
+            params: session.params,
+            function_roots: session.functions[1..]
+                .iter()
+                .map(|x| x.interpolation.commit())
+                .collect(),
+            tuples,
+            folding_roots: session.foldings.iter().map(|x| x.commit()).collect(),
+            final_value: session.final_value.unwrap(),
+            pow_nonce: session.pow_nonce,
+            folding_proofs,
+            function_proofs,
+        }
+    }
 }
@@ -1,45 +1,57 @@
-use super::Tuple;
-use util::merkle_tree::MERKLE_ROOT_SIZE;
+use super::{Tuple, CAPABILITIES};
+use util::params::{ParamsError, ProtocolParams};
 use util::query_result::QueryResult;
 use util::random_oracle::RandomOracle;
 use util::{
-    algebra::{coset::Coset, field::Field},
-    merkle_tree::MerkleTreeVerifier,
+    algebra::{
+        coset::Coset,
+        field::{Field, OddCharacteristicField},
+    },
+    merkle_tree::{Blake3Algorithm, MerkleHasher, MerkleTreeVerifier},
+    verification_error::VerificationError,
 };
 
 #[derive(Clone)]
-pub struct FriVerifier<T: Field> {
+pub struct FriVerifier<T: Field, H: MerkleHasher = Blake3Algorithm> {
+    params: ProtocolParams,
+    prover_params: Option<ProtocolParams>,
     total_round: usize,
     interpolate_cosets: Vec<Coset<T>>,
-    function_root: Vec<(MerkleTreeVerifier, Vec<(T, T)>)>,
-    folding_root: Vec<MerkleTreeVerifier>,
+    function_root: Vec<MerkleTreeVerifier<H>>,
+    tuples: Vec<Tuple<T>>,
+    folding_root: Vec<MerkleTreeVerifier<H>>,
     oracle: RandomOracle<T>,
     final_value: Option<T>,
     open_point: Option<Vec<T>>,
+    pow_nonce: u64,
 }
 
-impl<T: Field> FriVerifier<T> {
+impl<T: Field, H: MerkleHasher> FriVerifier<T, H> {
     pub fn new(
-        total_round: usize,
+        params: &ProtocolParams,
+        variable_num: usize,
         coset: &Vec<Coset<T>>,
-        polynomial_commitment: [u8; MERKLE_ROOT_SIZE],
+        polynomial_commitment: H::Hash,
         oracle: &RandomOracle<T>,
-    ) -> Self {
-        FriVerifier {
-            total_round,
+    ) -> Result<Self, ParamsError> {
+        params.check_field::<T>()?;
+        params.require(CAPABILITIES)?;
+        Ok(FriVerifier {
+            params: *params,
+            prover_params: None,
+            total_round: params.total_round(variable_num)?,
             interpolate_cosets: coset.clone(),
-            function_root: vec![(
-                MerkleTreeVerifier {
-                    leave_number: coset[0].size() / 2,
-                    merkle_root: polynomial_commitment,
-                },
-                vec![],
-            )],
+            function_root: vec![MerkleTreeVerifier {
+                leave_number: coset[0].size() / 2,
+                merkle_root: polynomial_commitment,
+            }],
+            tuples: vec![],
             folding_root: vec![],
             oracle: oracle.clone(),
             final_value: None,
             open_point: None,
-        }
+            pow_nonce: 0,
+        })
     }
 
     pub fn get_open_point(&mut self) -> Vec<T> {
@@ -50,51 +62,125 @@ impl<T: Field> FriVerifier<T> {
         point
     }
 
-    pub fn append_function(&mut self, function_root: [u8; MERKLE_ROOT_SIZE]) {
-        self.function_root.push((
-            MerkleTreeVerifier {
-                merkle_root: function_root,
-                leave_number: self.interpolate_cosets[0].size() / 2,
-            },
-            vec![],
-        ));
+    pub fn set_open_point(&mut self, open_point: Vec<T>) {
+        self.open_point = Some(open_point);
     }
 
-    pub fn set_tuples(&mut self, tuples: &Vec<Tuple<T>>) {
-        let beta = self.oracle.beta;
-        for i in 0..tuples.len() {
-            assert!(tuples[i].verify(beta, self.open_point.as_ref().unwrap()[i]));
-            self.function_root[i].1.push((beta, tuples[i].a));
-            self.function_root[i].1.push((-beta, tuples[i].b));
-            if i < tuples.len() - 1 {
-                self.function_root[i + 1].1.push((beta * beta, tuples[i].c))
-            }
+    pub fn append_function(&mut self, function_root: H::Hash) {
+        self.function_root.push(MerkleTreeVerifier {
+            merkle_root: function_root,
+            leave_number: self.interpolate_cosets[0].size() / 2,
+        });
+    }
+
+    /// The tuples are checked by `verify`, once `beta` is known.
+    pub fn set_tuples(&mut self, tuples: &Vec<Tuple<T>>) -> Result<(), VerificationError> {
+        let open_point = self
+            .open_point
+            .as_ref()
+            .ok_or(VerificationError::WrongProofShape)?;
+        if tuples.len() != self.total_round
+            || open_point.len() != self.total_round
+            || self.function_root.len() != self.total_round
+        {
+            return Err(VerificationError::WrongProofShape);
         }
+        self.tuples = tuples.clone();
+        Ok(())
     }
 
-    pub fn receive_folding_root(
-        &mut self,
-        leave_number: usize,
-        folding_root: [u8; MERKLE_ROOT_SIZE],
-    ) {
+    pub fn receive_folding_root(&mut self, leave_number: usize, folding_root: H::Hash) {
         self.folding_root.push(MerkleTreeVerifier {
             leave_number,
             merkle_root: folding_root,
         });
     }
 
+    /// Parameters the prover claims to have used, checked by `verify`.
+    pub fn receive_params(&mut self, params: &ProtocolParams) {
+        self.prover_params = Some(*params);
+    }
+
     pub fn set_final_value(&mut self, value: T) {
-        assert_ne!(value, T::from_int(0));
         self.final_value = Some(value);
     }
 
+    pub fn set_pow_nonce(&mut self, nonce: u64) {
+        self.pow_nonce = nonce;
+    }
+
+    fn replay_oracle(&self, final_value: T) -> Result<RandomOracle<T>, VerificationError> {
+        let mut oracle = self.oracle.clone();
+        oracle.absorb_root(&self.function_root[0].merkle_root);
+        oracle.absorb_field_elements(self.open_point.as_ref().unwrap());
+        for function in &self.function_root[1..] {
+            oracle.absorb_root(&function.merkle_root);
+        }
+        oracle.beta_challenge();
+        oracle.absorb_field_elements(&Tuple::flatten(&self.tuples));
+        oracle.rlc_challenge();
+        for i in 0..self.total_round {
+            oracle.folding_challenge(i);
+            if i < self.total_round - 1 {
+                oracle.absorb_root(&self.folding_root[i].merkle_root);
+            }
+        }
+        oracle.absorb_field_elements(&[final_value]);
+        if !oracle.check_proof_of_work(self.pow_nonce) {
+            return Err(VerificationError::InvalidProofOfWork);
+        }
+        oracle.sample_queries();
+        Ok(oracle)
+    }
+
+    /// The evaluations of every function the tuples claim: at `beta` and
+    /// `-beta`, and at `beta^2` for the functions after the first.
+    fn check_tuples(&self, beta: T) -> Result<Vec<Vec<(T, T)>>, VerificationError>
+    where
+        T: OddCharacteristicField,
+    {
+        let open_point = self.open_point.as_ref().unwrap();
+        let mut evaluations = vec![vec![]; self.tuples.len()];
+        for (i, tuple) in self.tuples.iter().enumerate() {
+            if !tuple.verify(beta, open_point[i]) {
+                return Err(VerificationError::FoldingMismatch(i));
+            }
+            evaluations[i].push((beta, tuple.a));
+            evaluations[i].push((-beta, tuple.b));
+            if i < self.tuples.len() - 1 {
+                evaluations[i + 1].push((beta * beta, tuple.c));
+            }
+        }
+        Ok(evaluations)
+    }
+
     pub fn verify(
         &self,
         folding_proofs: &Vec<QueryResult<T>>,
         function_proofs: &Vec<QueryResult<T>>,
-    ) -> bool {
-        let mut leaf_indices = self.oracle.query_list.clone();
-        let rlc = self.oracle.rlc;
+    ) -> Result<(), VerificationError>
+    where
+        T: OddCharacteristicField,
+    {
+        if self.prover_params != Some(self.params) {
+            return Err(VerificationError::ParameterMismatch);
+        }
+        if self.total_round == 0
+            || function_proofs.len() != self.function_root.len()
+            || folding_proofs.len() != self.total_round - 1
+            || self.folding_root.len() != self.total_round - 1
+            || self.tuples.len() != self.total_round
+        {
+            return Err(VerificationError::WrongProofShape);
+        }
+        let final_value = self
+            .final_value
+            .filter(|x| !x.is_zero())
+            .ok_or(VerificationError::WrongProofShape)?;
+        let oracle = self.replay_oracle(final_value)?;
+        let evaluations = self.check_tuples(oracle.beta)?;
+        let mut leaf_indices = oracle.query_list.clone();
+        let rlc = oracle.rlc;
         for i in 0..self.total_round {
             let domain_size = self.interpolate_cosets[i].size();
             leaf_indices = leaf_indices
@@ -106,51 +192,52 @@ impl<T: Field> FriVerifier<T> {
 
             if i == 0 {
                 for j in 0..function_proofs.len() {
-                    assert!(function_proofs[j]
-                        .verify_merkle_tree(&leaf_indices, &self.function_root[j].0));
+                    function_proofs[j].verify_merkle_tree(&leaf_indices, &self.function_root[j])?;
                 }
             } else {
-                folding_proofs[i - 1].verify_merkle_tree(&leaf_indices, &self.folding_root[i - 1]);
+                folding_proofs[i - 1]
+                    .verify_merkle_tree(&leaf_indices, &self.folding_root[i - 1])?;
             }
 
-            let challenge = self.oracle.folding_challenges[i];
-            let get_folding_value = |index: &usize| {
+            let challenge = oracle.folding_challenges[i];
+            let get_folding_value = |index: usize| {
                 if i == 0 {
                     let mut tmp_rlc = T::from_int(1);
-                    let mut res = T::from_int(0); //function_proofs[0].proof_values[index];
+                    let mut res = T::from_int(0);
                     for f in 0..self.function_root.len() {
-                        let this_v = function_proofs[f].proof_values[index];
+                        let this_v = function_proofs[f].value_at(index)?;
                         res += this_v * tmp_rlc;
                         tmp_rlc *= rlc;
-                        for (x, y) in &self.function_root[f].1 {
+                        for (x, y) in &evaluations[f] {
                             res += tmp_rlc
                                 * (this_v - *y)
-                                * (self.interpolate_cosets[0].element_at(*index) - *x).inverse();
+                                * (self.interpolate_cosets[0].compute_element_at(index) - *x)
+                                    .inverse();
                             tmp_rlc *= rlc
                         }
                     }
-                    res
+                    Ok(res)
                 } else {
-                    folding_proofs[i - 1].proof_values[index]
+                    folding_proofs[i - 1].value_at(index)
                 }
             };
 
             for j in &leaf_indices {
-                let x = get_folding_value(j);
-                let nx = get_folding_value(&(j + domain_size / 2));
-                let v =
-                    x + nx + challenge * (x - nx) * self.interpolate_cosets[i].element_inv_at(*j);
-                if i < self.total_round - 1 {
-                    if v != folding_proofs[i].proof_values[j] {
-                        return false;
-                    }
+                let x = get_folding_value(*j)?;
+                let nx = get_folding_value(j + domain_size / 2)?;
+                let v = x
+                    + nx
+                    + challenge * (x - nx) * self.interpolate_cosets[i].compute_element_inv_at(*j);
+                let next_v = if i < self.total_round - 1 {
+                    folding_proofs[i].value_at(*j)?
                 } else {
-                    if v != self.final_value.unwrap() {
-                        return false;
-                    }
+                    final_value
+                };
+                if v != next_v {
+                    return Err(VerificationError::FoldingMismatch(i));
                 }
             }
         }
-        true
+        Ok(())
     }
 }
@@ -5,34 +5,56 @@ use pcs::{prover::One2ManyProver, verifier::One2ManyVerifier};
 use util::{
     algebra::{
         coset::Coset,
-        field::{mersenne61_ext::Mersenne61Ext, Field},
+        field::{
+            babybear::BabyBear,
+            extension::{QuadExt, QuarticExt},
+            fp64::Fp64,
+            koalabear::KoalaBear,
+            mersenne31_ext::Mersenne31Ext,
+            mersenne61_ext::Mersenne61Ext,
+            ExtensionField, Field,
+        },
         polynomial::MultilinearPolynomial,
     },
+    merkle_tree::Blake3Algorithm,
+    params::ProtocolParams,
     random_oracle::RandomOracle,
 };
 
-use util::{CODE_RATE, SECURITY_BITS};
-fn commit(criterion: &mut Criterion, variable_num: usize, terminate_round: usize) {
+/// Polynomials over `F` are committed, and opened at points of `T`.
+fn setup<T: ExtensionField<F>, F: Field>(
+    variable_num: usize,
+    terminate_round: usize,
+) -> (
+    MultilinearPolynomial<F>,
+    ProtocolParams,
+    Vec<Coset<T>>,
+    RandomOracle<T>,
+) {
     let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
-    let mut interpolate_cosets = vec![Coset::new(
-        1 << (variable_num + CODE_RATE),
-        Mersenne61Ext::random_element(),
-    )];
-    for i in 1..variable_num {
-        interpolate_cosets.push(interpolate_cosets[i - 1].pow(2));
-    }
-    let oracle = RandomOracle::new(variable_num, SECURITY_BITS / CODE_RATE);
+    let params = ProtocolParams::new(3, 100, terminate_round, 0, T::FIELD_CHOICE).unwrap();
+    let interpolate_cosets = params
+        .interpolate_cosets(variable_num, T::from_base(F::random_element()))
+        .unwrap();
+    let oracle = RandomOracle::from_params(variable_num, &params);
+    (polynomial, params, interpolate_cosets, oracle)
+}
 
-    criterion.bench_function(&format!("commit {}", variable_num), move |b| {
+fn commit<T: ExtensionField<F>, F: Field>(
+    criterion: &mut Criterion,
+    variable_num: usize,
+    terminate_round: usize,
+) {
+    let (polynomial, params, interpolate_cosets, oracle) =
+        setup::<T, F>(variable_num, terminate_round);
+
+    let name = format!("commit {:?} {}", params.field, variable_num);
+    criterion.bench_function(&name, move |b| {
         b.iter_batched(
             || polynomial.clone(),
             |p| {
-                let prover = One2ManyProver::new(
-                    variable_num - terminate_round,
-                    &interpolate_cosets,
-                    p,
-                    &oracle,
-                );
+                let prover: One2ManyProver<T, Blake3Algorithm, F> =
+                    One2ManyProver::new(&params, &interpolate_cosets, p, &oracle).unwrap();
                 prover.commit_polynomial();
             },
             BatchSize::SmallInput,
@@ -42,37 +64,30 @@ fn commit(criterion: &mut Criterion, variable_num: usize, terminate_round: usize
 
 fn bench_commit(c: &mut Criterion) {
     for i in 5..21 {
-        commit(c, i, 1);
+        commit::<Mersenne61Ext, Mersenne61Ext>(c, i, 1);
+        commit::<QuadExt<Fp64>, Fp64>(c, i, 1);
+        commit::<QuarticExt<BabyBear>, BabyBear>(c, i, 1);
+        commit::<QuarticExt<KoalaBear>, KoalaBear>(c, i, 1);
+        commit::<Mersenne31Ext, Mersenne31Ext>(c, i, 1);
     }
 }
 
-fn open(criterion: &mut Criterion, variable_num: usize, terminate_round: usize) {
-    let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
-    let mut interpolate_cosets = vec![Coset::new(
-        1 << (variable_num + CODE_RATE),
-        Mersenne61Ext::random_element(),
-    )];
-    for i in 1..variable_num {
-        interpolate_cosets.push(interpolate_cosets[i - 1].pow(2));
-    }
-    let oracle = RandomOracle::new(variable_num, SECURITY_BITS / CODE_RATE);
-    let prover = One2ManyProver::new(
-        variable_num - terminate_round,
-        &interpolate_cosets,
-        polynomial,
-        &oracle,
-    );
+fn open<T: ExtensionField<F>, F: Field>(
+    criterion: &mut Criterion,
+    variable_num: usize,
+    terminate_round: usize,
+) {
+    let (polynomial, params, interpolate_cosets, oracle) =
+        setup::<T, F>(variable_num, terminate_round);
+    let prover: One2ManyProver<T, Blake3Algorithm, F> =
+        One2ManyProver::new(&params, &interpolate_cosets, polynomial, &oracle).unwrap();
     let commit = prover.commit_polynomial();
-    let mut verifier = One2ManyVerifier::new(
-        variable_num - terminate_round,
-        variable_num,
-        &interpolate_cosets,
-        commit,
-        &oracle,
-    );
+    let mut verifier =
+        One2ManyVerifier::new(&params, variable_num, &interpolate_cosets, commit, &oracle).unwrap();
     let open_point = verifier.get_open_point();
 
-    criterion.bench_function(&format!("open {}", variable_num), move |b| {
+    let name = format!("open {:?} {}", params.field, variable_num);
+    criterion.bench_function(&name, move |b| {
         b.iter_batched(
             || prover.clone(),
             |mut p| {
@@ -88,50 +103,47 @@ fn open(criterion: &mut Criterion, variable_num: usize, terminate_round: usize)
 
 fn bench_open(c: &mut Criterion) {
     for i in 5..21 {
-        open(c, i, 1);
+        open::<Mersenne61Ext, Mersenne61Ext>(c, i, 1);
+        open::<QuadExt<Fp64>, Fp64>(c, i, 1);
+        open::<QuarticExt<BabyBear>, BabyBear>(c, i, 1);
+        open::<QuarticExt<KoalaBear>, KoalaBear>(c, i, 1);
+        open::<Mersenne31Ext, Mersenne31Ext>(c, i, 1);
     }
 }
 
-fn verify(criterion: &mut Criterion, variable_num: usize, terminate_round: usize) {
-    let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
-    let mut interpolate_cosets = vec![Coset::new(
-        1 << (variable_num + CODE_RATE),
-        Mersenne61Ext::random_element(),
-    )];
-    for i in 1..variable_num {
-        interpolate_cosets.push(interpolate_cosets[i - 1].pow(2));
-    }
-    let oracle = RandomOracle::new(variable_num, SECURITY_BITS / CODE_RATE);
-    let mut prover = One2ManyProver::new(
-        variable_num - terminate_round,
-        &interpolate_cosets,
-        polynomial,
-        &oracle,
-    );
+fn verify<T: ExtensionField<F>, F: Field>(
+    criterion: &mut Criterion,
+    variable_num: usize,
+    terminate_round: usize,
+) {
+    let (polynomial, params, interpolate_cosets, oracle) =
+        setup::<T, F>(variable_num, terminate_round);
+    let mut prover: One2ManyProver<T, Blake3Algorithm, F> =
+        One2ManyProver::new(&params, &interpolate_cosets, polynomial, &oracle).unwrap();
     let commit = prover.commit_polynomial();
-    let mut verifier = One2ManyVerifier::new(
-        variable_num - terminate_round,
-        variable_num,
-        &interpolate_cosets,
-        commit,
-        &oracle,
-    );
+    let mut verifier =
+        One2ManyVerifier::new(&params, variable_num, &interpolate_cosets, commit, &oracle).unwrap();
     let open_point = verifier.get_open_point();
 
     prover.commit_functions(&open_point, &mut verifier);
     prover.prove();
     prover.commit_foldings(&mut verifier);
     let (folding_proof, function_proof) = prover.query();
-    criterion.bench_function(&format!("verify {}", variable_num), move |b| {
+    let name = format!("verify {:?} {}", params.field, variable_num);
+    criterion.bench_function(&name, move |b| {
         b.iter(|| {
-            verifier.verify(&folding_proof, &function_proof);
+            verifier.verify(&folding_proof, &function_proof).unwrap();
         })
     });
 }
 
 fn bench_verify(c: &mut Criterion) {
     for i in 5..21 {
-        verify(c, i, 1);
+        verify::<Mersenne61Ext, Mersenne61Ext>(c, i, 1);
+        verify::<QuadExt<Fp64>, Fp64>(c, i, 1);
+        verify::<QuarticExt<BabyBear>, BabyBear>(c, i, 1);
+        verify::<QuarticExt<KoalaBear>, KoalaBear>(c, i, 1);
+        verify::<Mersenne31Ext, Mersenne31Ext>(c, i, 1);
     }
 }
 
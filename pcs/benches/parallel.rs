@@ -0,0 +1,62 @@
+extern crate criterion;
+use criterion::*;
+
+use pcs::{prover::One2ManyProver, verifier::One2ManyVerifier};
+use util::{
+    algebra::{
+        field::{mersenne61_ext::Mersenne61Ext, Field},
+        polynomial::MultilinearPolynomial,
+    },
+    parallel::rayon::ThreadPoolBuilder,
+    params::{FieldChoice, ProtocolParams},
+    random_oracle::RandomOracle,
+};
+
+fn commit_and_open(
+    params: &ProtocolParams,
+    polynomial: &MultilinearPolynomial<Mersenne61Ext>,
+    oracle: &RandomOracle<Mersenne61Ext>,
+    shift: Mersenne61Ext,
+) {
+    let variable_num = polynomial.variable_num();
+    let interpolate_cosets = params.interpolate_cosets(variable_num, shift).unwrap();
+    let mut prover: One2ManyProver<_> =
+        One2ManyProver::new(params, &interpolate_cosets, polynomial.clone(), oracle).unwrap();
+    let commit = prover.commit_polynomial();
+    let mut verifier =
+        One2ManyVerifier::new(params, variable_num, &interpolate_cosets, commit, oracle).unwrap();
+    let open_point = verifier.get_open_point();
+    prover.commit_functions(&open_point, &mut verifier);
+    prover.prove();
+    prover.commit_foldings(&mut verifier);
+    prover.query();
+}
+
+fn scaling(criterion: &mut Criterion, variable_num: usize) {
+    let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
+    let params = ProtocolParams::new(3, 100, 1, 0, FieldChoice::Mersenne61Ext).unwrap();
+    let oracle = RandomOracle::from_params(variable_num, &params);
+    let shift = Mersenne61Ext::random_element();
+
+    let mut group = criterion.benchmark_group(format!("commit and open {}", variable_num));
+    group.sample_size(10);
+    for threads in [1, 2, 4, 8] {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .unwrap();
+        group.bench_function(format!("{} threads", threads), |b| {
+            b.iter(|| pool.install(|| commit_and_open(&params, &polynomial, &oracle, shift)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_scaling(c: &mut Criterion) {
+    for i in [12, 16, 20] {
+        scaling(c, i);
+    }
+}
+
+criterion_group!(benches, bench_scaling);
+criterion_main!(benches);
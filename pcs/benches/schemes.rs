@@ -0,0 +1,69 @@
+extern crate criterion;
+use criterion::*;
+
+use gemini_fri::GeminiParams;
+use pcs::PcsParams;
+use util::{
+    algebra::{
+        field::{mersenne61_ext::Mersenne61Ext, Field},
+        polynomial::MultilinearPolynomial,
+    },
+    merkle_tree::Blake3Algorithm,
+    multilinear_pcs::MultilinearPCS,
+    params::{FieldChoice, ProtocolParams},
+};
+use virgo::VirgoParams;
+
+fn bench_scheme<S: MultilinearPCS<Mersenne61Ext, Blake3Algorithm>>(
+    criterion: &mut Criterion,
+    name: &str,
+    variable_num: usize,
+    protocol: ProtocolParams,
+) {
+    let params = S::setup(variable_num, protocol).unwrap();
+    let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
+    let point: Vec<_> = (0..variable_num)
+        .map(|_| Mersenne61Ext::random_element())
+        .collect();
+    let value = polynomial.evaluate(&point);
+
+    criterion.bench_function(&format!("{} commit {}", name, variable_num), |b| {
+        b.iter_batched(
+            || polynomial.clone(),
+            |p| params.commit(p).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    let (commitment, prover) = params.commit(polynomial).unwrap();
+    criterion.bench_function(&format!("{} open {}", name, variable_num), |b| {
+        b.iter(|| params.open(&prover, &point))
+    });
+    let proof = params.open(&prover, &point);
+    criterion.bench_function(&format!("{} verify {}", name, variable_num), |b| {
+        b.iter(|| params.verify(&commitment, &point, value, &proof).unwrap())
+    });
+    println!(
+        "{} proof size of {} variables is {} bytes",
+        name,
+        variable_num,
+        S::proof_size(&proof)
+    );
+}
+
+fn bench_schemes(c: &mut Criterion) {
+    // Gemini and Virgo fold down to a constant, without a terminate round.
+    let folded = ProtocolParams::new(3, 100, 0, 0, FieldChoice::Mersenne61Ext).unwrap();
+    for i in 5..21 {
+        bench_scheme::<PcsParams<_>>(c, "polyfrim", i, ProtocolParams::default());
+        bench_scheme::<GeminiParams<_>>(c, "gemini", i, folded);
+        bench_scheme::<VirgoParams<_>>(c, "virgo", i, folded);
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().sample_size(10);
+    targets = bench_schemes
+}
+
+criterion_main!(benches);
@@ -0,0 +1,72 @@
+//! Peak heap usage of `One2ManyVerifier::verify`, measured with a counting
+//! global allocator. The verifier uses its own cosets, so no element table
+//! built by the prover is shared with it.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use pcs::{prover::One2ManyProver, verifier::One2ManyVerifier};
+use util::{
+    algebra::{
+        field::{mersenne61_ext::Mersenne61Ext, Field},
+        polynomial::MultilinearPolynomial,
+    },
+    params::{FieldChoice, ProtocolParams},
+    random_oracle::RandomOracle,
+};
+
+struct Counting;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(current, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn verifier_peak(variable_num: usize) -> usize {
+    let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
+    let params = ProtocolParams::new(3, 100, 1, 0, FieldChoice::Mersenne61Ext).unwrap();
+    let shift = Mersenne61Ext::random_element();
+    let oracle = RandomOracle::from_params(variable_num, &params);
+    let prover_cosets = params.interpolate_cosets(variable_num, shift).unwrap();
+    let mut prover: One2ManyProver<_> =
+        One2ManyProver::new(&params, &prover_cosets, polynomial, &oracle).unwrap();
+    let commit = prover.commit_polynomial();
+    let verifier_cosets = params.interpolate_cosets(variable_num, shift).unwrap();
+    let mut verifier =
+        One2ManyVerifier::new(&params, variable_num, &verifier_cosets, commit, &oracle).unwrap();
+    let open_point = verifier.get_open_point();
+    prover.commit_functions(&open_point, &mut verifier);
+    prover.prove();
+    prover.commit_foldings(&mut verifier);
+    let (folding_proof, function_proof) = prover.query();
+    drop(prover);
+
+    let base = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+    verifier.verify(&folding_proof, &function_proof).unwrap();
+    PEAK.load(Ordering::Relaxed) - base
+}
+
+fn main() {
+    for variable_num in (8..21).step_by(2) {
+        println!(
+            "verifier peak memory of {} variables is {} bytes",
+            variable_num,
+            verifier_peak(variable_num)
+        );
+    }
+}
@@ -1,66 +1,1308 @@
+pub mod multi_point;
+pub mod proof;
 pub mod prover;
 pub mod verifier;
 
+use proof::{MultiPointProof, PcsProof};
+use prover::One2ManyProver;
+use std::marker::PhantomData;
+use util::algebra::{
+    coset::Coset,
+    domain::EvaluationDomain,
+    field::{ExtensionField, Field},
+    polynomial::MultilinearPolynomial,
+};
+use util::merkle_tree::MerkleHasher;
+use util::multilinear_pcs::MultilinearPCS;
+use util::params::{ParamsError, ProtocolParams};
+use util::random_oracle::RandomOracle;
+use util::transcript::Transcript;
+use util::verification_error::VerificationError;
+use verifier::One2ManyVerifier;
+
+const TRANSCRIPT_LABEL: &[u8] = b"PolyFRIM";
+
+/// Public parameters shared by the prover and a standalone verifier. The
+/// evaluation domains are of type `D`, cosets by default.
+#[derive(Clone)]
+pub struct PcsParams<T: Field, D: EvaluationDomain<T> = Coset<T>> {
+    pub variable_num: usize,
+    pub total_round: usize,
+    pub protocol: ProtocolParams,
+    pub interpolate_cosets: Vec<D>,
+    field: PhantomData<T>,
+}
+
+impl<T: Field> PcsParams<T> {
+    pub fn new(
+        variable_num: usize,
+        protocol: ProtocolParams,
+        shift: T,
+    ) -> Result<Self, ParamsError> {
+        Self::over_domains::<T>(variable_num, protocol, shift)
+    }
+}
+
+impl<T: Field, D: EvaluationDomain<T>> PcsParams<T, D> {
+    /// Same as `new` with evaluation domains of type `D`, e.g.
+    /// `AffineSubspace` over a binary field, for polynomials over the
+    /// subfield `F`, in which the first domain must then lie.
+    pub fn over_domains<F: Field>(
+        variable_num: usize,
+        protocol: ProtocolParams,
+        shift: T,
+    ) -> Result<Self, ParamsError>
+    where
+        T: ExtensionField<F>,
+    {
+        let interpolate_cosets: Vec<D> = protocol.interpolate_domains(variable_num, shift)?;
+        if !interpolate_cosets[0].lies_in::<F>() {
+            return Err(ParamsError::DomainNotInBaseField);
+        }
+        Ok(PcsParams {
+            variable_num,
+            total_round: protocol.total_round(variable_num)?,
+            protocol,
+            interpolate_cosets,
+            field: PhantomData,
+        })
+    }
+
+    /// Fiat-Shamir oracle used for non-interactive openings.
+    pub fn oracle(&self) -> RandomOracle<T> {
+        RandomOracle::from_transcript(Transcript::new(TRANSCRIPT_LABEL), self.protocol.query_num)
+            .with_grinding(self.protocol.grinding_bits)
+    }
+}
+
+impl<T: Field, H: MerkleHasher, D: EvaluationDomain<T>> MultilinearPCS<T, H> for PcsParams<T, D> {
+    type Prover = One2ManyProver<T, H, T, D>;
+    type Proof = PcsProof<T, H>;
+
+    fn setup(variable_num: usize, protocol: ProtocolParams) -> Result<Self, ParamsError> {
+        PcsParams::over_domains::<T>(variable_num, protocol, T::random_element())
+    }
+
+    fn commit(
+        &self,
+        polynomial: MultilinearPolynomial<T>,
+    ) -> Result<(H::Hash, Self::Prover), ParamsError> {
+        if polynomial.variable_num() != self.variable_num {
+            return Err(ParamsError::VariableNumMismatch);
+        }
+        let prover = One2ManyProver::new(
+            &self.protocol,
+            &self.interpolate_cosets,
+            polynomial,
+            &self.oracle(),
+        )?;
+        Ok((prover.commit_polynomial(), prover))
+    }
+
+    fn open(&self, prover: &Self::Prover, point: &[T]) -> Self::Proof {
+        prover.open(&point.to_vec())
+    }
+
+    fn verify(
+        &self,
+        commitment: &H::Hash,
+        point: &[T],
+        value: T,
+        proof: &Self::Proof,
+    ) -> Result<(), VerificationError> {
+        verify(self, commitment, point, value, proof)
+    }
+
+    fn proof_size(proof: &Self::Proof) -> usize {
+        proof.proof_size()
+    }
+}
+
+/// Checks that `proof` opens `commitment` to `value` at `point`.
+pub fn verify<T: ExtensionField<F>, H: MerkleHasher, F: Field, D: EvaluationDomain<T>>(
+    params: &PcsParams<T, D>,
+    commitment: &H::Hash,
+    point: &[T],
+    value: T,
+    proof: &PcsProof<T, H, F>,
+) -> Result<(), VerificationError> {
+    let verifier = receive_proof(params, commitment, point, proof)?;
+    verifier.verify(&proof.folding_proofs, &proof.all_function_proofs())?;
+    // In hiding mode the masking variables are fixed to zero.
+    let mut rest = point[params.total_round.min(point.len())..].to_vec();
+    rest.resize(proof.evaluation.variable_num(), T::from_int(0));
+    if proof.evaluation.evaluate(&rest) != value {
+        return Err(VerificationError::EvaluationMismatch);
+    }
+    Ok(())
+}
+
+/// Checks that `proof` opens a batch commitment made with
+/// `One2ManyProver::new_batch` to `values` at `point`.
+pub fn verify_batch<T: ExtensionField<F>, H: MerkleHasher, F: Field, D: EvaluationDomain<T>>(
+    params: &PcsParams<T, D>,
+    commitment: &H::Hash,
+    point: &[T],
+    values: &[T],
+    proof: &PcsProof<T, H, F>,
+) -> Result<(), VerificationError> {
+    if values.is_empty() {
+        return Err(VerificationError::WrongProofShape);
+    }
+    let mut verifier = receive_proof(params, commitment, point, proof)?;
+    verifier.set_batch_evaluations(values.to_vec());
+    verifier.verify(&proof.folding_proofs, &proof.all_function_proofs())
+}
+
+/// Checks that `proof` opens `commitment` to `values[k]` at `points[k]`
+/// for every `k`. Not available in hiding mode, nor over the 31-bit fields
+/// without an extension, see `multi_point`.
+pub fn verify_multi<T: ExtensionField<F>, H: MerkleHasher, F: Field, D: EvaluationDomain<T>>(
+    params: &PcsParams<T, D>,
+    commitment: &H::Hash,
+    points: &[Vec<T>],
+    values: &[T],
+    proof: &MultiPointProof<T, H, F>,
+) -> Result<(), VerificationError> {
+    if params.protocol.hiding
+        || multi_point::check_field::<T>().is_err()
+        || points.is_empty()
+        || points.len() != values.len()
+        || points.iter().any(|x| x.len() != params.variable_num)
+        || proof.sumcheck.rounds.len() != params.variable_num
+    {
+        return Err(VerificationError::WrongProofShape);
+    }
+    let point = multi_point::verify(commitment, points, values, &proof.sumcheck, proof.value)?;
+    verify(params, commitment, &point, proof.value, &proof.opening)
+}
+
+fn receive_proof<T: ExtensionField<F>, H: MerkleHasher, F: Field, D: EvaluationDomain<T>>(
+    params: &PcsParams<T, D>,
+    commitment: &H::Hash,
+    point: &[T],
+    proof: &PcsProof<T, H, F>,
+) -> Result<One2ManyVerifier<T, H, F, D>, VerificationError> {
+    if proof.params != params.protocol {
+        return Err(VerificationError::ParameterMismatch);
+    }
+    let rounds = params.protocol.folding_rounds(params.total_round);
+    let function_root_num = if params.protocol.shared_trees {
+        0
+    } else {
+        rounds.len() - 1
+    };
+    if point.len() != params.variable_num
+        || proof.function_roots.len() != function_root_num
+        || proof.folding_roots.len() + 1 != rounds.len()
+    {
+        return Err(VerificationError::WrongProofShape);
+    }
+    let cosets = &params.interpolate_cosets;
+    let mut verifier = One2ManyVerifier::<T, H, F, D>::new_with_open_point(
+        &params.protocol,
+        params.variable_num,
+        cosets,
+        *commitment,
+        point.to_vec(),
+        &params.oracle(),
+    )
+    .map_err(|_| VerificationError::ParameterMismatch)?;
+    verifier.receive_params(&proof.params);
+    let leave_number = |i: usize| cosets[rounds[i].start].size() >> rounds[i].len();
+    for (i, root) in proof.function_roots.iter().enumerate() {
+        verifier.set_function(leave_number(i + 1), root);
+    }
+    verifier.set_evaluation(proof.evaluation.clone());
+    for (i, root) in proof.folding_roots.iter().enumerate() {
+        verifier.receive_folding_root(leave_number(i + 1), *root);
+    }
+    verifier.set_final_value(&proof.final_value);
+    verifier.set_pow_nonce(proof.pow_nonce);
+    Ok(verifier)
+}
+
 #[cfg(test)]
 mod tests {
     use std::mem::size_of;
 
-    use crate::{prover::One2ManyProver, verifier::One2ManyVerifier};
+    use crate::{
+        proof::{MultiPointProof, PcsProof},
+        prover::One2ManyProver,
+        verifier::One2ManyVerifier,
+        verify, verify_batch, verify_multi, PcsParams,
+    };
     use util::{
         algebra::{
-            coset::Coset,
-            field::{mersenne61_ext::Mersenne61Ext, Field},
-            polynomial::MultilinearPolynomial,
+            field::{
+                babybear::BabyBear, binary_tower::BinaryTower128, extension::QuadExt, fp64::Fp64,
+                mersenne61::Mersenne61, mersenne61_ext::Mersenne61Ext, ExtensionField, Field,
+            },
+            polynomial::{MultilinearPolynomial, Polynomial},
+            subspace::AffineSubspace,
         },
-        merkle_tree::MERKLE_ROOT_SIZE,
+        codec::DecodeError,
+        merkle_tree::{
+            Blake3Algorithm, Keccak256Algorithm, MerkleHasher, Sha256Algorithm, MERKLE_ROOT_SIZE,
+        },
+        multilinear_pcs::MultilinearPCS,
+        params::{FieldChoice, ParamsError, ProtocolParams},
+        poseidon::PoseidonAlgorithm,
         random_oracle::RandomOracle,
+        sumcheck::SumcheckProof,
+        transcript::Transcript,
+        verification_error::VerificationError,
     };
-    use util::{CODE_RATE, SECURITY_BITS};
 
-    fn output_proof_size(variable_num: usize, terminate_round: usize) -> usize {
+    fn protocol_params(terminate_round: usize, grinding_bits: usize) -> ProtocolParams {
+        ProtocolParams::new(
+            3,
+            100,
+            terminate_round,
+            grinding_bits,
+            FieldChoice::Mersenne61Ext,
+        )
+        .unwrap()
+    }
+
+    /// Hiding parameters at a lower security, so that 4 masking variables
+    /// cover the values every round opens.
+    fn hiding_params(field: FieldChoice) -> ProtocolParams {
+        ProtocolParams::new(3, 21, 4, 0, field)
+            .unwrap()
+            .with_hiding(true)
+            .unwrap()
+    }
+
+    fn output_proof_size(variable_num: usize, params: ProtocolParams) -> usize {
         let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
-        let mut interpolate_cosets = vec![Coset::new(
-            1 << (variable_num + CODE_RATE),
-            Mersenne61Ext::random_element(),
-        )];
-        for i in 1..variable_num {
-            interpolate_cosets.push(interpolate_cosets[i - 1].pow(2));
-        }
-        let oracle = RandomOracle::new(variable_num, SECURITY_BITS / CODE_RATE);
-        let mut prover = One2ManyProver::new(
-            variable_num - terminate_round,
-            &interpolate_cosets,
-            polynomial,
-            &oracle,
-        );
+        let interpolate_cosets = params
+            .interpolate_cosets(variable_num, Mersenne61Ext::random_element())
+            .unwrap();
+        let oracle = RandomOracle::from_params(variable_num, &params).with_query_seed(0);
+        let mut prover: One2ManyProver<_> =
+            One2ManyProver::new(&params, &interpolate_cosets, polynomial, &oracle).unwrap();
         let commit = prover.commit_polynomial();
+        let mut verifier =
+            One2ManyVerifier::new(&params, variable_num, &interpolate_cosets, commit, &oracle)
+                .unwrap();
+        let open_point = verifier.get_open_point();
+
+        prover.commit_functions(&open_point, &mut verifier);
+        prover.prove();
+        prover.commit_foldings(&mut verifier);
+        let (folding_proof, function_proof) = prover.query();
+        verifier.verify(&folding_proof, &function_proof).unwrap();
+        let trees_per_round = if params.shared_trees { 1 } else { 2 };
+        folding_proof.iter().map(|x| x.proof_size()).sum::<usize>()
+            + function_proof.iter().map(|x| x.proof_size()).sum::<usize>()
+            + params
+                .folding_rounds(params.total_round(variable_num).unwrap())
+                .len()
+                * MERKLE_ROOT_SIZE
+                * trees_per_round
+            + ((1 << params.terminate_round) + 1) * size_of::<Mersenne61Ext>() * 2
+    }
+
+    #[test]
+    fn non_interactive() {
+        let variable_num = 10;
+        let params = protocol_params(1, 0);
+        let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
+        let interpolate_cosets = params
+            .interpolate_cosets(variable_num, Mersenne61Ext::random_element())
+            .unwrap();
+        let prover_oracle =
+            RandomOracle::from_transcript(Transcript::new(b"pcs"), params.query_num);
+        let mut prover: One2ManyProver<_> =
+            One2ManyProver::new(&params, &interpolate_cosets, polynomial, &prover_oracle).unwrap();
+        let commit = prover.commit_polynomial();
+        let verifier_oracle =
+            RandomOracle::from_transcript(Transcript::new(b"pcs"), params.query_num);
         let mut verifier = One2ManyVerifier::new(
-            variable_num - terminate_round,
+            &params,
             variable_num,
             &interpolate_cosets,
             commit,
-            &oracle,
-        );
+            &verifier_oracle,
+        )
+        .unwrap();
         let open_point = verifier.get_open_point();
 
         prover.commit_functions(&open_point, &mut verifier);
         prover.prove();
         prover.commit_foldings(&mut verifier);
         let (folding_proof, function_proof) = prover.query();
-        verifier.verify(&folding_proof, &function_proof);
-        folding_proof.iter().map(|x| x.proof_size()).sum::<usize>()
-            + function_proof.iter().map(|x| x.proof_size()).sum::<usize>()
-            + (variable_num - terminate_round) * MERKLE_ROOT_SIZE * 2
-            + ((1 << terminate_round) + 1) * size_of::<Mersenne61Ext>() * 2
+        assert_eq!(verifier.verify(&folding_proof, &function_proof), Ok(()));
+    }
+
+    #[test]
+    fn standalone_verify() {
+        let variable_num = 10;
+        let params = PcsParams::new(
+            variable_num,
+            protocol_params(1, 0),
+            Mersenne61Ext::random_element(),
+        )
+        .unwrap();
+        let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
+        let point = (0..variable_num)
+            .map(|_| Mersenne61Ext::random_element())
+            .collect();
+        let value = polynomial.evaluate(&point);
+        let prover: One2ManyProver<_> = One2ManyProver::new(
+            &params.protocol,
+            &params.interpolate_cosets,
+            polynomial,
+            &params.oracle(),
+        )
+        .unwrap();
+        let commitment = prover.commit_polynomial();
+        let proof = prover.open(&point);
+
+        let bytes = proof.to_bytes();
+        let decoded: PcsProof<_> = PcsProof::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+        assert_eq!(
+            verify(&params, &commitment, &point, value, &decoded),
+            Ok(())
+        );
+        assert_eq!(
+            verify(
+                &params,
+                &commitment,
+                &point,
+                value + Mersenne61Ext::from_int(1),
+                &decoded
+            ),
+            Err(VerificationError::EvaluationMismatch)
+        );
+
+        let mut tampered = proof.clone();
+        tampered.function_proofs.pop();
+        assert_eq!(
+            verify(&params, &commitment, &point, value, &tampered),
+            Err(VerificationError::WrongProofShape)
+        );
+        let mut tampered = proof.clone();
+        let (index, v) = tampered
+            .committed_proof
+            .proof_values
+            .iter_mut()
+            .next()
+            .unwrap();
+        let index = *index;
+        *v += Mersenne61Ext::from_int(1);
+        assert_eq!(
+            verify(&params, &commitment, &point, value, &tampered),
+            Err(VerificationError::BadMerklePath)
+        );
+        let mut tampered = proof.clone();
+        tampered.committed_proof.proof_values.remove(&index);
+        assert_eq!(
+            verify(&params, &commitment, &point, value, &tampered),
+            Err(VerificationError::MissingQueryValue(index))
+        );
+        assert!(PcsProof::<Mersenne61Ext>::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        let mut bad_version = bytes.clone();
+        bad_version[4] += 1;
+        assert_eq!(
+            PcsProof::<Mersenne61Ext>::from_bytes(&bad_version).err(),
+            Some(DecodeError::UnsupportedVersion(8))
+        );
+
+        let other = PcsParams {
+            protocol: protocol_params(1, 8),
+            ..params.clone()
+        };
+        assert_eq!(
+            verify(&other, &commitment, &point, value, &proof),
+            Err(VerificationError::ParameterMismatch)
+        );
+        let mut tampered = proof.clone();
+        tampered.params.query_num -= 1;
+        assert_eq!(
+            PcsProof::<Mersenne61Ext>::from_bytes(&tampered.to_bytes()).err(),
+            Some(DecodeError::InvalidParams)
+        );
+    }
+
+    #[test]
+    fn zero_polynomial() {
+        let variable_num = 8;
+        let params = PcsParams::new(
+            variable_num,
+            protocol_params(2, 0),
+            Mersenne61Ext::random_element(),
+        )
+        .unwrap();
+        let point: Vec<_> = (0..variable_num)
+            .map(|_| Mersenne61Ext::random_element())
+            .collect();
+        let mut constant = vec![Mersenne61Ext::ZERO; 1 << variable_num];
+        constant[0] = Mersenne61Ext::random_element();
+        for coefficients in [vec![Mersenne61Ext::ZERO; 1 << variable_num], constant] {
+            let polynomial = MultilinearPolynomial::new(coefficients);
+            let value = polynomial.evaluate(&point);
+            let prover: One2ManyProver<_> = One2ManyProver::new(
+                &params.protocol,
+                &params.interpolate_cosets,
+                polynomial,
+                &params.oracle(),
+            )
+            .unwrap();
+            let commitment = prover.commit_polynomial();
+            let proof = prover.open(&point);
+            assert!(proof.final_value.coefficients().len() <= 1);
+
+            let bytes = proof.to_bytes();
+            let decoded: PcsProof<_> = PcsProof::from_bytes(&bytes).unwrap();
+            assert_eq!(decoded.to_bytes(), bytes);
+            assert_eq!(
+                verify(&params, &commitment, &point, value, &decoded),
+                Ok(())
+            );
+        }
+
+        // A final polynomial above the degree bound does not decode.
+        let prover: One2ManyProver<_> = One2ManyProver::new(
+            &params.protocol,
+            &params.interpolate_cosets,
+            MultilinearPolynomial::random_polynomial(variable_num),
+            &params.oracle(),
+        )
+        .unwrap();
+        prover.commit_polynomial();
+        let mut tampered = prover.open(&point);
+        tampered.final_value = Polynomial::new(vec![Mersenne61Ext::from_int(1); 5]);
+        assert_eq!(
+            PcsProof::<Mersenne61Ext>::from_bytes(&tampered.to_bytes()).err(),
+            Some(DecodeError::InvalidLength)
+        );
+    }
+
+    fn open_with_hasher<H: MerkleHasher>() {
+        let variable_num = 8;
+        let params = PcsParams::new(
+            variable_num,
+            protocol_params(1, 0),
+            Mersenne61Ext::random_element(),
+        )
+        .unwrap();
+        let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
+        let point = (0..variable_num)
+            .map(|_| Mersenne61Ext::random_element())
+            .collect();
+        let value = polynomial.evaluate(&point);
+        let prover = One2ManyProver::<_, H>::new(
+            &params.protocol,
+            &params.interpolate_cosets,
+            polynomial,
+            &params.oracle(),
+        )
+        .unwrap();
+        let commitment = prover.commit_polynomial();
+        let proof = prover.open(&point);
+        let decoded = PcsProof::<_, H>::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(
+            verify(&params, &commitment, &point, value, &decoded),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn hiding() {
+        let variable_num = 10;
+        let protocol = hiding_params(FieldChoice::Mersenne61Ext);
+        let params =
+            PcsParams::new(variable_num, protocol, Mersenne61Ext::random_element()).unwrap();
+        let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
+        let point: Vec<_> = (0..variable_num)
+            .map(|_| Mersenne61Ext::random_element())
+            .collect();
+        let value = polynomial.evaluate(&point);
+        let new_prover = || -> One2ManyProver<_> {
+            One2ManyProver::new(
+                &params.protocol,
+                &params.interpolate_cosets,
+                polynomial.clone(),
+                &params.oracle(),
+            )
+            .unwrap()
+        };
+        let prover = new_prover();
+        let commitment = prover.commit_polynomial();
+        assert_ne!(commitment, new_prover().commit_polynomial());
+        let proof = prover.open(&point);
+        assert!(!proof.committed_proof.salts.is_empty());
+        // Every round opens fewer values than the mask of its oracles has
+        // coefficients, so they say nothing of the partial evaluations.
+        let rounds = protocol.folding_rounds(params.total_round);
+        let committed_variable_num = protocol.committed_variable_num(variable_num);
+        for (i, round) in rounds.iter().enumerate() {
+            let opened = if i == 0 {
+                proof.committed_proof.proof_values.len()
+            } else {
+                proof.function_proofs[i - 1].proof_values.len()
+                    + proof.folding_proofs[i - 1].proof_values.len()
+            };
+            assert!(opened <= protocol.revealed_values(variable_num, i, round));
+            assert!(opened <= protocol.mask_size(variable_num, round));
+            assert!(opened < 1 << (committed_variable_num - round.start));
+        }
+        let decoded: PcsProof<_> = PcsProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(
+            verify(&params, &commitment, &point, value, &decoded),
+            Ok(())
+        );
+        assert_eq!(
+            verify(
+                &params,
+                &commitment,
+                &point,
+                value + Mersenne61Ext::from_int(1),
+                &proof
+            ),
+            Err(VerificationError::EvaluationMismatch)
+        );
+        let mut tampered = proof.clone();
+        tampered.committed_proof.salts[0][0] ^= 1;
+        assert_eq!(
+            verify(&params, &commitment, &point, value, &tampered),
+            Err(VerificationError::BadMerklePath)
+        );
+        let mut unsalted = proof.clone();
+        unsalted.committed_proof.salts.clear();
+        assert_eq!(
+            verify(&params, &commitment, &point, value, &unsalted),
+            Err(VerificationError::WrongProofShape)
+        );
+        let mut unsalted = proof.clone();
+        unsalted.folding_proofs[0].salts.clear();
+        assert_eq!(
+            verify(&params, &commitment, &point, value, &unsalted),
+            Err(VerificationError::WrongProofShape)
+        );
+        let plain = PcsParams {
+            protocol: protocol_params(1, 0),
+            ..params.clone()
+        };
+        assert_eq!(
+            verify(&plain, &commitment, &point, value, &proof),
+            Err(VerificationError::ParameterMismatch)
+        );
+
+        let polynomials = vec![polynomial.clone(); 3];
+        let prover: One2ManyProver<_> = One2ManyProver::new_batch(
+            &params.protocol,
+            &params.interpolate_cosets,
+            polynomials,
+            &params.oracle(),
+        )
+        .unwrap();
+        let commitment = prover.commit_polynomial();
+        let proof = prover.open(&point);
+        assert_eq!(
+            verify_batch(&params, &commitment, &point, &[value; 3], &proof),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn reuse_commitment() {
+        let variable_num = 10;
+        let params = PcsParams::new(
+            variable_num,
+            protocol_params(1, 0),
+            Mersenne61Ext::random_element(),
+        )
+        .unwrap();
+        let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
+        let prover: One2ManyProver<_> = One2ManyProver::new(
+            &params.protocol,
+            &params.interpolate_cosets,
+            polynomial.clone(),
+            &params.oracle(),
+        )
+        .unwrap();
+        let commitment = prover.commit_polynomial();
+        for _ in 0..3 {
+            let point: Vec<_> = (0..variable_num)
+                .map(|_| Mersenne61Ext::random_element())
+                .collect();
+            let proof = prover.open(&point);
+            assert_eq!(
+                verify(
+                    &params,
+                    &commitment,
+                    &point,
+                    polynomial.evaluate(&point),
+                    &proof
+                ),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn shared_trees() {
+        let variable_num = 12;
+        let separate = output_proof_size(variable_num, protocol_params(1, 0));
+        let shared = output_proof_size(
+            variable_num,
+            protocol_params(1, 0).with_shared_trees(true).unwrap(),
+        );
+        assert!(shared * 4 < separate * 3);
+
+        let protocol = hiding_params(FieldChoice::Mersenne61Ext)
+            .with_shared_trees(true)
+            .unwrap();
+        let params =
+            PcsParams::new(variable_num, protocol, Mersenne61Ext::random_element()).unwrap();
+        let polynomials: Vec<_> = (0..3)
+            .map(|_| MultilinearPolynomial::random_polynomial(variable_num))
+            .collect();
+        let point: Vec<_> = (0..variable_num)
+            .map(|_| Mersenne61Ext::random_element())
+            .collect();
+        let values: Vec<_> = polynomials.iter().map(|x| x.evaluate(&point)).collect();
+        let prover: One2ManyProver<_> = One2ManyProver::new_batch(
+            &params.protocol,
+            &params.interpolate_cosets,
+            polynomials,
+            &params.oracle(),
+        )
+        .unwrap();
+        let commitment = prover.commit_polynomial();
+        let proof = prover.open(&point);
+        assert!(proof.function_roots.is_empty());
+        assert!(proof.function_proofs.is_empty());
+        let decoded: PcsProof<_> = PcsProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(
+            verify_batch(&params, &commitment, &point, &values, &decoded),
+            Ok(())
+        );
+
+        // The function half of a shared leaf is bound by the same path.
+        let mut tampered = proof.clone();
+        let domain_size = params.interpolate_cosets[1].size();
+        let v = tampered.folding_proofs[0]
+            .proof_values
+            .iter_mut()
+            .find(|(index, _)| **index >= domain_size)
+            .unwrap()
+            .1;
+        *v += Mersenne61Ext::from_int(1);
+        assert_ne!(
+            verify_batch(&params, &commitment, &point, &values, &tampered),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn folding_arity() {
+        let variable_num = 12;
+        let binary = output_proof_size(variable_num, protocol_params(1, 0));
+        let quaternary = output_proof_size(
+            variable_num,
+            protocol_params(1, 0).with_folding_arity(4).unwrap(),
+        );
+        assert!(quaternary < binary);
+
+        // 10 folding steps make rounds of 3, 3, 3 and 1 steps with arity 8.
+        for shared_trees in [false, true] {
+            let protocol = hiding_params(FieldChoice::Mersenne61Ext)
+                .with_shared_trees(shared_trees)
+                .unwrap()
+                .with_folding_arity(8)
+                .unwrap();
+            let params =
+                PcsParams::new(variable_num - 2, protocol, Mersenne61Ext::random_element())
+                    .unwrap();
+            let polynomials: Vec<_> = (0..2)
+                .map(|_| MultilinearPolynomial::random_polynomial(params.variable_num))
+                .collect();
+            let point: Vec<_> = (0..params.variable_num)
+                .map(|_| Mersenne61Ext::random_element())
+                .collect();
+            let values: Vec<_> = polynomials.iter().map(|x| x.evaluate(&point)).collect();
+            let prover: One2ManyProver<_> = One2ManyProver::new_batch(
+                &params.protocol,
+                &params.interpolate_cosets,
+                polynomials,
+                &params.oracle(),
+            )
+            .unwrap();
+            let commitment = prover.commit_polynomial();
+            let proof = prover.open(&point);
+            assert_eq!(proof.folding_roots.len(), 3);
+            let decoded: PcsProof<_> = PcsProof::from_bytes(&proof.to_bytes()).unwrap();
+            assert_eq!(
+                verify_batch(&params, &commitment, &point, &values, &decoded),
+                Ok(())
+            );
+
+            let mut tampered = proof.clone();
+            let v = tampered.folding_proofs[2]
+                .proof_values
+                .values_mut()
+                .next()
+                .unwrap();
+            *v += Mersenne61Ext::from_int(1);
+            assert_ne!(
+                verify_batch(&params, &commitment, &point, &values, &tampered),
+                Ok(())
+            );
+        }
+
+        for arity in [2, 4, 16] {
+            let protocol = protocol_params(1, 0).with_folding_arity(arity).unwrap();
+            let params =
+                PcsParams::new(variable_num, protocol, Mersenne61Ext::random_element()).unwrap();
+            let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
+            let point: Vec<_> = (0..variable_num)
+                .map(|_| Mersenne61Ext::random_element())
+                .collect();
+            let value = polynomial.evaluate(&point);
+            let prover: One2ManyProver<_> = One2ManyProver::new(
+                &params.protocol,
+                &params.interpolate_cosets,
+                polynomial,
+                &params.oracle(),
+            )
+            .unwrap();
+            let proof = prover.open(&point);
+            assert_eq!(
+                verify(&params, &prover.commit_polynomial(), &point, value, &proof),
+                Ok(())
+            );
+        }
+    }
+
+    #[test]
+    fn open_on_worker() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<One2ManyProver<Mersenne61Ext>>();
+        assert_send_sync::<One2ManyVerifier<Mersenne61Ext>>();
+        assert_send_sync::<PcsParams<Mersenne61Ext>>();
+
+        let variable_num = 10;
+        let params = PcsParams::new(
+            variable_num,
+            protocol_params(1, 0),
+            Mersenne61Ext::random_element(),
+        )
+        .unwrap();
+        let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
+        let prover: One2ManyProver<_> = One2ManyProver::new(
+            &params.protocol,
+            &params.interpolate_cosets,
+            polynomial.clone(),
+            &params.oracle(),
+        )
+        .unwrap();
+        let commitment = prover.commit_polynomial();
+        let point: Vec<_> = (0..variable_num)
+            .map(|_| Mersenne61Ext::random_element())
+            .collect();
+        let worker_point = point.clone();
+        let proof = std::thread::spawn(move || prover.open(&worker_point))
+            .join()
+            .unwrap();
+        assert_eq!(
+            verify(
+                &params,
+                &commitment,
+                &point,
+                polynomial.evaluate(&point),
+                &proof
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn multi_point_opening() {
+        let variable_num = 10;
+        let params = PcsParams::new(
+            variable_num,
+            protocol_params(1, 0),
+            Mersenne61Ext::random_element(),
+        )
+        .unwrap();
+        let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
+        let prover: One2ManyProver<_> = One2ManyProver::new(
+            &params.protocol,
+            &params.interpolate_cosets,
+            polynomial.clone(),
+            &params.oracle(),
+        )
+        .unwrap();
+        let commitment = prover.commit_polynomial();
+        let points: Vec<Vec<_>> = (0..5)
+            .map(|_| {
+                (0..variable_num)
+                    .map(|_| Mersenne61Ext::random_element())
+                    .collect()
+            })
+            .collect();
+        let values: Vec<_> = points.iter().map(|x| polynomial.evaluate(x)).collect();
+        let proof = prover.open_multi(&points).unwrap();
+        let decoded: MultiPointProof<_> = MultiPointProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(
+            verify_multi(&params, &commitment, &points, &values, &decoded),
+            Ok(())
+        );
+        // One folding proof regardless of the number of points.
+        assert!(proof.proof_size() < prover.open(&points[0]).proof_size() * 2);
+
+        let mut wrong = values.clone();
+        wrong[2] += Mersenne61Ext::from_int(1);
+        assert_eq!(
+            verify_multi(&params, &commitment, &points, &wrong, &proof),
+            Err(VerificationError::EvaluationMismatch)
+        );
+        let mut tampered = proof.clone();
+        tampered.value += Mersenne61Ext::from_int(1);
+        assert_eq!(
+            verify_multi(&params, &commitment, &points, &values, &tampered),
+            Err(VerificationError::EvaluationMismatch)
+        );
+        let mut tampered = proof.clone();
+        tampered.sumcheck.rounds.pop();
+        assert_eq!(
+            verify_multi(&params, &commitment, &points, &values, &tampered),
+            Err(VerificationError::WrongProofShape)
+        );
+    }
+
+    #[test]
+    fn multi_point_small_field() {
+        let variable_num = 6;
+        let protocol = ProtocolParams::new(3, 100, 1, 0, FieldChoice::BabyBear).unwrap();
+        let params = PcsParams::new(variable_num, protocol, BabyBear::random_element()).unwrap();
+        let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
+        let prover: One2ManyProver<_> = One2ManyProver::new(
+            &params.protocol,
+            &params.interpolate_cosets,
+            polynomial.clone(),
+            &params.oracle(),
+        )
+        .unwrap();
+        let points = vec![(0..variable_num)
+            .map(|_| BabyBear::random_element())
+            .collect::<Vec<_>>()];
+        assert_eq!(
+            prover.open_multi(&points).err(),
+            Some(ParamsError::FieldTooSmall)
+        );
+        // A proof is not accepted either, whatever it contains.
+        let proof = MultiPointProof {
+            sumcheck: SumcheckProof {
+                rounds: vec![vec![BabyBear::ZERO; 3]; variable_num],
+            },
+            value: polynomial.evaluate(&points[0]),
+            opening: prover.open(&points[0]),
+        };
+        let values = vec![proof.value];
+        assert_eq!(
+            verify_multi(
+                &params,
+                &prover.commit_polynomial(),
+                &points,
+                &values,
+                &proof
+            ),
+            Err(VerificationError::WrongProofShape)
+        );
+    }
+
+    #[test]
+    fn pluggable_hasher() {
+        open_with_hasher::<Sha256Algorithm>();
+        open_with_hasher::<Keccak256Algorithm>();
+        open_with_hasher::<PoseidonAlgorithm<Mersenne61Ext>>();
+    }
+
+    #[test]
+    fn batch_opening() {
+        let variable_num = 10;
+        let batch_size = 16;
+        let params = PcsParams::new(
+            variable_num,
+            protocol_params(1, 0),
+            Mersenne61Ext::random_element(),
+        )
+        .unwrap();
+        let polynomials: Vec<_> = (0..batch_size)
+            .map(|_| MultilinearPolynomial::random_polynomial(variable_num))
+            .collect();
+        let point: Vec<_> = (0..variable_num)
+            .map(|_| Mersenne61Ext::random_element())
+            .collect();
+        let values: Vec<_> = polynomials.iter().map(|x| x.evaluate(&point)).collect();
+        let prover: One2ManyProver<_> = One2ManyProver::new_batch(
+            &params.protocol,
+            &params.interpolate_cosets,
+            polynomials.clone(),
+            &params.oracle(),
+        )
+        .unwrap();
+        let commitment = prover.commit_polynomial();
+        let proof = prover.open(&point);
+        let decoded: PcsProof<_> = PcsProof::from_bytes(&proof.to_bytes()).unwrap();
+        assert_eq!(
+            verify_batch(&params, &commitment, &point, &values, &decoded),
+            Ok(())
+        );
+
+        let mut wrong = values.clone();
+        wrong[batch_size - 1] += Mersenne61Ext::from_int(1);
+        assert_eq!(
+            verify_batch(&params, &commitment, &point, &wrong, &proof),
+            Err(VerificationError::EvaluationMismatch)
+        );
+        assert_eq!(
+            verify_batch(&params, &commitment, &point, &values[1..], &proof),
+            Err(VerificationError::EvaluationMismatch)
+        );
+        let mut tampered = proof.clone();
+        let index = *tampered.committed_proof.proof_values.keys().max().unwrap();
+        tampered.committed_proof.proof_values.remove(&index);
+        assert_eq!(
+            verify_batch(&params, &commitment, &point, &values, &tampered),
+            Err(VerificationError::MissingQueryValue(index))
+        );
+
+        // Only the leaves of the first tree grow with the batch size.
+        let prover: One2ManyProver<_> = One2ManyProver::new(
+            &params.protocol,
+            &params.interpolate_cosets,
+            polynomials[0].clone(),
+            &params.oracle(),
+        )
+        .unwrap();
+        prover.commit_polynomial();
+        let single = prover.open(&point);
+        let opened = proof.committed_proof.proof_values.len() / batch_size;
+        let extra = (batch_size - 1) * opened * (8 + size_of::<Mersenne61Ext>());
+        assert!(proof.proof_size() < single.proof_size() + extra + extra / 2);
+        assert!(proof.proof_size() < 2 * single.proof_size());
     }
 
     #[test]
     fn test_proof_size() {
         for i in 5..21 {
-            let proof_size = output_proof_size(i, 1);
-            println!(
-                "frolling pcs proof size of {} variables is {} bytes",
-                i, proof_size
+            for arity in [2, 4, 8, 16] {
+                for shared_trees in [false, true] {
+                    let params = protocol_params(1, 0)
+                        .with_shared_trees(shared_trees)
+                        .unwrap()
+                        .with_folding_arity(arity)
+                        .unwrap();
+                    let proof_size = output_proof_size(i, params);
+                    println!(
+                        "frolling pcs proof size of {} variables with arity {} and {} trees is {} bytes",
+                        i,
+                        arity,
+                        if shared_trees { "shared" } else { "separate" },
+                        proof_size
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn grinding() {
+        let grinding_bits = 12;
+        assert!(
+            output_proof_size(10, protocol_params(1, grinding_bits))
+                < output_proof_size(10, protocol_params(1, 0))
+        );
+
+        let variable_num = 10;
+        let params = PcsParams::new(
+            variable_num,
+            protocol_params(1, grinding_bits),
+            Mersenne61Ext::random_element(),
+        )
+        .unwrap();
+        // ceil((100 - 12) / 3) queries instead of ceil(100 / 3).
+        assert_eq!(params.oracle().query_num(), 30);
+        let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
+        let point = (0..variable_num)
+            .map(|_| Mersenne61Ext::random_element())
+            .collect();
+        let value = polynomial.evaluate(&point);
+        let prover: One2ManyProver<_> = One2ManyProver::new(
+            &params.protocol,
+            &params.interpolate_cosets,
+            polynomial,
+            &params.oracle(),
+        )
+        .unwrap();
+        let commitment = prover.commit_polynomial();
+        let proof = prover.open(&point);
+        assert_eq!(verify(&params, &commitment, &point, value, &proof), Ok(()));
+        // The prover returns the smallest valid nonce, so any smaller one fails.
+        if proof.pow_nonce > 0 {
+            let mut tampered = proof.clone();
+            tampered.pow_nonce -= 1;
+            assert_eq!(
+                verify(&params, &commitment, &point, value, &tampered),
+                Err(VerificationError::InvalidProofOfWork)
+            );
+        }
+    }
+
+    #[test]
+    fn base_field_commitment() {
+        type T = QuadExt<Fp64>;
+        let variable_num = 10;
+        let protocol = ProtocolParams::new(3, 100, 1, 0, FieldChoice::Fp64Quad).unwrap();
+        for protocol in [protocol, protocol.with_shared_trees(true).unwrap()] {
+            let params =
+                PcsParams::new(variable_num, protocol, T::from_base(Fp64::random_element()))
+                    .unwrap();
+            let polynomial = MultilinearPolynomial::<Fp64>::random_polynomial(variable_num);
+            let point: Vec<_> = (0..variable_num).map(|_| T::random_element()).collect();
+            let value = MultilinearPolynomial::new(
+                polynomial
+                    .coefficients()
+                    .iter()
+                    .map(|x| T::from_base(*x))
+                    .collect(),
+            )
+            .evaluate(&point);
+            let prover: One2ManyProver<T, Blake3Algorithm, Fp64> = One2ManyProver::new(
+                &params.protocol,
+                &params.interpolate_cosets,
+                polynomial,
+                &params.oracle(),
+            )
+            .unwrap();
+            let commitment = prover.commit_polynomial();
+            let proof = prover.open(&point);
+
+            let bytes = proof.to_bytes();
+            let decoded = PcsProof::<T, Blake3Algorithm, Fp64>::from_bytes(&bytes).unwrap();
+            assert_eq!(decoded.to_bytes(), bytes);
+            // The committed values are sent in the base field.
+            assert!(PcsProof::<T>::from_bytes(&bytes).is_err());
+            assert_eq!(
+                verify(&params, &commitment, &point, value, &decoded),
+                Ok(())
+            );
+            assert_eq!(
+                verify(
+                    &params,
+                    &commitment,
+                    &point,
+                    value + T::from_int(1),
+                    &decoded
+                ),
+                Err(VerificationError::EvaluationMismatch)
+            );
+            let mut tampered = proof.clone();
+            let v = tampered
+                .committed_proof
+                .proof_values
+                .values_mut()
+                .next()
+                .unwrap();
+            *v += Fp64::from_int(1);
+            assert_eq!(
+                verify(&params, &commitment, &point, value, &tampered),
+                Err(VerificationError::BadMerklePath)
+            );
+        }
+    }
+
+    #[test]
+    fn base_field_domain() {
+        // Mersenne61 has no multiplicative subgroup of size above 2, so its
+        // polynomials cannot be committed over a domain of its extension.
+        let variable_num = 6;
+        let params = protocol_params(1, 0);
+        assert_eq!(
+            PcsParams::<Mersenne61Ext>::over_domains::<Mersenne61>(
+                variable_num,
+                params,
+                Mersenne61Ext::random_element()
+            )
+            .err(),
+            Some(ParamsError::DomainNotInBaseField)
+        );
+        let interpolate_cosets = params
+            .interpolate_cosets(variable_num, Mersenne61Ext::random_element())
+            .unwrap();
+        let oracle = RandomOracle::from_params(variable_num, &params);
+        let polynomial = MultilinearPolynomial::<Mersenne61>::random_polynomial(variable_num);
+        let prover: Result<One2ManyProver<Mersenne61Ext, Blake3Algorithm, Mersenne61>, _> =
+            One2ManyProver::new(&params, &interpolate_cosets, polynomial, &oracle);
+        assert_eq!(prover.err(), Some(ParamsError::DomainNotInBaseField));
+    }
+
+    #[test]
+    fn multilinear_pcs() {
+        type Scheme = PcsParams<Mersenne61Ext>;
+        let variable_num = 8;
+        let params = <Scheme as MultilinearPCS<_, Blake3Algorithm>>::setup(
+            variable_num,
+            ProtocolParams::default(),
+        )
+        .unwrap();
+        let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
+        let (commitment, prover) = params.commit(polynomial.clone()).unwrap();
+        for _ in 0..2 {
+            let point: Vec<_> = (0..variable_num)
+                .map(|_| Mersenne61Ext::random_element())
+                .collect();
+            let value = polynomial.evaluate(&point);
+            let proof = params.open(&prover, &point);
+            assert_eq!(params.verify(&commitment, &point, value, &proof), Ok(()));
+            assert_ne!(
+                params.verify(
+                    &commitment,
+                    &point,
+                    value + Mersenne61Ext::from_int(1),
+                    &proof
+                ),
+                Ok(())
+            );
+            assert!(<Scheme as MultilinearPCS<_, Blake3Algorithm>>::proof_size(&proof) > 0);
+        }
+    }
+
+    #[test]
+    fn constructor_errors() {
+        let variable_num = 8;
+        let params = protocol_params(2, 0);
+        let interpolate_cosets = params
+            .interpolate_cosets(variable_num, Mersenne61Ext::random_element())
+            .unwrap();
+        let oracle = RandomOracle::from_params(variable_num, &params);
+        let polynomial = MultilinearPolynomial::<Mersenne61Ext>::random_polynomial(2);
+        assert_eq!(
+            One2ManyProver::<_>::new(&params, &interpolate_cosets, polynomial.clone(), &oracle)
+                .err(),
+            Some(ParamsError::TooFewVariables)
+        );
+        assert_eq!(
+            One2ManyProver::<_>::new_batch(&params, &interpolate_cosets, vec![], &oracle).err(),
+            Some(ParamsError::VariableNumMismatch)
+        );
+        let polynomials = vec![
+            MultilinearPolynomial::random_polynomial(variable_num),
+            MultilinearPolynomial::random_polynomial(variable_num - 1),
+        ];
+        assert_eq!(
+            One2ManyProver::<_>::new_batch(&params, &interpolate_cosets, polynomials, &oracle)
+                .err(),
+            Some(ParamsError::VariableNumMismatch)
+        );
+        let verifier = One2ManyVerifier::<_>::new_with_open_point(
+            &params,
+            variable_num,
+            &interpolate_cosets,
+            [0; MERKLE_ROOT_SIZE],
+            vec![Mersenne61Ext::from_int(1); variable_num - 1],
+            &oracle,
+        );
+        assert_eq!(verifier.err(), Some(ParamsError::VariableNumMismatch));
+        let fp64_params = ProtocolParams {
+            field: FieldChoice::Fp64,
+            ..params
+        };
+        let verifier = One2ManyVerifier::<_>::new(
+            &fp64_params,
+            variable_num,
+            &interpolate_cosets,
+            [0; MERKLE_ROOT_SIZE],
+            &oracle,
+        );
+        assert_eq!(verifier.err(), Some(ParamsError::WrongField));
+
+        let scheme = <PcsParams<Mersenne61Ext> as MultilinearPCS<_, Blake3Algorithm>>::setup(
+            variable_num,
+            params,
+        )
+        .unwrap();
+        let commit: Result<(_, One2ManyProver<_>), _> = scheme.commit(polynomial);
+        assert_eq!(commit.err(), Some(ParamsError::VariableNumMismatch));
+    }
+
+    #[test]
+    fn additive_domain() {
+        type T = BinaryTower128;
+        let variable_num = 10;
+        let protocol = ProtocolParams::new(3, 100, 1, 0, FieldChoice::BinaryTower128).unwrap();
+        let protocols = [
+            protocol,
+            protocol
+                .with_shared_trees(true)
+                .unwrap()
+                .with_folding_arity(4)
+                .unwrap(),
+            hiding_params(FieldChoice::BinaryTower128),
+        ];
+        for protocol in protocols {
+            let params = PcsParams::<T, AffineSubspace<T>>::over_domains::<T>(
+                variable_num,
+                protocol,
+                T::random_element(),
+            )
+            .unwrap();
+            let polynomials: Vec<_> = (0..3)
+                .map(|_| MultilinearPolynomial::random_polynomial(variable_num))
+                .collect();
+            let point: Vec<_> = (0..variable_num).map(|_| T::random_element()).collect();
+            let values: Vec<_> = polynomials.iter().map(|x| x.evaluate(&point)).collect();
+
+            let prover: One2ManyProver<T, Blake3Algorithm, T, AffineSubspace<T>> =
+                One2ManyProver::new(
+                    &params.protocol,
+                    &params.interpolate_cosets,
+                    polynomials[0].clone(),
+                    &params.oracle(),
+                )
+                .unwrap();
+            let commitment = prover.commit_polynomial();
+            let proof = prover.open(&point);
+            let decoded: PcsProof<T> = PcsProof::from_bytes(&proof.to_bytes()).unwrap();
+            assert_eq!(
+                verify(&params, &commitment, &point, values[0], &decoded),
+                Ok(())
+            );
+            assert_eq!(
+                verify(&params, &commitment, &point, values[1], &decoded),
+                Err(VerificationError::EvaluationMismatch)
+            );
+            let mut tampered = proof.clone();
+            *tampered.folding_proofs[0]
+                .proof_values
+                .values_mut()
+                .next()
+                .unwrap() += T::from_int(1);
+            assert_ne!(
+                verify(&params, &commitment, &point, values[0], &tampered),
+                Ok(())
+            );
+
+            let prover: One2ManyProver<T, Blake3Algorithm, T, AffineSubspace<T>> =
+                One2ManyProver::new_batch(
+                    &params.protocol,
+                    &params.interpolate_cosets,
+                    polynomials,
+                    &params.oracle(),
+                )
+                .unwrap();
+            let commitment = prover.commit_polynomial();
+            let proof = prover.open(&point);
+            assert_eq!(
+                verify_batch(&params, &commitment, &point, &values, &proof),
+                Ok(())
             );
         }
     }
@@ -0,0 +1,139 @@
+//! Opening one commitment at several points.
+//!
+//! With `f(x) = sum_S c_S prod_{i in S} x_i`, the claims `f(z_k) = y_k` are
+//! combined with powers of a challenge `alpha` into `<c, w> = sum_k alpha^k
+//! y_k` for the public vector `w_S = sum_k alpha^k prod_{i in S} z_{k,i}`.
+//! A sumcheck over the hypercube reduces this to one evaluation of the
+//! multilinear extension of `c` at a random `r`, which equals
+//! `prod_i (1 - r_i) * f(r_i / (1 - r_i))` and is checked with a single
+//! PolyFRIM opening.
+//!
+//! This needs every `r_i` to differ from one, and the sumcheck to be sound
+//! with challenges drawn from the field itself. Both only hold up to the
+//! inverse of the field size per variable, so opening at several points is
+//! rejected over the 31-bit fields used without an extension.
+
+use util::algebra::field::Field;
+use util::params::ParamsError;
+use util::sumcheck::{self, SumcheckProof};
+use util::transcript::Transcript;
+use util::verification_error::VerificationError;
+
+const TRANSCRIPT_LABEL: &[u8] = b"PolyFRIM multi-point";
+
+/// Size in bytes of the smallest field challenges are drawn from.
+const MIN_FIELD_BYTES: usize = 8;
+
+/// Whether the challenges of `T` are large enough, see the module
+/// documentation.
+pub(crate) fn check_field<T: Field>() -> Result<(), ParamsError> {
+    if T::BYTE_SIZE < MIN_FIELD_BYTES {
+        return Err(ParamsError::FieldTooSmall);
+    }
+    Ok(())
+}
+
+fn transcript<T: Field>(
+    commitment: &impl AsRef<[u8]>,
+    points: &[Vec<T>],
+    values: &[T],
+) -> Transcript {
+    let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+    transcript.append_root(commitment);
+    for point in points {
+        transcript.append_field_elements(point);
+    }
+    transcript.append_field_elements(values);
+    transcript
+}
+
+/// `w` from the module documentation, indexed like the coefficients.
+fn weights<T: Field>(points: &[Vec<T>], alpha: T) -> Vec<T> {
+    let mut res = vec![T::from_int(0); 1 << points[0].len()];
+    let mut acc = T::from_int(1);
+    for point in points {
+        let mut monomials = vec![acc];
+        for z in point {
+            let high = monomials.iter().map(|x| *x * *z).collect::<Vec<_>>();
+            monomials.extend(high);
+        }
+        for (x, y) in res.iter_mut().zip(monomials) {
+            *x += y;
+        }
+        acc *= alpha;
+    }
+    res
+}
+
+/// Multilinear extension of `weights(points, alpha)` at `r`.
+fn weight_at<T: Field>(points: &[Vec<T>], alpha: T, r: &[T]) -> T {
+    let mut res = T::from_int(0);
+    let mut acc = T::from_int(1);
+    for point in points {
+        let mut v = acc;
+        for (z, r) in point.iter().zip(r) {
+            v *= T::from_int(1) - *r + *r * *z;
+        }
+        res += v;
+        acc *= alpha;
+    }
+    res
+}
+
+/// Maps the sumcheck point `r` to the point at which `f` is opened.
+fn open_point<T: Field>(r: &[T]) -> Option<(Vec<T>, T)> {
+    let mut scale = T::from_int(1);
+    let mut point = vec![];
+    for r in r {
+        let s = T::from_int(1) - *r;
+        if s.is_zero() {
+            return None;
+        }
+        scale *= s;
+        point.push(*r * s.inverse());
+    }
+    Some((point, scale))
+}
+
+/// Runs the sumcheck for `f` with the given coefficients, returning the
+/// round messages and the point at which `f` must be opened.
+pub(crate) fn prove<T: Field>(
+    commitment: &impl AsRef<[u8]>,
+    coefficients: Vec<T>,
+    points: &[Vec<T>],
+    values: &[T],
+) -> (SumcheckProof<T>, Vec<T>) {
+    let mut transcript = transcript(commitment, points, values);
+    let alpha = transcript.challenge();
+    let w = weights(points, alpha);
+    let (proof, r, _) =
+        sumcheck::prove_tables(vec![coefficients, w], 2, |x| x[0] * x[1], &mut transcript);
+    // Has negligible probability over the fields `check_field` accepts.
+    let (point, _) = open_point(&r).expect("sumcheck challenge equals one");
+    (proof, point)
+}
+
+/// Checks the sumcheck rounds given that `f` takes `opened_value` at the
+/// returned point, which the caller must check with a PolyFRIM opening.
+pub(crate) fn verify<T: Field>(
+    commitment: &impl AsRef<[u8]>,
+    points: &[Vec<T>],
+    values: &[T],
+    proof: &SumcheckProof<T>,
+    opened_value: T,
+) -> Result<Vec<T>, VerificationError> {
+    let mut transcript = transcript(commitment, points, values);
+    let alpha: T = transcript.challenge();
+    let mut sum = T::from_int(0);
+    let mut acc = T::from_int(1);
+    for y in values {
+        sum += *y * acc;
+        acc *= alpha;
+    }
+    let claim = sumcheck::verify(sum, points[0].len(), 2, proof, &mut transcript)?;
+    let (point, scale) = open_point(&claim.point).ok_or(VerificationError::WrongProofShape)?;
+    if claim.value != scale * opened_value * weight_at(points, alpha, &claim.point) {
+        return Err(VerificationError::EvaluationMismatch);
+    }
+    Ok(point)
+}
@@ -0,0 +1,169 @@
+use util::algebra::field::{ExtensionField, Field};
+use util::algebra::polynomial::{MultilinearPolynomial, Polynomial};
+use util::codec::{DecodeError, Decoder, Encoder};
+use util::merkle_tree::{Blake3Algorithm, MerkleHasher};
+use util::params::ProtocolParams;
+use util::query_result::QueryResult;
+use util::sumcheck::SumcheckProof;
+
+const MAGIC: &[u8; 4] = b"FRIM";
+const VERSION: u8 = 7;
+
+/// Everything the prover sends after the polynomial commitment, so that an
+/// opening can be checked by `crate::verify` without any prover state. The
+/// values of the committed polynomials lie in the base field `F`, so their
+/// answer `committed_proof` is kept and encoded in `F`, while
+/// `function_proofs` answers for the functions committed in later rounds.
+#[derive(Clone)]
+pub struct PcsProof<T: Field, H: MerkleHasher = Blake3Algorithm, F: Field = T> {
+    pub params: ProtocolParams,
+    pub function_roots: Vec<H::Hash>,
+    pub evaluation: MultilinearPolynomial<T>,
+    pub folding_roots: Vec<H::Hash>,
+    pub final_value: Polynomial<T>,
+    pub pow_nonce: u64,
+    pub folding_proofs: Vec<QueryResult<T>>,
+    pub committed_proof: QueryResult<F>,
+    pub function_proofs: Vec<QueryResult<T>>,
+}
+
+impl<T: ExtensionField<F>, H: MerkleHasher, F: Field> PcsProof<T, H, F> {
+    pub fn proof_size(&self) -> usize {
+        self.to_bytes().len()
+    }
+
+    /// The answers of every committed function, in the extension, as the
+    /// verifier takes them.
+    pub fn all_function_proofs(&self) -> Vec<QueryResult<T>> {
+        let mut res = vec![self.committed_proof.lift()];
+        res.extend(self.function_proofs.iter().cloned());
+        res
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new();
+        encoder.write_raw(MAGIC);
+        encoder.write_u8(VERSION);
+        self.params.encode(&mut encoder);
+        encoder.write_roots(&self.function_roots);
+        encoder.write_fields(self.evaluation.coefficients());
+        encoder.write_roots(&self.folding_roots);
+        encoder.write_fields(self.final_value.coefficients());
+        encoder.write_u64(self.pow_nonce);
+        encoder.write_usize(self.folding_proofs.len());
+        for proof in &self.folding_proofs {
+            proof.encode(&mut encoder);
+        }
+        self.committed_proof.encode(&mut encoder);
+        encoder.write_usize(self.function_proofs.len());
+        for proof in &self.function_proofs {
+            proof.encode(&mut encoder);
+        }
+        encoder.into_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut decoder = Decoder::new(bytes);
+        if decoder.read_raw(MAGIC.len())? != MAGIC {
+            return Err(DecodeError::BadMagic);
+        }
+        let version = decoder.read_u8()?;
+        if version != VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+        let params = ProtocolParams::decode(&mut decoder)?;
+        let function_roots = decoder.read_roots::<H>()?;
+        let evaluation = decoder.read_fields()?;
+        if !evaluation.len().is_power_of_two() {
+            return Err(DecodeError::InvalidLength);
+        }
+        let folding_roots = decoder.read_roots::<H>()?;
+        let final_value: Vec<T> = decoder.read_fields()?;
+        // The final polynomial has degree below `2^terminate_round`, and may
+        // be shorter or even zero once `Polynomial::new` trims it.
+        if final_value.len() > 1 << params.terminate_round {
+            return Err(DecodeError::InvalidLength);
+        }
+        let pow_nonce = decoder.read_u64()?;
+        let len = decoder.read_len(16)?;
+        let folding_proofs = (0..len)
+            .map(|_| QueryResult::decode(&mut decoder))
+            .collect::<Result<Vec<_>, _>>()?;
+        let committed_proof = QueryResult::decode(&mut decoder)?;
+        let len = decoder.read_len(16)?;
+        let function_proofs = (0..len)
+            .map(|_| QueryResult::decode(&mut decoder))
+            .collect::<Result<Vec<_>, _>>()?;
+        decoder.finish()?;
+        Ok(PcsProof {
+            params,
+            function_roots,
+            evaluation: MultilinearPolynomial::new(evaluation),
+            folding_roots,
+            final_value: Polynomial::new(final_value),
+            pow_nonce,
+            folding_proofs,
+            committed_proof,
+            function_proofs,
+        })
+    }
+}
+
+const MULTI_POINT_MAGIC: &[u8; 4] = b"FRMP";
+const MULTI_POINT_VERSION: u8 = 1;
+
+/// Opening of one commitment at several points, see `crate::multi_point`.
+#[derive(Clone)]
+pub struct MultiPointProof<T: Field, H: MerkleHasher = Blake3Algorithm, F: Field = T> {
+    pub sumcheck: SumcheckProof<T>,
+    pub value: T,
+    pub opening: PcsProof<T, H, F>,
+}
+
+impl<T: ExtensionField<F>, H: MerkleHasher, F: Field> MultiPointProof<T, H, F> {
+    pub fn proof_size(&self) -> usize {
+        self.to_bytes().len()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut encoder = Encoder::new();
+        encoder.write_raw(MULTI_POINT_MAGIC);
+        encoder.write_u8(MULTI_POINT_VERSION);
+        encoder.write_usize(self.sumcheck.rounds.first().map_or(0, |x| x.len()));
+        encoder.write_fields(&self.sumcheck.rounds.concat());
+        encoder.write_field(&self.value);
+        encoder.write_bytes(&self.opening.to_bytes());
+        encoder.into_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut decoder = Decoder::new(bytes);
+        if decoder.read_raw(MULTI_POINT_MAGIC.len())? != MULTI_POINT_MAGIC {
+            return Err(DecodeError::BadMagic);
+        }
+        let version = decoder.read_u8()?;
+        if version != MULTI_POINT_VERSION {
+            return Err(DecodeError::UnsupportedVersion(version));
+        }
+        let round_len = decoder.read_usize()?;
+        let sumcheck: Vec<T> = decoder.read_fields()?;
+        if (round_len == 0 && !sumcheck.is_empty())
+            || (round_len != 0 && !sumcheck.len().is_multiple_of(round_len))
+        {
+            return Err(DecodeError::InvalidLength);
+        }
+        let value = decoder.read_field()?;
+        let opening = PcsProof::from_bytes(&decoder.read_bytes()?)?;
+        decoder.finish()?;
+        Ok(MultiPointProof {
+            sumcheck: SumcheckProof {
+                rounds: sumcheck
+                    .chunks_exact(round_len.max(1))
+                    .map(|x| x.to_vec())
+                    .collect(),
+            },
+            value,
+            opening,
+        })
+    }
+}
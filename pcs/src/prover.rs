@@ -1,196 +1,558 @@
+use super::multi_point;
+use super::proof::{MultiPointProof, PcsProof};
 use super::verifier::One2ManyVerifier;
+use std::ops::Range;
 use util::algebra::polynomial::{MultilinearPolynomial, Polynomial};
 
-use util::merkle_tree::MERKLE_ROOT_SIZE;
 use util::query_result::QueryResult;
 use util::{
     algebra::{
         coset::Coset,
-        field::{as_bytes_vec, Field},
+        domain::EvaluationDomain,
+        field::{as_bytes_vec, ExtensionField, Field, BATCH_SIZE},
     },
-    merkle_tree::MerkleTreeProver,
+    merkle_tree::{Blake3Algorithm, MerkleHasher, MerkleTreeProver},
+    parallel,
+    params::{ParamsError, ProtocolParams},
     random_oracle::RandomOracle,
 };
 
 #[derive(Clone)]
-struct InterpolateValue<T: Field> {
+struct InterpolateValue<T: Field, H: MerkleHasher> {
     value: Vec<T>,
-    merkle_tree: MerkleTreeProver,
+    width: usize,
+    arity: usize,
+    merkle_tree: MerkleTreeProver<H>,
 }
 
-impl<T: Field> InterpolateValue<T> {
-    fn new(value: Vec<T>) -> Self {
-        let len = value.len() / 2;
-        let merkle_tree = MerkleTreeProver::new(
-            (0..len)
-                .map(|i| as_bytes_vec(&[value[i], value[i + len]]))
-                .collect(),
-        );
-        Self { value, merkle_tree }
+impl<T: Field, H: MerkleHasher> InterpolateValue<T, H> {
+    fn new(value: Vec<T>, arity: usize, hiding: bool) -> Self {
+        Self::with_width(value, 1, arity, hiding)
+    }
+
+    /// Commits to several functions on the same domain with one tree, each
+    /// leaf holding the values of all of them.
+    fn new_batch(values: Vec<Vec<T>>, arity: usize, hiding: bool) -> Self {
+        let width = values.len();
+        Self::with_width(values.concat(), width, arity, hiding)
+    }
+
+    /// Leaf `i` holds the `arity` values at `i + t * len` of each function,
+    /// the coset folded into position `i` of the next committed domain.
+    fn with_width(value: Vec<T>, width: usize, arity: usize, hiding: bool) -> Self {
+        let len = value.len() / width / arity;
+        let leaves = parallel::map(len, |i| {
+            let leaf = (0..width * arity)
+                .map(|t| value[t * len + i])
+                .collect::<Vec<_>>();
+            as_bytes_vec(&leaf)
+        });
+        let merkle_tree = if hiding {
+            MerkleTreeProver::new_salted(leaves)
+        } else {
+            MerkleTreeProver::new(leaves)
+        };
+        Self {
+            value,
+            width,
+            arity,
+            merkle_tree,
+        }
+    }
+
+    /// Values of the first function of the leaves.
+    fn first(&self) -> &[T] {
+        &self.value[..self.value.len() / self.width]
     }
 
     fn leave_num(&self) -> usize {
         self.merkle_tree.leave_num()
     }
 
-    fn commit(&self) -> [u8; MERKLE_ROOT_SIZE] {
+    fn commit(&self) -> H::Hash {
         self.merkle_tree.commit()
     }
 
     fn query(&self, leaf_indices: &Vec<usize>) -> QueryResult<T> {
         let len = self.merkle_tree.leave_num();
-        let proof_values = leaf_indices
-            .iter()
-            .flat_map(|j| [(*j, self.value[*j]), (*j + len, self.value[*j + len])])
+        let proof_values = (0..self.width * self.arity)
+            .flat_map(|t| leaf_indices.iter().map(move |j| t * len + j))
+            .map(|j| (j, self.value[j]))
             .collect();
         let proof_bytes = self.merkle_tree.open(&leaf_indices);
         QueryResult {
             proof_bytes,
             proof_values,
+            salts: self.merkle_tree.salts(leaf_indices),
         }
     }
 }
 
+/// Prover for polynomials with coefficients in `F`, committed by their
+/// codewords over `F`, while the open point and the folding challenges are
+/// drawn from its extension `T`, in which everything after the first
+/// folding lives. The evaluation domains are of type `D`, cosets by
+/// default.
 #[derive(Clone)]
-pub struct One2ManyProver<T: Field> {
+pub struct One2ManyProver<
+    T: Field,
+    H: MerkleHasher = Blake3Algorithm,
+    F: Field = T,
+    D: EvaluationDomain<T> = Coset<T>,
+> {
+    params: ProtocolParams,
     total_round: usize,
+    folding_rounds: Vec<Range<usize>>,
     variable_num: usize,
-    interpolate_cosets: Vec<Coset<T>>,
-    functions: Vec<InterpolateValue<T>>,
-    foldings: Vec<InterpolateValue<T>>,
+    interpolate_cosets: Vec<D>,
+    /// Codeword of the committed polynomial, or of all of them for a batch.
+    committed: InterpolateValue<F, H>,
+    /// Codewords committed at the start of the later rounds.
+    functions: Vec<InterpolateValue<T, H>>,
+    batch_polynomials: Vec<MultilinearPolynomial<F>>,
+    batch_evaluations: Vec<T>,
+    /// The committed codeword over `T`, combining those of a batch.
+    combined_function: Option<Vec<T>>,
+    /// Values of the function of each folding step without a tree of its
+    /// own: those inside a round, which the verifier folds itself, and with
+    /// `shared_trees` those waiting for the folding tree of the previous
+    /// round.
+    pending_functions: Vec<Vec<T>>,
+    foldings: Vec<InterpolateValue<T, H>>,
     oracle: RandomOracle<T>,
+    evaluation: Option<MultilinearPolynomial<T>>,
     final_value: Option<Polynomial<T>>,
+    pow_nonce: u64,
 }
 
-impl<T: Field> One2ManyProver<T> {
+impl<T: ExtensionField<F>, H: MerkleHasher, F: Field, D: EvaluationDomain<T>>
+    One2ManyProver<T, H, F, D>
+{
+    /// The first of `interpolate_coset` must lie in `F`, the values of the
+    /// committed codewords being computed there.
     pub fn new(
-        total_round: usize,
-        interpolate_coset: &Vec<Coset<T>>,
-        polynomial: MultilinearPolynomial<T>,
+        params: &ProtocolParams,
+        interpolate_coset: &Vec<D>,
+        polynomial: MultilinearPolynomial<F>,
         oracle: &RandomOracle<T>,
-    ) -> One2ManyProver<T> {
-        let interpolation = interpolate_coset[0].fft(polynomial.coefficients().clone());
+    ) -> Result<One2ManyProver<T, H, F, D>, ParamsError> {
+        params.check_field::<T>()?;
+        let total_round = params.total_round(polynomial.variable_num())?;
+        let folding_rounds = params.folding_rounds(total_round);
+        let polynomial = Self::mask(params, polynomial);
+        let interpolation = interpolate_coset[0]
+            .fft_base(polynomial.coefficients().clone())
+            .ok_or(ParamsError::DomainNotInBaseField)?;
+        let function =
+            InterpolateValue::new(interpolation, 1 << folding_rounds[0].len(), params.hiding);
 
-        One2ManyProver {
+        Ok(One2ManyProver {
+            params: *params,
             total_round,
+            folding_rounds,
             variable_num: polynomial.variable_num(),
             interpolate_cosets: interpolate_coset.clone(),
-            functions: vec![InterpolateValue::new(interpolation)],
+            committed: function,
+            functions: vec![],
+            batch_polynomials: vec![],
+            batch_evaluations: vec![],
+            combined_function: None,
+            pending_functions: vec![],
+            foldings: vec![],
+            oracle: oracle.clone(),
+            evaluation: None,
+            final_value: None,
+            pow_nonce: 0,
+        })
+    }
+
+    /// Commits to all `polynomials` with a single Merkle tree. They are
+    /// opened at the same point by folding their random linear combination.
+    pub fn new_batch(
+        params: &ProtocolParams,
+        interpolate_coset: &Vec<D>,
+        polynomials: Vec<MultilinearPolynomial<F>>,
+        oracle: &RandomOracle<T>,
+    ) -> Result<One2ManyProver<T, H, F, D>, ParamsError> {
+        params.check_field::<T>()?;
+        let variable_num = polynomials
+            .first()
+            .ok_or(ParamsError::VariableNumMismatch)?
+            .variable_num();
+        if polynomials.iter().any(|x| x.variable_num() != variable_num) {
+            return Err(ParamsError::VariableNumMismatch);
+        }
+        let total_round = params.total_round(variable_num)?;
+        let folding_rounds = params.folding_rounds(total_round);
+        let polynomials: Vec<_> = polynomials
+            .into_iter()
+            .map(|x| Self::mask(params, x))
+            .collect();
+        let interpolations = polynomials
+            .iter()
+            .map(|x| interpolate_coset[0].fft_base(x.coefficients().clone()))
+            .collect::<Option<_>>()
+            .ok_or(ParamsError::DomainNotInBaseField)?;
+        let function = InterpolateValue::new_batch(
+            interpolations,
+            1 << folding_rounds[0].len(),
+            params.hiding,
+        );
+
+        Ok(One2ManyProver {
+            params: *params,
+            total_round,
+            folding_rounds,
+            variable_num: params.committed_variable_num(variable_num),
+            interpolate_cosets: interpolate_coset.clone(),
+            committed: function,
+            functions: vec![],
+            batch_polynomials: polynomials,
+            batch_evaluations: vec![],
+            combined_function: None,
+            pending_functions: vec![],
             foldings: vec![],
             oracle: oracle.clone(),
+            evaluation: None,
             final_value: None,
+            pow_nonce: 0,
+        })
+    }
+
+    /// In hiding mode, appends the masking variables `y` with random
+    /// coefficients, committing to `f(x) + sum_S y^S * r_S(x)` over the
+    /// nonempty sets `S` of them. Openings fix `y = 0`, while the revealed
+    /// values of every round's oracles are masked by the `r_S`.
+    fn mask(
+        params: &ProtocolParams,
+        polynomial: MultilinearPolynomial<F>,
+    ) -> MultilinearPolynomial<F> {
+        if !params.hiding {
+            return polynomial;
         }
+        let mut coefficients = polynomial.coefficients().clone();
+        let mask_len = coefficients.len() * ((1 << params.mask_variable_num()) - 1);
+        coefficients.extend((0..mask_len).map(|_| F::random_element()));
+        MultilinearPolynomial::new(coefficients)
     }
 
-    pub fn commit_polynomial(&self) -> [u8; MERKLE_ROOT_SIZE] {
-        assert_eq!(self.functions.len(), 1);
-        self.functions[0].commit()
+    pub fn commit_polynomial(&self) -> H::Hash {
+        assert!(self.functions.is_empty());
+        self.committed.commit()
     }
 
-    fn fold(values: &Vec<T>, parameter: T, coset: &Coset<T>) -> Vec<T> {
+    fn fold(values: &Vec<T>, parameter: T, coset: &D) -> Vec<T> {
         let len = values.len() / 2;
-        let res = (0..len)
-            .into_iter()
-            .map(|i| {
-                let x = values[i];
-                let nx = values[i + len];
-                let new_v = (x + nx) + parameter * (x - nx) * coset.element_inv_at(i);
-                new_v * T::INVERSE_2
+        let twiddles = coset.fold_twiddles();
+        let parameters = [parameter; BATCH_SIZE];
+        let scales = [coset.fold_scale(); BATCH_SIZE];
+        let mut folded = vec![T::ZERO; len];
+        parallel::for_each_chunk_mut(&mut folded, BATCH_SIZE, |start, evens| {
+            let mut odds = [T::ZERO; BATCH_SIZE];
+            let odds = &mut odds[..evens.len()];
+            for (k, (even, odd)) in evens.iter_mut().zip(odds.iter_mut()).enumerate() {
+                let i = start + k;
+                (*even, *odd) = D::split(values[i], values[i + len], twiddles[i]);
+            }
+            T::mul_assign_slice(odds, &parameters[..odds.len()]);
+            T::add_assign_slice(evens, odds);
+            T::mul_assign_slice(evens, &scales[..odds.len()]);
+        });
+        folded
+    }
+
+    fn first_function(&self) -> &Vec<T> {
+        self.combined_function.as_ref().unwrap()
+    }
+
+    /// Number of values per leaf of the trees of `round`.
+    fn arity(&self, round: usize) -> usize {
+        1 << self.folding_rounds[round].len()
+    }
+
+    /// Values of the function folded in `step`. At the start of a round it
+    /// is committed in `functions`, or in the shared layout in the tree of
+    /// the previous folding after its first half.
+    fn function_value(&self, step: usize) -> &[T] {
+        let round = step / self.params.log_folding_arity;
+        if step == 0 {
+            self.first_function()
+        } else if !self.pending_functions[step].is_empty() {
+            &self.pending_functions[step]
+        } else if self.params.shared_trees {
+            let folding = &self.foldings[round - 1].value;
+            &folding[folding.len() / 2..]
+        } else {
+            &self.functions[round - 1].value
+        }
+    }
+
+    /// `open_point` with the masking variables, if any, set to zero.
+    fn padded_point(&self, open_point: &Vec<T>) -> Vec<T> {
+        let mut point = open_point.clone();
+        point.resize(self.variable_num, T::from_int(0));
+        point
+    }
+
+    fn lift(values: &[F]) -> Vec<T> {
+        parallel::map(values.len(), |i| T::from_base(values[i]))
+    }
+
+    fn combine_batch(&mut self, open_point: &Vec<T>) {
+        self.batch_evaluations = self
+            .batch_polynomials
+            .iter()
+            .map(|x| {
+                MultilinearPolynomial::new(Self::lift(x.coefficients()))
+                    .evaluate(&self.padded_point(open_point))
             })
             .collect();
-        res
+        self.oracle.absorb_field_elements(&self.batch_evaluations);
+        let rlc = self.oracle.rlc_challenge();
+        let value = &self.committed.value;
+        let len = value.len() / self.batch_polynomials.len();
+        let mut combined = Self::lift(&value[..len]);
+        let mut acc = rlc;
+        for function in value.chunks(len).skip(1) {
+            for (x, y) in combined.iter_mut().zip(function) {
+                *x += acc.mul_base(*y);
+            }
+            acc *= rlc;
+        }
+        self.combined_function = Some(combined);
     }
 
-    pub fn commit_functions(&mut self, open_point: &Vec<T>, verifier: &mut One2ManyVerifier<T>) {
+    fn fold_functions(&mut self, open_point: &Vec<T>) {
+        self.oracle.absorb_root(&self.committed.commit());
+        self.oracle.absorb_field_elements(open_point);
+        if self.batch_polynomials.is_empty() {
+            self.combined_function = Some(Self::lift(self.committed.first()));
+        } else {
+            self.combine_batch(open_point);
+        }
+        let mut functions: Vec<Vec<T>> = vec![];
         let mut evaluation = None;
         for round in 0..self.total_round {
-            let next_evaluation = Self::fold(
-                &self.functions[round].value,
-                open_point[round],
-                &self.interpolate_cosets[round],
-            );
+            let function = if round == 0 {
+                self.first_function()
+            } else {
+                &functions[round - 1]
+            };
+            let next_evaluation =
+                Self::fold(function, open_point[round], &self.interpolate_cosets[round]);
             if round < self.total_round - 1 {
-                self.functions.push(InterpolateValue::new(next_evaluation));
+                functions.push(next_evaluation);
             } else {
                 let mut coefficients = self.interpolate_cosets[round + 1].ifft(next_evaluation);
                 coefficients.truncate(1 << (self.variable_num - self.total_round));
                 evaluation = Some(MultilinearPolynomial::new(coefficients));
             }
         }
-        for i in 1..self.total_round {
-            let function = &self.functions[i];
+        let evaluation = evaluation.unwrap();
+        self.pending_functions = vec![vec![]];
+        for (step, function) in (1..).zip(functions) {
+            if step % self.params.log_folding_arity != 0 || self.params.shared_trees {
+                self.pending_functions.push(function);
+                continue;
+            }
+            let arity = self.arity(step / self.params.log_folding_arity);
+            let function = InterpolateValue::new(function, arity, self.params.hiding);
+            self.oracle.absorb_root(&function.commit());
+            self.functions.push(function);
+            self.pending_functions.push(vec![]);
+        }
+        self.oracle.absorb_field_elements(evaluation.coefficients());
+        self.evaluation = Some(evaluation);
+    }
+
+    pub fn commit_functions(
+        &mut self,
+        open_point: &Vec<T>,
+        verifier: &mut One2ManyVerifier<T, H, F, D>,
+    ) {
+        self.fold_functions(open_point);
+        verifier.receive_params(&self.params);
+        for function in &self.functions {
             verifier.set_function(function.leave_num(), &function.commit());
         }
-        verifier.set_evaluation(evaluation.unwrap());
+        verifier.set_evaluation(self.evaluation.clone().unwrap());
+        if !self.batch_polynomials.is_empty() {
+            verifier.set_batch_evaluations(self.batch_evaluations.clone());
+        }
     }
 
-    pub fn commit_foldings(&self, verifier: &mut One2ManyVerifier<T>) {
-        for i in 0..(self.total_round - 1) {
-            let interpolation = &self.foldings[i];
+    pub fn commit_foldings(&self, verifier: &mut One2ManyVerifier<T, H, F, D>) {
+        for interpolation in &self.foldings {
             verifier.receive_folding_root(interpolation.leave_num(), interpolation.commit());
         }
         verifier.set_final_value(self.final_value.as_ref().unwrap());
+        verifier.set_pow_nonce(self.pow_nonce);
     }
 
-    fn evaluation_next_domain(&self, round: usize, challenge: T) -> Vec<T> {
-        let mut res = vec![];
-        let len = self.interpolate_cosets[round].size();
-        let get_folding_value = if round == 0 {
-            &self.functions[round]
-        } else {
-            &self.foldings[round - 1]
+    /// Folds the folding of `step`, which is `folding` inside a round and
+    /// committed at its start.
+    fn evaluation_next_domain(&self, step: usize, folding: Option<&[T]>, challenge: T) -> Vec<T> {
+        let len = self.interpolate_cosets[step].size();
+        let get_folding_value = match folding {
+            Some(folding) => folding,
+            None if step == 0 => self.first_function(),
+            None => self.foldings[step / self.params.log_folding_arity - 1].first(),
         };
-        let coset = &self.interpolate_cosets[round];
-        for i in 0..(len / 2) {
-            let x = get_folding_value.value[i];
-            let nx = get_folding_value.value[i + len / 2];
-            let new_v = (x + nx) + challenge * (x - nx) * coset.element_inv_at(i);
-            if round == 0 {
-                res.push(new_v);
+        let function = self.function_value(step);
+        let twiddles = self.interpolate_cosets[step].fold_twiddles();
+        parallel::map(len / 2, |i| {
+            let (even, odd) = D::split(
+                get_folding_value[i],
+                get_folding_value[i + len / 2],
+                twiddles[i],
+            );
+            let new_v = even + challenge * odd;
+            if step == 0 {
+                new_v
             } else {
-                let fv = &self.functions[round];
-                let x = fv.value[i];
-                let nx = fv.value[i + len / 2];
-                let new_v =
-                    (new_v * challenge + (x + nx)) * challenge + (x - nx) * coset.element_inv_at(i);
-                res.push(new_v);
+                let (even, odd) = D::split(function[i], function[i + len / 2], twiddles[i]);
+                (new_v * challenge + even) * challenge + odd
             }
-        }
-        res
+        })
     }
 
     pub fn prove(&mut self) {
+        let mut folding: Option<Vec<T>> = None;
         for i in 0..self.total_round {
-            let challenge = self.oracle.folding_challenges[i];
-            if i < self.total_round - 1 {
-                let next_evalutation = self.evaluation_next_domain(i, challenge);
-                self.foldings.push(InterpolateValue::new(next_evalutation));
-            } else {
-                let next_evalutation = self.evaluation_next_domain(i, challenge);
+            let challenge = self.oracle.folding_challenge(i);
+            let next_evalutation = self.evaluation_next_domain(i, folding.as_deref(), challenge);
+            if i == self.total_round - 1 {
                 let coefficients = self.interpolate_cosets[i + 1].ifft(next_evalutation);
-                self.final_value = Some(Polynomial::new(coefficients));
+                let final_value = Polynomial::new(coefficients);
+                self.oracle
+                    .absorb_field_elements(final_value.coefficients());
+                self.final_value = Some(final_value);
+            } else if (i + 1) % self.params.log_folding_arity != 0 {
+                folding = Some(next_evalutation);
+            } else {
+                let arity = self.arity((i + 1) / self.params.log_folding_arity);
+                let interpolate_value = if self.params.shared_trees {
+                    let function = std::mem::take(&mut self.pending_functions[i + 1]);
+                    InterpolateValue::new_batch(
+                        vec![next_evalutation, function],
+                        arity,
+                        self.params.hiding,
+                    )
+                } else {
+                    InterpolateValue::new(next_evalutation, arity, self.params.hiding)
+                };
+                self.oracle.absorb_root(&interpolate_value.commit());
+                self.foldings.push(interpolate_value);
+                folding = None;
             }
         }
+        self.pow_nonce = self.oracle.grind();
+        self.oracle.sample_queries();
     }
 
     pub fn query(&self) -> (Vec<QueryResult<T>>, Vec<QueryResult<T>>) {
+        let (folding_res, committed_res, mut functions_res) = self.query_rounds();
+        functions_res.insert(0, committed_res.lift());
+        (folding_res, functions_res)
+    }
+
+    /// The answers of the foldings, of the committed polynomials, which
+    /// stay in the base field, and of the functions of the later rounds.
+    fn query_rounds(&self) -> (Vec<QueryResult<T>>, QueryResult<F>, Vec<QueryResult<T>>) {
         let mut folding_res = vec![];
+        let mut committed_res = None;
         let mut functions_res = vec![];
         let mut leaf_indices = self.oracle.query_list.clone();
 
-        for i in 0..self.total_round {
-            let len = self.interpolate_cosets[i].size();
-            leaf_indices = leaf_indices.iter_mut().map(|v| *v % (len >> 1)).collect();
+        for (i, round) in self.folding_rounds.iter().enumerate() {
+            let len = self.interpolate_cosets[round.start].size() >> round.len();
+            leaf_indices = leaf_indices.iter_mut().map(|v| *v % len).collect();
             leaf_indices.sort();
             leaf_indices.dedup();
 
-            let query_result = self.functions[i].query(&leaf_indices);
-            functions_res.push(query_result);
+            if i == 0 {
+                committed_res = Some(self.committed.query(&leaf_indices));
+            } else if !self.params.shared_trees {
+                functions_res.push(self.functions[i - 1].query(&leaf_indices));
+            }
 
             if i > 0 {
                 folding_res.push(self.foldings[i - 1].query(&leaf_indices));
             }
         }
-        (folding_res, functions_res)
+        (folding_res, committed_res.unwrap(), functions_res)
+    }
+
+    /// Fresh opening session over the committed functions, so that one
+    /// commitment can be opened any number of times.
+    fn session(&self) -> Self {
+        One2ManyProver {
+            params: self.params,
+            total_round: self.total_round,
+            folding_rounds: self.folding_rounds.clone(),
+            variable_num: self.variable_num,
+            interpolate_cosets: self.interpolate_cosets.clone(),
+            committed: self.committed.clone(),
+            functions: vec![],
+            batch_polynomials: self.batch_polynomials.clone(),
+            batch_evaluations: vec![],
+            combined_function: None,
+            pending_functions: vec![],
+            foldings: vec![],
+            oracle: self.oracle.clone(),
+            evaluation: None,
+            final_value: None,
+            pow_nonce: 0,
+        }
+    }
+
+    /// Non-interactive opening at `open_point`. It does not change the
+    /// prover, but must not be mixed with the interactive methods above,
+    /// which advance the oracle.
+    pub fn open(&self, open_point: &Vec<T>) -> PcsProof<T, H, F> {
+        let mut session = self.session();
+        session.fold_functions(open_point);
+        session.prove();
+        let (folding_proofs, committed_proof, function_proofs) = session.query_rounds();
+        PcsProof {
+            params: session.params,
+            function_roots: session.functions.iter().map(|x| x.commit()).collect(),
+            evaluation: session.evaluation.unwrap(),
+            folding_roots: session.foldings.iter().map(|x| x.commit()).collect(),
+            final_value: session.final_value.unwrap(),
+            pow_nonce: session.pow_nonce,
+            folding_proofs,
+            committed_proof,
+            function_proofs,
+        }
+    }
+
+    /// Opens a single committed polynomial at all `points` with one
+    /// folding proof, see `crate::multi_point`. The sumcheck is not
+    /// zero-knowledge, so this is not available in hiding mode, nor over the
+    /// 31-bit fields without an extension.
+    pub fn open_multi(&self, points: &[Vec<T>]) -> Result<MultiPointProof<T, H, F>, ParamsError> {
+        assert!(self.batch_polynomials.is_empty() && !self.params.hiding);
+        multi_point::check_field::<T>()?;
+        let mut coefficients = self.interpolate_cosets[0]
+            .ifft_base(self.committed.value.clone())
+            .expect("checked when the polynomial was committed");
+        coefficients.truncate(1 << self.variable_num);
+        let polynomial = MultilinearPolynomial::new(Self::lift(&coefficients));
+        let values = points
+            .iter()
+            .map(|x| polynomial.evaluate(x))
+            .collect::<Vec<_>>();
+        let (sumcheck, point) = multi_point::prove(
+            &self.committed.commit(),
+            polynomial.coefficients().clone(),
+            points,
+            &values,
+        );
+        Ok(MultiPointProof {
+            sumcheck,
+            value: polynomial.evaluate(&point),
+            opening: self.open(&point),
+        })
     }
 }
@@ -1,72 +1,122 @@
+use std::marker::PhantomData;
+use std::ops::Range;
 use util::algebra::polynomial::{MultilinearPolynomial, Polynomial};
-use util::merkle_tree::MERKLE_ROOT_SIZE;
+use util::params::{ParamsError, ProtocolParams};
 use util::random_oracle::RandomOracle;
 use util::{
-    algebra::{coset::Coset, field::Field},
-    merkle_tree::MerkleTreeVerifier,
+    algebra::{
+        coset::Coset,
+        domain::EvaluationDomain,
+        field::{ExtensionField, Field},
+    },
+    merkle_tree::{Blake3Algorithm, MerkleHasher, MerkleTreeVerifier},
     query_result::QueryResult,
+    verification_error::VerificationError,
 };
 
+/// Verifier for a commitment to polynomials over `F`, see `One2ManyProver`.
 #[derive(Clone)]
-pub struct One2ManyVerifier<T: Field> {
+pub struct One2ManyVerifier<
+    T: Field,
+    H: MerkleHasher = Blake3Algorithm,
+    F: Field = T,
+    D: EvaluationDomain<T> = Coset<T>,
+> {
+    params: ProtocolParams,
+    prover_params: Option<ProtocolParams>,
     total_round: usize,
-    log_max_degree: usize,
-    interpolate_cosets: Vec<Coset<T>>,
-    function_root: Vec<MerkleTreeVerifier>,
-    folding_root: Vec<MerkleTreeVerifier>,
+    folding_rounds: Vec<Range<usize>>,
+    interpolate_cosets: Vec<D>,
+    function_root: Vec<MerkleTreeVerifier<H>>,
+    folding_root: Vec<MerkleTreeVerifier<H>>,
     oracle: RandomOracle<T>,
     final_value: Option<Polynomial<T>>,
     evaluation: Option<MultilinearPolynomial<T>>,
+    batch_evaluations: Option<Vec<T>>,
     open_point: Vec<T>,
+    pow_nonce: u64,
+    base_field: PhantomData<F>,
 }
 
-impl<T: Field> One2ManyVerifier<T> {
+impl<T: ExtensionField<F>, H: MerkleHasher, F: Field, D: EvaluationDomain<T>>
+    One2ManyVerifier<T, H, F, D>
+{
     pub fn new(
-        total_round: usize,
+        params: &ProtocolParams,
         log_max_degree: usize,
-        coset: &Vec<Coset<T>>,
-        commit: [u8; MERKLE_ROOT_SIZE],
+        coset: &Vec<D>,
+        commit: H::Hash,
         oracle: &RandomOracle<T>,
-    ) -> Self {
-        One2ManyVerifier {
+    ) -> Result<Self, ParamsError> {
+        let open_point = (0..log_max_degree)
+            .into_iter()
+            .map(|_| T::random_element())
+            .collect();
+        Self::new_with_open_point(params, log_max_degree, coset, commit, open_point, oracle)
+    }
+
+    pub fn new_with_open_point(
+        params: &ProtocolParams,
+        log_max_degree: usize,
+        coset: &Vec<D>,
+        commit: H::Hash,
+        open_point: Vec<T>,
+        oracle: &RandomOracle<T>,
+    ) -> Result<Self, ParamsError> {
+        if open_point.len() != log_max_degree {
+            return Err(ParamsError::VariableNumMismatch);
+        }
+        params.check_field::<T>()?;
+        let total_round = params.total_round(log_max_degree)?;
+        let folding_rounds = params.folding_rounds(total_round);
+        Ok(One2ManyVerifier {
+            params: *params,
+            prover_params: None,
             total_round,
-            log_max_degree,
             interpolate_cosets: coset.clone(),
             function_root: vec![MerkleTreeVerifier {
                 merkle_root: commit,
-                leave_number: coset[0].size() / 2,
+                leave_number: coset[0].size() >> folding_rounds[0].len(),
             }],
+            folding_rounds,
             folding_root: vec![],
             oracle: oracle.clone(),
             final_value: None,
             evaluation: None,
-            open_point: (0..log_max_degree)
-                .into_iter()
-                .map(|_| T::random_element())
-                .collect(),
-        }
+            batch_evaluations: None,
+            open_point,
+            pow_nonce: 0,
+            base_field: PhantomData,
+        })
     }
 
     pub fn get_open_point(&self) -> Vec<T> {
         self.open_point.clone()
     }
 
+    /// Parameters the prover claims to have used, checked by `verify`.
+    pub fn receive_params(&mut self, params: &ProtocolParams) {
+        self.prover_params = Some(*params);
+    }
+
     pub fn set_evaluation(&mut self, evaluation: MultilinearPolynomial<T>) {
         self.evaluation = Some(evaluation);
     }
 
-    pub fn set_function(&mut self, leave_number: usize, function_root: &[u8; MERKLE_ROOT_SIZE]) {
+    /// Claimed values at the open point of each polynomial of a batch
+    /// commitment, in the order they were committed.
+    pub fn set_batch_evaluations(&mut self, evaluations: Vec<T>) {
+        self.batch_evaluations = Some(evaluations);
+    }
+
+    pub fn set_function(&mut self, leave_number: usize, function_root: &H::Hash) {
         self.function_root.push(MerkleTreeVerifier {
-            merkle_root: function_root.clone(),
+            merkle_root: *function_root,
             leave_number,
         });
     }
 
-    pub fn receive_folding_root(
-        &mut self,
-        leave_number: usize,
-        folding_root: [u8; MERKLE_ROOT_SIZE],
-    ) {
+    pub fn receive_folding_root(&mut self, leave_number: usize, folding_root: H::Hash) {
         self.folding_root.push(MerkleTreeVerifier {
             leave_number,
             merkle_root: folding_root,
@@ -74,82 +124,245 @@ impl<T: Field> One2ManyVerifier<T> {
     }
 
     pub fn set_final_value(&mut self, value: &Polynomial<T>) {
-        assert!(value.degree() <= 1 << (self.log_max_degree - self.total_round));
         self.final_value = Some(value.clone());
     }
 
+    pub fn set_pow_nonce(&mut self, nonce: u64) {
+        self.pow_nonce = nonce;
+    }
+
+    /// Coordinates of the open point not consumed by folding, including the
+    /// masking variables fixed to zero in hiding mode.
+    fn remaining_point(&self) -> Vec<T> {
+        let mut point = self.open_point[self.total_round.min(self.open_point.len())..].to_vec();
+        point.resize(
+            point.len() + self.params.mask_variable_num(),
+            T::from_int(0),
+        );
+        point
+    }
+
+    fn check_shape(
+        &self,
+        folding_proof: &Vec<QueryResult<T>>,
+        function_proof: &Vec<QueryResult<T>>,
+    ) -> Result<(), VerificationError> {
+        if self.prover_params != Some(self.params) {
+            return Err(VerificationError::ParameterMismatch);
+        }
+        let round_num = self.folding_rounds.len();
+        let function_num = if self.params.shared_trees {
+            1
+        } else {
+            round_num
+        };
+        if round_num == 0
+            || self.function_root.len() != function_num
+            || self.folding_root.len() != round_num - 1
+            || function_proof.len() != function_num
+            || folding_proof.len() != round_num - 1
+            || self.final_value.is_none()
+            || self.evaluation.as_ref().map(|x| x.variable_num())
+                != Some(self.remaining_point().len())
+        {
+            return Err(VerificationError::WrongProofShape);
+        }
+        let final_value = self.final_value.as_ref().unwrap();
+        if final_value.degree() > 1 << self.remaining_point().len() {
+            return Err(VerificationError::FinalPolynomialDegree);
+        }
+        Ok(())
+    }
+
+    fn replay_oracle(&self) -> Result<RandomOracle<T>, VerificationError> {
+        let mut oracle = self.oracle.clone();
+        oracle.absorb_root(&self.function_root[0].merkle_root);
+        oracle.absorb_field_elements(&self.open_point);
+        if let Some(evaluations) = &self.batch_evaluations {
+            oracle.absorb_field_elements(evaluations);
+            oracle.rlc_challenge();
+        }
+        for function in &self.function_root[1..] {
+            oracle.absorb_root(&function.merkle_root);
+        }
+        oracle.absorb_field_elements(self.evaluation.as_ref().unwrap().coefficients());
+        for (i, round) in self.folding_rounds.iter().enumerate() {
+            for step in round.clone() {
+                oracle.folding_challenge(step);
+            }
+            if i < self.folding_rounds.len() - 1 {
+                oracle.absorb_root(&self.folding_root[i].merkle_root);
+            }
+        }
+        oracle.absorb_field_elements(self.final_value.as_ref().unwrap().coefficients());
+        if !oracle.check_proof_of_work(self.pow_nonce) {
+            return Err(VerificationError::InvalidProofOfWork);
+        }
+        oracle.sample_queries();
+        Ok(oracle)
+    }
+
+    /// Powers of the combination challenge, one per committed polynomial.
+    fn batch_coefficients(&self, rlc: T) -> Vec<T> {
+        let batch_size = self.batch_evaluations.as_ref().map_or(1, |x| x.len());
+        let mut coefficients = vec![T::from_int(1)];
+        for i in 1..batch_size {
+            coefficients.push(coefficients[i - 1] * rlc);
+        }
+        coefficients
+    }
+
+    /// Splits the proofs of the shared layout into one proof per folding
+    /// and per function, without Merkle paths.
+    fn split_shared(
+        &self,
+        folding_proof: &Vec<QueryResult<T>>,
+        function_proof: &Vec<QueryResult<T>>,
+    ) -> (Vec<QueryResult<T>>, Vec<QueryResult<T>>) {
+        let mut foldings = vec![];
+        let mut functions = vec![function_proof[0].clone()];
+        for (i, proof) in folding_proof.iter().enumerate() {
+            let domain_size = self.interpolate_cosets[self.folding_rounds[i + 1].start].size();
+            foldings.push(proof.column(domain_size, 0));
+            functions.push(proof.column(domain_size, 1));
+        }
+        (foldings, functions)
+    }
+
     pub fn verify(
         &self,
         folding_proof: &Vec<QueryResult<T>>,
         function_proof: &Vec<QueryResult<T>>,
-    ) -> bool {
-        let mut leaf_indices = self.oracle.query_list.clone();
-        for i in 0..self.total_round {
-            let domain_size = self.interpolate_cosets[i].size();
-            leaf_indices = leaf_indices
-                .iter_mut()
-                .map(|v| *v % (domain_size >> 1))
-                .collect();
+    ) -> Result<(), VerificationError> {
+        self.check_shape(folding_proof, function_proof)?;
+        let shared = self.params.shared_trees;
+        let split;
+        let (folding_values, function_values) = if shared {
+            split = self.split_shared(folding_proof, function_proof);
+            (&split.0, &split.1)
+        } else {
+            (folding_proof, function_proof)
+        };
+        let final_value = self.final_value.as_ref().unwrap();
+        let evaluation = self.evaluation.as_ref().unwrap();
+        let oracle = self.replay_oracle()?;
+        let coefficients = self.batch_coefficients(oracle.rlc);
+        if let Some(evaluations) = &self.batch_evaluations {
+            let combined = evaluations
+                .iter()
+                .zip(&coefficients)
+                .fold(T::from_int(0), |acc, (y, c)| acc + *y * *c);
+            if evaluation.evaluate(&self.remaining_point()) != combined {
+                return Err(VerificationError::EvaluationMismatch);
+            }
+        }
+        let mut leaf_indices = oracle.query_list.clone();
+        let round_num = self.folding_rounds.len();
+        for (i, round) in self.folding_rounds.iter().enumerate() {
+            let domain_size = self.interpolate_cosets[round.start].size();
+            let arity = 1 << round.len();
+            let len = domain_size / arity;
+            leaf_indices = leaf_indices.iter_mut().map(|v| *v % len).collect();
             leaf_indices.sort();
             leaf_indices.dedup();
 
+            // The committed values lie in the base field and are hashed as
+            // such.
             if i == 0 {
-                function_proof[i].verify_merkle_tree(&leaf_indices, &self.function_root[i]);
-            } else {
-                folding_proof[i - 1].verify_merkle_tree(&leaf_indices, &self.folding_root[i - 1]);
+                function_proof[0]
+                    .to_base::<F>()
+                    .ok_or(VerificationError::WrongProofShape)?
+                    .verify_coset_merkle_tree(
+                        &leaf_indices,
+                        &self.function_root[0],
+                        coefficients.len(),
+                        arity,
+                        self.params.hiding,
+                    )?;
+            } else if !shared {
+                function_proof[i].verify_coset_merkle_tree(
+                    &leaf_indices,
+                    &self.function_root[i],
+                    1,
+                    arity,
+                    self.params.hiding,
+                )?;
             }
-
-            let challenge = self.oracle.folding_challenges[i];
-            let get_folding_value = if i == 0 {
-                &function_proof[i].proof_values
+            let combined;
+            let function = if i == 0 && self.batch_evaluations.is_some() {
+                combined = function_proof[0].linear_combination(domain_size, &coefficients)?;
+                &combined
             } else {
-                &folding_proof[i - 1].proof_values
+                &function_values[i]
             };
+            if i > 0 {
+                folding_proof[i - 1].verify_coset_merkle_tree(
+                    &leaf_indices,
+                    &self.folding_root[i - 1],
+                    if shared { 2 } else { 1 },
+                    arity,
+                    self.params.hiding,
+                )?;
+            }
 
-            let function_values = if i != 0 {
-                let function_query_result = &function_proof[i];
-                function_query_result.verify_merkle_tree(&leaf_indices, &self.function_root[i]);
-                Some(&function_query_result.proof_values)
-            } else {
-                None
-            };
             for j in &leaf_indices {
-                let x = get_folding_value[j];
-                let nx = get_folding_value[&(j + domain_size / 2)];
-                let v =
-                    x + nx + challenge * (x - nx) * self.interpolate_cosets[i].element_inv_at(*j);
-                if i != 0 {
-                    let x = function_values.as_ref().unwrap()[j];
-                    let nx = function_values.as_ref().unwrap()[&(j + domain_size / 2)];
-                    let v = (v * challenge + (x + nx)) * challenge
-                        + (x - nx) * self.interpolate_cosets[i].element_inv_at(*j);
-                    if i == self.total_round - 1 {
-                        let x = self.interpolate_cosets[i + 1].element_at(*j);
-                        if v != self.final_value.as_ref().unwrap().evaluation_at(x) {
-                            return false;
-                        }
-                    } else if v != folding_proof[i].proof_values[j] {
-                        return false;
-                    }
+                let coset_values = |proof: &QueryResult<T>| {
+                    (0..arity)
+                        .map(|t| proof.value_at(t * len + j))
+                        .collect::<Result<Vec<_>, _>>()
+                };
+                let mut function_value = coset_values(function)?;
+                let mut folding_value = if i == 0 {
+                    None
                 } else {
-                    if v != folding_proof[i].proof_values[j] {
-                        return false;
+                    Some(coset_values(&folding_values[i - 1])?)
+                };
+                // Folds the coset of leaf `j` through the steps of the round,
+                // the values at `u` and `u + half` being at `j + u * len` and
+                // its opposite in the domain of `step`.
+                for step in round.clone() {
+                    let challenge = oracle.folding_challenges[step];
+                    let half = function_value.len() / 2;
+                    let mut next_folding = vec![];
+                    let mut next_function = vec![];
+                    for u in 0..half {
+                        let twiddle =
+                            self.interpolate_cosets[step].compute_fold_twiddle(j + u * len);
+                        let folding = folding_value.as_ref().unwrap_or(&function_value);
+                        let (even, odd) = D::split(folding[u], folding[u + half], twiddle);
+                        let mut v = even + challenge * odd;
+                        let (even, odd) =
+                            D::split(function_value[u], function_value[u + half], twiddle);
+                        if step != 0 {
+                            v = (v * challenge + even) * challenge + odd;
+                        }
+                        next_folding.push(v);
+                        next_function.push(
+                            (even + self.open_point[step] * odd)
+                                * self.interpolate_cosets[step].fold_scale(),
+                        );
                     }
+                    folding_value = Some(next_folding);
+                    function_value = next_function;
                 }
-                let x = function_proof[i].proof_values[j];
-                let nx = function_proof[i].proof_values[&(j + domain_size / 2)];
-                let v = x
-                    + nx
-                    + self.open_point[i] * (x - nx) * self.interpolate_cosets[i].element_inv_at(*j);
-                if i < self.total_round - 1 {
-                    assert_eq!(v, function_proof[i + 1].proof_values[j] * T::from_int(2));
+
+                let (next_folding, next_function) = if i == round_num - 1 {
+                    let domain = &self.interpolate_cosets[round.end];
+                    (
+                        domain.evaluate_at(final_value.coefficients(), *j),
+                        domain.evaluate_at(evaluation.coefficients(), *j),
+                    )
                 } else {
-                    let x = self.interpolate_cosets[i + 1].element_at(*j);
-                    let poly_v = self.evaluation.as_ref().unwrap().evaluate_as_polynomial(x);
-                    assert_eq!(v, poly_v * T::from_int(2));
+                    (
+                        folding_values[i].value_at(*j)?,
+                        function_values[i + 1].value_at(*j)?,
+                    )
+                };
+                if folding_value.unwrap()[0] != next_folding || function_value[0] != next_function {
+                    return Err(VerificationError::FoldingMismatch(i));
                 }
             }
         }
-        true
+        Ok(())
     }
 }
@@ -1,4 +1,9 @@
-use super::{field::Field, polynomial::Polynomial};
+use super::{
+    domain::EvaluationDomain,
+    field::{ExtensionField, Field, BATCH_SIZE},
+    polynomial::Polynomial,
+};
+use crate::parallel;
 
 #[derive(Debug, Clone, Copy)]
 struct Radix2Domain<T: Field> {
@@ -31,9 +36,7 @@ impl<T: Field> Radix2Domain<T> {
     pub fn ifft(&self, a: &mut Vec<T>) {
         _fft(a, self.omega.inverse());
         let t = T::from_int(self.order as u64).inverse();
-        for i in a {
-            *i *= t;
-        }
+        parallel::for_each_mut(a, |_, x| *x *= t);
     }
 
     #[inline]
@@ -79,27 +82,80 @@ fn _fft<T: Field>(a: &mut Vec<T>, omega: T) {
     for _i in 0..log_n {
         let w_m = omega.pow(n >> (log_m + 1));
         let m = 1 << log_m;
-        for j in (0..n).step_by(m * 2) {
-            let mut w = T::from_int(1);
-            for k in 0..m {
-                let t = w * a[j + k + m];
-                a[j + k + m] = a[j + k] - t;
-                a[j + k] += t;
-                w *= w_m;
+        let mut twiddles = Vec::with_capacity(m);
+        let mut w = T::from_int(1);
+        for _k in 0..m {
+            twiddles.push(w);
+            w *= w_m;
+        }
+        // `(x, y) = (x + t, x - t)` with `t = w * y`, by chunks so that the
+        // batched operations of the field apply.
+        let butterflies = |start: usize, low: &mut [T], high: &mut [T]| {
+            let mut products = [T::ZERO; BATCH_SIZE];
+            for (k, (x, y)) in low
+                .chunks_mut(BATCH_SIZE)
+                .zip(high.chunks_mut(BATCH_SIZE))
+                .enumerate()
+            {
+                let offset = start + k * BATCH_SIZE;
+                let t = &mut products[..y.len()];
+                t.copy_from_slice(y);
+                T::mul_assign_slice(t, &twiddles[offset..offset + y.len()]);
+                y.copy_from_slice(x);
+                T::sub_assign_slice(y, t);
+                T::add_assign_slice(x, t);
+            }
+        };
+        // Spread the blocks over the threads while there are enough of them,
+        // and the butterflies of each block in the last few layers.
+        if n / (m * 2) >= parallel::num_threads() {
+            parallel::for_each_chunk_mut(a, m * 2, |_, block| {
+                let (low, high) = block.split_at_mut(m);
+                butterflies(0, low, high);
+            });
+        } else {
+            for block in a.chunks_mut(m * 2) {
+                let (low, high) = block.split_at_mut(m);
+                parallel::zip_chunks_mut(low, high, BATCH_SIZE, butterflies);
             }
         }
         log_m += 1;
     }
 }
 
-use std::{cell::RefCell, rc::Rc};
+use std::sync::{Arc, OnceLock};
+
+/// `shift^-1` and `omega^(2^k)`, `omega^(-2^k)` for every bit of the order.
+#[derive(Debug)]
+struct PowerLadder<T: Field> {
+    shift_inv: T,
+    omega: Vec<T>,
+    omega_inv: Vec<T>,
+}
+
+impl<T: Field> PowerLadder<T> {
+    fn product(start: T, ladder: &[T], index: usize) -> T {
+        let mut res = start;
+        for (k, x) in ladder.iter().enumerate() {
+            if (index >> k) & 1 == 1 {
+                res *= *x;
+            }
+        }
+        res
+    }
+}
 
+/// The element tables are computed on first use and shared between clones,
+/// so a `Coset` can be cloned into every prover and verifier cheaply.
 #[derive(Debug, Clone)]
 pub struct Coset<T: Field> {
-    elements: Rc<RefCell<Vec<T>>>,
-    elements_inv: Rc<RefCell<Vec<T>>>,
+    elements: Arc<OnceLock<Vec<T>>>,
+    elements_inv: Arc<OnceLock<Vec<T>>>,
+    ladder: Arc<OnceLock<PowerLadder<T>>>,
     fft_eval_domain: Radix2Domain<T>,
     shift: T,
+    /// `1 / 2`, kept here rather than in `Field` as binary fields have none.
+    inverse_2: T,
 }
 
 impl<T: Field> Coset<T> {
@@ -133,10 +189,12 @@ impl<T: Field> Coset<T> {
         assert!(!shift.is_zero());
         let omega = T::get_generator(order);
         Coset {
-            elements: Rc::new(RefCell::new(vec![])),
-            elements_inv: Rc::new(RefCell::new(vec![])),
+            elements: Arc::new(OnceLock::new()),
+            elements_inv: Arc::new(OnceLock::new()),
+            ladder: Arc::new(OnceLock::new()),
             fft_eval_domain: Radix2Domain::new(order, omega),
             shift,
+            inverse_2: T::from_int(2).inverse(),
         }
     }
 
@@ -148,10 +206,12 @@ impl<T: Field> Coset<T> {
         let lowbit = (index as i64 & (-(index as i64))) as usize;
         let omega = self.generator().pow(index);
         Coset {
-            elements: Rc::new(RefCell::new(vec![])),
-            elements_inv: Rc::new(RefCell::new(vec![])),
+            elements: Arc::new(OnceLock::new()),
+            elements_inv: Arc::new(OnceLock::new()),
+            ladder: Arc::new(OnceLock::new()),
             fft_eval_domain: Radix2Domain::new(self.order() / lowbit, omega),
             shift: self.shift.pow(index),
+            inverse_2: self.inverse_2,
         }
     }
 
@@ -159,59 +219,90 @@ impl<T: Field> Coset<T> {
         self.fft_eval_domain.omega
     }
 
-    pub fn element_at(&self, index: usize) -> T {
-        let mut elements = self.elements.borrow_mut();
-        if elements.len() == 0 {
-            let mut el = self.shift;
-            let omega = self.generator();
-            for _i in 0..self.fft_eval_domain.order() {
-                elements.push(el);
-                el *= omega;
-            }
-            elements[index]
-        } else {
-            elements[index]
+    /// Scale of the folding, see `fold_scale`.
+    pub fn inverse_2(&self) -> T {
+        self.inverse_2
+    }
+
+    fn powers(start: T, step: T, n: usize) -> Vec<T> {
+        let mut res = Vec::with_capacity(n);
+        let mut el = start;
+        for _i in 0..n {
+            res.push(el);
+            el *= step;
         }
+        res
     }
 
-    pub fn element_inv_at(&self, index: usize) -> T {
-        let mut elements_inv = self.elements_inv.borrow_mut();
-        if elements_inv.len() == 0 {
-            let mut el = self.shift.inverse();
+    /// All elements in order, starting from the shift.
+    pub fn elements(&self) -> &Vec<T> {
+        self.elements
+            .get_or_init(|| Self::powers(self.shift, self.fft_eval_domain.omega(), self.size()))
+    }
+
+    /// Inverses of `elements()`.
+    pub fn elements_inv(&self) -> &Vec<T> {
+        self.elements_inv.get_or_init(|| {
             let omega_inv = self.generator().pow(self.order() - 1);
-            for _i in 0..self.fft_eval_domain.order() {
-                elements_inv.push(el);
-                el *= omega_inv;
+            Self::powers(self.shift.inverse(), omega_inv, self.size())
+        })
+    }
+
+    pub fn element_at(&self, index: usize) -> T {
+        self.elements()[index]
+    }
+
+    pub fn element_inv_at(&self, index: usize) -> T {
+        self.elements_inv()[index]
+    }
+
+    fn ladder(&self) -> &PowerLadder<T> {
+        self.ladder.get_or_init(|| {
+            let squares = |mut x: T| {
+                (0..self.order().trailing_zeros())
+                    .map(|_| {
+                        let res = x;
+                        x *= x;
+                        res
+                    })
+                    .collect()
+            };
+            PowerLadder {
+                shift_inv: self.shift.inverse(),
+                omega: squares(self.generator()),
+                omega_inv: squares(self.generator().inverse()),
             }
-            elements_inv[index]
-        } else {
-            elements_inv[index]
+        })
+    }
+
+    /// Same as `element_at`, but with `O(log order)` multiplications instead
+    /// of building the element table, which is what verifiers need.
+    pub fn compute_element_at(&self, index: usize) -> T {
+        debug_assert!(index < self.size());
+        match self.elements.get() {
+            Some(elements) => elements[index],
+            None => PowerLadder::product(self.shift, &self.ladder().omega, index),
         }
     }
 
-    pub fn all_elements_inv(&self) -> Vec<T> {
-        let mut elements_inv = self.elements_inv.borrow_mut();
-        if elements_inv.len() == 0 {
-            let mut el = self.shift.inverse();
-            let omega_inv = self.generator().pow(self.order() - 1);
-            for _i in 0..self.fft_eval_domain.order() {
-                elements_inv.push(el);
-                el *= omega_inv;
+    /// Same as `element_inv_at`, see `compute_element_at`.
+    pub fn compute_element_inv_at(&self, index: usize) -> T {
+        debug_assert!(index < self.size());
+        match self.elements_inv.get() {
+            Some(elements_inv) => elements_inv[index],
+            None => {
+                let ladder = self.ladder();
+                PowerLadder::product(ladder.shift_inv, &ladder.omega_inv, index)
             }
         }
-        elements_inv.clone()
+    }
+
+    pub fn all_elements_inv(&self) -> Vec<T> {
+        self.elements_inv().clone()
     }
 
     pub fn all_elements(&self) -> Vec<T> {
-        let mut elements = self.elements.borrow_mut();
-        if elements.len() == 0 {
-            let mut el = self.shift;
-            for _i in 0..self.fft_eval_domain.order() {
-                elements.push(el);
-                el *= self.fft_eval_domain.omega();
-            }
-        }
-        elements.clone()
+        self.elements().clone()
     }
 
     pub fn size(&self) -> usize {
@@ -239,6 +330,92 @@ impl<T: Field> Coset<T> {
     pub fn shift(&self) -> T {
         self.shift
     }
+
+    /// The coset as one of the subfield `F`, if it lies in it.
+    pub fn to_base<F: Field>(&self) -> Option<Coset<F>>
+    where
+        T: ExtensionField<F>,
+    {
+        let shift = self.shift.to_base()?;
+        if self.size().trailing_zeros() as u64 > F::LOG_ORDER {
+            return None;
+        }
+        let res = Coset::new(self.size(), shift);
+        (T::from_base(res.generator()) == self.generator()).then_some(res)
+    }
+}
+
+impl<T: Field> EvaluationDomain<T> for Coset<T> {
+    fn with_shift(log_size: usize, shift: T) -> Option<Self> {
+        (log_size as u64 <= T::LOG_ORDER).then(|| Coset::new(1 << log_size, shift))
+    }
+
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn folded(&self) -> Self {
+        self.pow(2)
+    }
+
+    fn compute_element_at(&self, index: usize) -> T {
+        self.compute_element_at(index)
+    }
+
+    fn fft(&self, coefficients: Vec<T>) -> Vec<T> {
+        self.fft(coefficients)
+    }
+
+    fn ifft(&self, evaluations: Vec<T>) -> Vec<T> {
+        self.ifft(evaluations)
+    }
+
+    fn lies_in<F: Field>(&self) -> bool
+    where
+        T: ExtensionField<F>,
+    {
+        self.to_base::<F>().is_some()
+    }
+
+    fn fft_base<F: Field>(&self, coefficients: Vec<F>) -> Option<Vec<F>>
+    where
+        T: ExtensionField<F>,
+    {
+        Some(self.to_base::<F>()?.fft(coefficients))
+    }
+
+    fn ifft_base<F: Field>(&self, evaluations: Vec<F>) -> Option<Vec<F>>
+    where
+        T: ExtensionField<F>,
+    {
+        Some(self.to_base::<F>()?.ifft(evaluations))
+    }
+
+    fn evaluate_at(&self, coefficients: &[T], index: usize) -> T {
+        let x = self.compute_element_at(index);
+        coefficients
+            .iter()
+            .rev()
+            .fold(T::from_int(0), |acc, c| acc * x + *c)
+    }
+
+    fn fold_twiddles(&self) -> &[T] {
+        self.elements_inv()
+    }
+
+    fn compute_fold_twiddle(&self, index: usize) -> T {
+        self.compute_element_inv_at(index)
+    }
+
+    /// With `f(x) = e(x^2) + x * o(x^2)`, `c = 2` and the twiddle is `1 / x`.
+    #[inline]
+    fn split(x: T, nx: T, twiddle: T) -> (T, T) {
+        (x + nx, (x - nx) * twiddle)
+    }
+
+    fn fold_scale(&self) -> T {
+        self.inverse_2
+    }
 }
 
 #[cfg(test)]
@@ -294,6 +471,42 @@ mod tests {
         assert_eq!(*elements.last().unwrap() * omega, elements[0]);
     }
 
+    #[test]
+    fn shared_between_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Coset<Fp64>>();
+
+        let coset = Coset::new(64, Fp64::random_element());
+        let copy = coset.clone();
+        let inverses = std::thread::spawn(move || copy.all_elements_inv())
+            .join()
+            .unwrap();
+        // The table computed on the other thread is shared with the original.
+        assert_eq!(coset.elements_inv.get(), Some(&inverses));
+        for (x, y) in coset.all_elements().iter().zip(&inverses) {
+            assert_eq!(*x * *y, Fp64::from_int(1));
+        }
+    }
+
+    #[test]
+    fn compute_elements() {
+        let coset = Coset::new(1 << 10, Mersenne61Ext::random_element());
+        let copy = coset.pow(4);
+        for i in [0, 1, 2, 3, 255, 256, 511, 1000, 1023] {
+            assert_eq!(
+                coset.compute_element_at(i),
+                coset.shift() * coset.generator().pow(i)
+            );
+            assert_eq!(
+                coset.compute_element_at(i) * coset.compute_element_inv_at(i),
+                Mersenne61Ext::from_int(1)
+            );
+            assert_eq!(copy.compute_element_at(i % 256), copy.element_at(i % 256));
+        }
+        // No table was built for the original coset.
+        assert!(coset.elements.get().is_none() && coset.elements_inv.get().is_none());
+    }
+
     #[test]
     fn pow() {
         let shift = Mersenne61Ext::random_element();
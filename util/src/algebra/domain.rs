@@ -0,0 +1,64 @@
+use super::field::{ExtensionField, Field};
+
+/// Evaluation domain of the Reed-Solomon codewords folded by PolyFRIM,
+/// either a multiplicative `Coset` or an additive `AffineSubspace`.
+///
+/// Polynomials over a domain are written in its basis, where the `j`-th
+/// basis polynomial is the product of `b_i` over the bits `i` of `j`, `b_i`
+/// being the first basis polynomial `b` of the domain folded `i` times. For
+/// a coset `b(x) = x` and this is the monomial basis. Folding maps elements
+/// `i` and `i + size / 2` to element `i` of the folded domain, and a
+/// function `f = e(q) + b * o(q)`, `q` the folding map, to `e + r * o`,
+/// consuming the lowest bit of the basis indices as a multilinear variable.
+pub trait EvaluationDomain<T: Field>: Clone + std::fmt::Debug + Send + Sync {
+    /// Domain of `2^log_size` elements with `shift`, if the field has one.
+    fn with_shift(log_size: usize, shift: T) -> Option<Self>;
+
+    fn size(&self) -> usize;
+
+    /// Image of the domain by the folding map, of half the size.
+    fn folded(&self) -> Self;
+
+    /// Element `index`, computed without building a table.
+    fn compute_element_at(&self, index: usize) -> T;
+
+    /// Values over the domain of the polynomial with `coefficients` in the
+    /// basis of the domain.
+    fn fft(&self, coefficients: Vec<T>) -> Vec<T>;
+
+    fn ifft(&self, evaluations: Vec<T>) -> Vec<T>;
+
+    /// Whether the domain lies in the subfield `F`, as `fft_base` needs.
+    fn lies_in<F: Field>(&self) -> bool
+    where
+        T: ExtensionField<F>;
+
+    /// Same as `fft` for coefficients in a subfield `F`, computed in `F`,
+    /// or `None` if the domain does not lie in `F`.
+    fn fft_base<F: Field>(&self, coefficients: Vec<F>) -> Option<Vec<F>>
+    where
+        T: ExtensionField<F>;
+
+    fn ifft_base<F: Field>(&self, evaluations: Vec<F>) -> Option<Vec<F>>
+    where
+        T: ExtensionField<F>;
+
+    /// Value at element `index` of the polynomial with `coefficients` in the
+    /// basis of the domain.
+    fn evaluate_at(&self, coefficients: &[T], index: usize) -> T;
+
+    /// Twiddle `t_i` of the pair of elements `i` and `i + size / 2`, for
+    /// `i < size / 2`, see `split`.
+    fn fold_twiddles(&self) -> &[T];
+
+    /// Same as `fold_twiddles()[index]` without building the table.
+    fn compute_fold_twiddle(&self, index: usize) -> T;
+
+    /// `(c * e, c * o)` at the image of the pair with values `x` and `nx`
+    /// and twiddle `twiddle`, for a constant `c` of the domain type, so that
+    /// folding by `r` gives `(c * e + r * c * o) * fold_scale()`.
+    fn split(x: T, nx: T, twiddle: T) -> (T, T);
+
+    /// `1 / c`, see `split`.
+    fn fold_scale(&self) -> T;
+}
@@ -1,5 +1,15 @@
+pub mod babybear;
+pub mod binary_tower;
+pub mod extension;
 pub mod fp64;
+pub mod koalabear;
+pub mod mersenne31;
+pub mod mersenne31_ext;
+pub mod mersenne61;
 pub mod mersenne61_ext;
+pub mod prime31;
+
+use crate::params::FieldChoice;
 
 pub trait Field:
     Sized
@@ -15,16 +25,51 @@ pub trait Field:
     + std::cmp::PartialEq
     + std::fmt::Display
     + std::fmt::Debug
+    + Send
+    + Sync
 {
     const LOG_ORDER: u64;
     const ROOT_OF_UNITY: Self;
-    const INVERSE_2: Self;
+    const ZERO: Self;
+    const BYTE_SIZE: usize;
+    const FIELD_CHOICE: FieldChoice;
+    /// Bit length of the characteristic, so `from_int` is injective on the
+    /// integers below `2^(CHARACTERISTIC_BITS - 1)`.
+    const CHARACTERISTIC_BITS: u32;
 
     fn from_int(x: u64) -> Self;
     fn random_element() -> Self;
+    fn from_uniform_bytes(bytes: &[u8; 32]) -> Self;
     fn inverse(&self) -> Self;
     fn is_zero(&self) -> bool;
     fn to_bytes(&self) -> Vec<u8>;
+    fn from_bytes(bytes: &[u8]) -> Option<Self>;
+
+    /// `xs[i] += ys[i]` for every `i`. The FFTs and the foldings are
+    /// written with these batched operations, so a field with vectorized
+    /// arithmetic only needs to override them.
+    fn add_assign_slice(xs: &mut [Self], ys: &[Self]) {
+        assert_eq!(xs.len(), ys.len());
+        for (x, y) in xs.iter_mut().zip(ys) {
+            *x += *y;
+        }
+    }
+
+    /// `xs[i] -= ys[i]` for every `i`.
+    fn sub_assign_slice(xs: &mut [Self], ys: &[Self]) {
+        assert_eq!(xs.len(), ys.len());
+        for (x, y) in xs.iter_mut().zip(ys) {
+            *x -= *y;
+        }
+    }
+
+    /// `xs[i] *= ys[i]` for every `i`.
+    fn mul_assign_slice(xs: &mut [Self], ys: &[Self]) {
+        assert_eq!(xs.len(), ys.len());
+        for (x, y) in xs.iter_mut().zip(ys) {
+            *x *= *y;
+        }
+    }
 
     fn get_generator(order: usize) -> Self {
         if (order & (order - 1)) != 0 || order > (1 << Self::LOG_ORDER) {
@@ -54,6 +99,61 @@ pub trait Field:
     }
 }
 
+/// Field containing `Base` as a subfield, of dimension `DEGREE` over it.
+/// Data can then live in `Base` while challenges are drawn from the
+/// extension, with mixed products cheaper than full ones.
+pub trait ExtensionField<Base: Field>: Field {
+    const DEGREE: usize;
+
+    fn from_base(x: Base) -> Self;
+
+    /// The element as one of `Base`, if it lies in the subfield.
+    fn to_base(&self) -> Option<Base>;
+
+    #[inline]
+    fn mul_base(&self, x: Base) -> Self {
+        *self * Self::from_base(x)
+    }
+}
+
+impl<T: Field> ExtensionField<T> for T {
+    const DEGREE: usize = 1;
+
+    #[inline]
+    fn from_base(x: T) -> Self {
+        x
+    }
+
+    #[inline]
+    fn to_base(&self) -> Option<T> {
+        Some(*self)
+    }
+
+    #[inline]
+    fn mul_base(&self, x: T) -> Self {
+        *self * x
+    }
+}
+
+/// Field of odd characteristic, in which `2` is invertible. Protocols that
+/// halve values, such as the folding of multiplicative cosets, need it.
+pub trait OddCharacteristicField: Field {
+    const INVERSE_2: Self;
+}
+
+/// Field of characteristic 2, a vector space of dimension `BITS` over
+/// `GF(2)`. Its evaluation domains are affine subspaces over `GF(2)`.
+pub trait BinaryField: Field {
+    const BITS: usize;
+
+    /// The `i`-th element of a basis over `GF(2)`.
+    fn basis(i: usize) -> Self;
+}
+
+/// Length of the chunks the batched operations are applied to, small
+/// enough for buffers on the stack.
+pub const BATCH_SIZE: usize = 64;
+
 #[inline]
 pub fn as_bytes_vec<T: Field>(s: &[T]) -> Vec<u8> {
     let mut res = vec![];
@@ -79,6 +179,7 @@ pub fn batch_inverse<T: Field>(v: &Vec<T>) -> Vec<T> {
     res
 }
 
+#[cfg(test)]
 mod field_tests {
     use super::*;
 
@@ -89,6 +190,8 @@ mod field_tests {
             let c = a + b - a;
             assert!(b == c)
         }
+        assert!(T::ZERO.is_zero());
+        assert_eq!(T::ZERO, T::from_int(0));
     }
 
     pub fn mult_and_inverse<T: Field>() {
@@ -98,7 +201,22 @@ mod field_tests {
             assert_eq!(a * b, T::from_int(1));
             assert_eq!(b * a, T::from_int(1));
         }
-        assert_eq!(T::INVERSE_2 * T::from_int(2), T::from_int(1));
+    }
+
+    pub fn bytes_round_trip<T: Field>() {
+        for _i in 0..100 {
+            let a = T::random_element();
+            let bytes = a.to_bytes();
+            assert_eq!(bytes.len(), T::BYTE_SIZE);
+            assert_eq!(T::from_bytes(&bytes), Some(a));
+        }
+        // Only the encoding of an element is accepted, so this is rejected
+        // unless every byte string of the size encodes one.
+        let ones = vec![0xff; T::BYTE_SIZE];
+        if let Some(a) = T::from_bytes(&ones) {
+            assert_eq!(a.to_bytes(), ones);
+        }
+        assert_eq!(T::from_bytes(&vec![0; T::BYTE_SIZE + 1]), None);
     }
 
     pub fn assigns<T: Field>() {
@@ -118,10 +236,47 @@ mod field_tests {
         }
     }
 
+    pub fn extension<B: Field, T: ExtensionField<B>>() {
+        for _i in 0..100 {
+            let (a, b) = (B::random_element(), B::random_element());
+            let x = T::random_element();
+            assert_eq!(T::from_base(a) + T::from_base(b), T::from_base(a + b));
+            assert_eq!(T::from_base(a) * T::from_base(b), T::from_base(a * b));
+            assert_eq!(x.mul_base(a), x * T::from_base(a));
+            assert_eq!(T::from_base(a).to_base(), Some(a));
+            assert_eq!(T::from_base(a).to_bytes().len(), T::BYTE_SIZE);
+        }
+        assert_eq!(T::from_base(B::ZERO), T::ZERO);
+    }
+
+    pub fn inverse_2<B: OddCharacteristicField, T: ExtensionField<B> + OddCharacteristicField>() {
+        assert_eq!(T::INVERSE_2 * T::from_int(2), T::from_int(1));
+        assert_eq!(T::from_base(B::INVERSE_2), T::INVERSE_2);
+    }
+
+    pub fn batched<T: Field>() {
+        for len in [0, 1, 7, BATCH_SIZE + 3] {
+            let xs = (0..len).map(|_| T::random_element()).collect::<Vec<_>>();
+            let ys = (0..len).map(|_| T::random_element()).collect::<Vec<_>>();
+            let (mut sum, mut difference, mut product) = (xs.clone(), xs.clone(), xs.clone());
+            T::add_assign_slice(&mut sum, &ys);
+            T::sub_assign_slice(&mut difference, &ys);
+            T::mul_assign_slice(&mut product, &ys);
+            for i in 0..len {
+                assert_eq!(sum[i], xs[i] + ys[i]);
+                assert_eq!(difference[i], xs[i] - ys[i]);
+                assert_eq!(product[i], xs[i] * ys[i]);
+            }
+        }
+    }
+
     pub fn pow_and_generator<T: Field>() {
         assert_eq!(T::get_generator(1), T::from_int(1));
-        let x = T::get_generator(1 << 32);
-        assert_eq!(x.pow(1 << 32), T::from_int(1));
-        assert_ne!(x.pow(1 << 31), T::from_int(1));
+        let order = 1 << T::LOG_ORDER;
+        let x = T::get_generator(order);
+        assert_eq!(x.pow(order), T::from_int(1));
+        if order > 1 {
+            assert_ne!(x.pow(order / 2), T::from_int(1));
+        }
     }
 }
@@ -0,0 +1,39 @@
+use super::extension::QuarticBase;
+use super::prime31::{Prime31, Prime31Params};
+use super::FieldChoice;
+
+#[derive(Debug, Clone, Copy)]
+pub struct BabyBearParams;
+
+impl Prime31Params for BabyBearParams {
+    const MOD: u32 = 2013265921;
+    const LOG_ORDER: u64 = 27;
+    const ROOT_OF_UNITY: u32 = 1227303670;
+    const FIELD_CHOICE: FieldChoice = FieldChoice::BabyBear;
+}
+
+/// The prime field of `2^31 - 2^27 + 1` elements, with a two-adic subgroup of
+/// order `2^27`.
+pub type BabyBear = Prime31<BabyBearParams>;
+
+/// `11` is not a square modulo `2^31 - 2^27 + 1`.
+impl QuarticBase for BabyBear {
+    const QUARTIC_NON_RESIDUE: Self = BabyBear::new(11);
+    const QUARTIC_FIELD_CHOICE: FieldChoice = FieldChoice::BabyBearQuartic;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::field_tests::*;
+    use super::*;
+
+    #[test]
+    fn test() {
+        add_and_sub::<BabyBear>();
+        mult_and_inverse::<BabyBear>();
+        inverse_2::<BabyBear, BabyBear>();
+        assigns::<BabyBear>();
+        pow_and_generator::<BabyBear>();
+        bytes_round_trip::<BabyBear>();
+    }
+}
@@ -0,0 +1,290 @@
+use super::{BinaryField, Field, FieldChoice};
+use rand::Rng;
+use std::sync::OnceLock;
+
+/// The field of `2^128` elements built as the tower `T_0 = GF(2)`,
+/// `T_{k+1} = T_k[X_k] / (X_k^2 + X_{k-1} * X_k + 1)` with `X_{-1} = 1`.
+/// An element of `T_{k+1}` is stored as its low and high halves `a0` and
+/// `a1` in `T_k`, standing for `a0 + a1 * X_k`, so the bits are its
+/// coordinates over `GF(2)` and addition is a xor.
+///
+/// The multiplicative group has odd order, so there are no multiplicative
+/// evaluation domains, see `AffineSubspace` instead.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinaryTower128 {
+    real: u128,
+}
+
+/// Products in the subfield `T_3` of 256 elements, where the recursion of
+/// `mul_bits` stops.
+fn mul_table() -> &'static Vec<u8> {
+    static TABLE: OnceLock<Vec<u8>> = OnceLock::new();
+    TABLE.get_or_init(|| {
+        (0..1 << 16)
+            .map(|i: u128| mul_small(i & 0xff, i >> 8, 8) as u8)
+            .collect()
+    })
+}
+
+/// Product of `x` by `X_{k-1}` in `T_k`, `bits = 2^k`.
+fn mul_by_generator(x: u128, bits: u32) -> u128 {
+    if bits == 1 {
+        return x;
+    }
+    let half = bits / 2;
+    let (x0, x1) = (x & ((1 << half) - 1), x >> half);
+    ((x0 ^ mul_by_generator(x1, half)) << half) | x1
+}
+
+/// Product in `T_k`, `bits = 2^k`, by Karatsuba on the halves with
+/// `mul_half` the product in `T_{k-1}`. With `X = X_k`,
+/// `(a0 + a1 X)(b0 + b1 X) = a0 b0 + a1 b1 + (a0 b1 + a1 b0 + a1 b1 X_{k-1}) X`.
+fn karatsuba(a: u128, b: u128, bits: u32, mul_half: impl Fn(u128, u128) -> u128) -> u128 {
+    let half = bits / 2;
+    let mask = (1 << half) - 1;
+    let (a0, a1) = (a & mask, a >> half);
+    let (b0, b1) = (b & mask, b >> half);
+    let low = mul_half(a0, b0);
+    let high = mul_half(a1, b1);
+    let middle = mul_half(a0 ^ a1, b0 ^ b1);
+    let res_high = middle ^ low ^ high ^ mul_by_generator(high, half);
+    (res_high << half) | (low ^ high)
+}
+
+fn mul_small(a: u128, b: u128, bits: u32) -> u128 {
+    if bits == 1 {
+        return a & b;
+    }
+    karatsuba(a, b, bits, |x, y| mul_small(x, y, bits / 2))
+}
+
+/// Product in `T_k`, `bits = 2^k`.
+fn mul_bits(a: u128, b: u128, bits: u32) -> u128 {
+    if bits <= 8 {
+        return mul_table()[(a | b << 8) as usize] as u128;
+    }
+    karatsuba(a, b, bits, |x, y| mul_bits(x, y, bits / 2))
+}
+
+/// Inverse in `T_k`, `bits = 2^k`. For `a = a0 + a1 * X`, the product by
+/// the conjugate `a0 + a1 * X_{k-1} + a1 * X` is the norm
+/// `a0^2 + a0 * a1 * X_{k-1} + a1^2` in `T_{k-1}`.
+fn inverse_bits(a: u128, bits: u32) -> u128 {
+    if bits == 1 {
+        return a;
+    }
+    let half = bits / 2;
+    let (a0, a1) = (a & ((1 << half) - 1), a >> half);
+    let conjugate_low = a0 ^ mul_by_generator(a1, half);
+    let norm = mul_bits(a0, conjugate_low, half) ^ mul_bits(a1, a1, half);
+    let norm_inv = inverse_bits(norm, half);
+    (mul_bits(a1, norm_inv, half) << half) | mul_bits(conjugate_low, norm_inv, half)
+}
+
+impl std::ops::Neg for BinaryTower128 {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        self
+    }
+}
+
+impl std::ops::Add for BinaryTower128 {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn add(self, rhs: Self) -> Self::Output {
+        BinaryTower128 {
+            real: self.real ^ rhs.real,
+        }
+    }
+}
+
+impl std::ops::AddAssign for BinaryTower128 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::Sub for BinaryTower128 {
+    type Output = Self;
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + rhs
+    }
+}
+
+impl std::ops::SubAssign for BinaryTower128 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl std::ops::Mul for BinaryTower128 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        BinaryTower128 {
+            real: mul_bits(self.real, rhs.real, 128),
+        }
+    }
+}
+
+impl std::ops::MulAssign for BinaryTower128 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl std::fmt::Display for BinaryTower128 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:#034x}", self.real)
+    }
+}
+
+impl Field for BinaryTower128 {
+    const LOG_ORDER: u64 = 0;
+    const ROOT_OF_UNITY: Self = BinaryTower128 { real: 1 };
+    const ZERO: Self = BinaryTower128 { real: 0 };
+    const BYTE_SIZE: usize = 16;
+    const FIELD_CHOICE: FieldChoice = FieldChoice::BinaryTower128;
+    const CHARACTERISTIC_BITS: u32 = 2;
+
+    /// The image of `x` in the field, which has characteristic 2.
+    #[inline]
+    fn from_int(x: u64) -> Self {
+        BinaryTower128 {
+            real: (x & 1) as u128,
+        }
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.real == 0
+    }
+
+    #[inline]
+    fn random_element() -> Self {
+        BinaryTower128 {
+            real: rand::thread_rng().gen(),
+        }
+    }
+
+    #[inline]
+    fn from_uniform_bytes(bytes: &[u8; 32]) -> Self {
+        BinaryTower128 {
+            real: u128::from_le_bytes(bytes[..16].try_into().unwrap()),
+        }
+    }
+
+    fn inverse(&self) -> Self {
+        BinaryTower128 {
+            real: inverse_bits(self.real, 128),
+        }
+    }
+
+    #[inline]
+    fn to_bytes(&self) -> Vec<u8> {
+        self.real.to_le_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        Some(BinaryTower128 {
+            real: u128::from_le_bytes(bytes.try_into().ok()?),
+        })
+    }
+}
+
+impl BinaryField for BinaryTower128 {
+    const BITS: usize = 128;
+
+    #[inline]
+    fn basis(i: usize) -> Self {
+        assert!(i < Self::BITS);
+        BinaryTower128 { real: 1 << i }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::field_tests::*;
+    use super::*;
+
+    #[test]
+    fn test() {
+        add_and_sub::<BinaryTower128>();
+        mult_and_inverse::<BinaryTower128>();
+        assigns::<BinaryTower128>();
+        pow_and_generator::<BinaryTower128>();
+        bytes_round_trip::<BinaryTower128>();
+        batched::<BinaryTower128>();
+        for _i in 0..100 {
+            let (a, b, c) = (
+                BinaryTower128::random_element(),
+                BinaryTower128::random_element(),
+                BinaryTower128::random_element(),
+            );
+            assert_eq!(a * b, b * a);
+            assert_eq!((a * b) * c, a * (b * c));
+            assert_eq!(a * (b + c), a * b + a * c);
+        }
+        assert_eq!(BinaryTower128::from_int(2), BinaryTower128::ZERO);
+    }
+
+    #[test]
+    fn inverse() {
+        let one = BinaryTower128::from_int(1);
+        assert_eq!(one.inverse(), one);
+        assert_eq!(BinaryTower128::ZERO.inverse(), BinaryTower128::ZERO);
+        // Elements of every subfield `T_k`, whose inverses stay in it.
+        for k in 0..8 {
+            let bits = 1u32 << k;
+            for _i in 0..20 {
+                let mut a = BinaryTower128::random_element();
+                if bits < 128 {
+                    a.real &= (1 << bits) - 1;
+                }
+                if a.is_zero() {
+                    continue;
+                }
+                let inv = a.inverse();
+                assert_eq!(a * inv, one);
+                assert_eq!(inv.inverse(), a);
+                if bits < 128 {
+                    assert!(inv.real < 1 << bits);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bytes() {
+        let a = BinaryTower128 {
+            real: 0x0f0e0d0c0b0a09080706050403020100,
+        };
+        assert_eq!(a.to_bytes(), (0..16).collect::<Vec<u8>>());
+        assert_eq!(BinaryTower128::from_bytes(&a.to_bytes()), Some(a));
+        // Every 16 bytes encode an element.
+        let ones = BinaryTower128::from_bytes(&[0xff; 16]).unwrap();
+        assert_eq!(ones.real, u128::MAX);
+        assert_eq!(BinaryTower128::from_bytes(&[0; 15]), None);
+        assert_eq!(BinaryTower128::from_bytes(&[0; 17]), None);
+    }
+
+    #[test]
+    fn tower() {
+        // X_k^2 = X_{k-1} * X_k + 1 at every level, `X_k` being the lowest
+        // bit of the high half of `T_{k+1}`.
+        let generator = |k: u32| BinaryTower128 {
+            real: 1 << (1 << k),
+        };
+        let one = BinaryTower128::from_int(1);
+        assert_eq!(generator(0) * generator(0), generator(0) + one);
+        for k in 1..7 {
+            let x = generator(k);
+            assert_eq!(x * x, generator(k - 1) * x + one);
+        }
+        // `T_k` is closed under the operations.
+        let a = BinaryTower128 { real: 0xbeef };
+        let b = BinaryTower128 { real: 0x1234 };
+        assert!((a * b).real < 1 << 16);
+        assert!(a.inverse().real < 1 << 16);
+    }
+}
@@ -0,0 +1,686 @@
+//! Extensions of degree 2, 3 and 4 of any field with a suitable non-residue.
+//!
+//! `QuadExt<F>` is `F[u] / (u^2 - W)` for a non-square `W` of `F`,
+//! `CubicExt<F>` is `F[u] / (u^3 - W)` for a non-cube `W`, and
+//! `QuarticExt<F>` is `F[u] / (u^4 - W)` for a non-square `W` when
+//! `4 | p - 1`. Their two-adic
+//! subgroup is taken to be the one of `F`, so that evaluation domains lie in
+//! the base field and polynomials over `F` can be committed as such, see
+//! `ExtensionField`.
+
+use super::{ExtensionField, Field, FieldChoice, OddCharacteristicField};
+
+/// Base field of `QuadExt`.
+pub trait QuadraticBase: Field {
+    /// A non-square, so that `u^2 - QUADRATIC_NON_RESIDUE` is irreducible.
+    const QUADRATIC_NON_RESIDUE: Self;
+    const QUADRATIC_FIELD_CHOICE: FieldChoice;
+}
+
+/// Base field of `CubicExt`, which needs `3 | p - 1`.
+pub trait CubicBase: Field {
+    /// A non-cube, so that `u^3 - CUBIC_NON_RESIDUE` is irreducible.
+    const CUBIC_NON_RESIDUE: Self;
+    const CUBIC_FIELD_CHOICE: FieldChoice;
+}
+
+/// Base field of `QuarticExt`, which needs `4 | p - 1`.
+pub trait QuarticBase: Field {
+    /// A non-square, so that `u^4 - QUARTIC_NON_RESIDUE` is irreducible.
+    const QUARTIC_NON_RESIDUE: Self;
+    const QUARTIC_FIELD_CHOICE: FieldChoice;
+}
+
+/// The `i`-th coordinate drawn from `bytes`, each one from its own hash so
+/// that they are independent whatever part of the bytes `F` uses.
+fn uniform_coordinate<F: Field>(bytes: &[u8; 32], i: u8) -> F {
+    F::from_uniform_bytes(blake3::hash(&[&bytes[..], &[i]].concat()).as_bytes())
+}
+
+/// `c0 + c1 * u` with `u^2 = F::QUADRATIC_NON_RESIDUE`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuadExt<F> {
+    c0: F,
+    c1: F,
+}
+
+impl<F: QuadraticBase> QuadExt<F> {
+    pub fn new(c0: F, c1: F) -> Self {
+        QuadExt { c0, c1 }
+    }
+}
+
+impl<F: QuadraticBase> std::ops::Neg for QuadExt<F> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        QuadExt {
+            c0: -self.c0,
+            c1: -self.c1,
+        }
+    }
+}
+
+impl<F: QuadraticBase> std::ops::Add for QuadExt<F> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        QuadExt {
+            c0: self.c0 + rhs.c0,
+            c1: self.c1 + rhs.c1,
+        }
+    }
+}
+
+impl<F: QuadraticBase> std::ops::AddAssign for QuadExt<F> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<F: QuadraticBase> std::ops::Sub for QuadExt<F> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        QuadExt {
+            c0: self.c0 - rhs.c0,
+            c1: self.c1 - rhs.c1,
+        }
+    }
+}
+
+impl<F: QuadraticBase> std::ops::SubAssign for QuadExt<F> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<F: QuadraticBase> std::ops::Mul for QuadExt<F> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        let (a, b) = (self.c0 * rhs.c0, self.c1 * rhs.c1);
+        QuadExt {
+            c0: a + F::QUADRATIC_NON_RESIDUE * b,
+            c1: (self.c0 + self.c1) * (rhs.c0 + rhs.c1) - a - b,
+        }
+    }
+}
+
+impl<F: QuadraticBase> std::ops::MulAssign for QuadExt<F> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<F: QuadraticBase> std::fmt::Display for QuadExt<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} + {} * u", self.c0, self.c1)
+    }
+}
+
+impl<F: QuadraticBase + OddCharacteristicField> OddCharacteristicField for QuadExt<F> {
+    const INVERSE_2: Self = QuadExt {
+        c0: F::INVERSE_2,
+        c1: F::ZERO,
+    };
+}
+
+impl<F: QuadraticBase> Field for QuadExt<F> {
+    const LOG_ORDER: u64 = F::LOG_ORDER;
+    const ROOT_OF_UNITY: Self = QuadExt {
+        c0: F::ROOT_OF_UNITY,
+        c1: F::ZERO,
+    };
+    const ZERO: Self = QuadExt {
+        c0: F::ZERO,
+        c1: F::ZERO,
+    };
+    const BYTE_SIZE: usize = 2 * F::BYTE_SIZE;
+    const FIELD_CHOICE: FieldChoice = F::QUADRATIC_FIELD_CHOICE;
+    const CHARACTERISTIC_BITS: u32 = F::CHARACTERISTIC_BITS;
+
+    #[inline]
+    fn from_int(x: u64) -> Self {
+        QuadExt {
+            c0: F::from_int(x),
+            c1: F::ZERO,
+        }
+    }
+
+    #[inline]
+    fn random_element() -> Self {
+        QuadExt {
+            c0: F::random_element(),
+            c1: F::random_element(),
+        }
+    }
+
+    fn from_uniform_bytes(bytes: &[u8; 32]) -> Self {
+        QuadExt {
+            c0: uniform_coordinate(bytes, 0),
+            c1: uniform_coordinate(bytes, 1),
+        }
+    }
+
+    /// `(c0 - c1 * u) / (c0^2 - W * c1^2)`.
+    fn inverse(&self) -> Self {
+        let norm = self.c0 * self.c0 - F::QUADRATIC_NON_RESIDUE * self.c1 * self.c1;
+        let norm_inv = norm.inverse();
+        QuadExt {
+            c0: self.c0 * norm_inv,
+            c1: -self.c1 * norm_inv,
+        }
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut x = self.c0.to_bytes();
+        x.extend(self.c1.to_bytes());
+        x
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::BYTE_SIZE {
+            return None;
+        }
+        let (c0, c1) = bytes.split_at(F::BYTE_SIZE);
+        Some(QuadExt {
+            c0: F::from_bytes(c0)?,
+            c1: F::from_bytes(c1)?,
+        })
+    }
+}
+
+impl<F: QuadraticBase> ExtensionField<F> for QuadExt<F> {
+    const DEGREE: usize = 2;
+
+    #[inline]
+    fn from_base(x: F) -> Self {
+        QuadExt { c0: x, c1: F::ZERO }
+    }
+
+    #[inline]
+    fn to_base(&self) -> Option<F> {
+        if self.c1.is_zero() {
+            Some(self.c0)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn mul_base(&self, x: F) -> Self {
+        QuadExt {
+            c0: self.c0 * x,
+            c1: self.c1 * x,
+        }
+    }
+}
+
+/// `c0 + c1 * u + c2 * u^2` with `u^3 = F::CUBIC_NON_RESIDUE`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CubicExt<F> {
+    c0: F,
+    c1: F,
+    c2: F,
+}
+
+impl<F: CubicBase> CubicExt<F> {
+    pub fn new(c0: F, c1: F, c2: F) -> Self {
+        CubicExt { c0, c1, c2 }
+    }
+}
+
+impl<F: CubicBase> std::ops::Neg for CubicExt<F> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        CubicExt {
+            c0: -self.c0,
+            c1: -self.c1,
+            c2: -self.c2,
+        }
+    }
+}
+
+impl<F: CubicBase> std::ops::Add for CubicExt<F> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        CubicExt {
+            c0: self.c0 + rhs.c0,
+            c1: self.c1 + rhs.c1,
+            c2: self.c2 + rhs.c2,
+        }
+    }
+}
+
+impl<F: CubicBase> std::ops::AddAssign for CubicExt<F> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<F: CubicBase> std::ops::Sub for CubicExt<F> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        CubicExt {
+            c0: self.c0 - rhs.c0,
+            c1: self.c1 - rhs.c1,
+            c2: self.c2 - rhs.c2,
+        }
+    }
+}
+
+impl<F: CubicBase> std::ops::SubAssign for CubicExt<F> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<F: CubicBase> std::ops::Mul for CubicExt<F> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        let w = F::CUBIC_NON_RESIDUE;
+        let (a, b) = (self, rhs);
+        CubicExt {
+            c0: a.c0 * b.c0 + w * (a.c1 * b.c2 + a.c2 * b.c1),
+            c1: a.c0 * b.c1 + a.c1 * b.c0 + w * a.c2 * b.c2,
+            c2: a.c0 * b.c2 + a.c1 * b.c1 + a.c2 * b.c0,
+        }
+    }
+}
+
+impl<F: CubicBase> std::ops::MulAssign for CubicExt<F> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<F: CubicBase> std::fmt::Display for CubicExt<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} + {} * u + {} * u^2", self.c0, self.c1, self.c2)
+    }
+}
+
+impl<F: CubicBase + OddCharacteristicField> OddCharacteristicField for CubicExt<F> {
+    const INVERSE_2: Self = CubicExt {
+        c0: F::INVERSE_2,
+        c1: F::ZERO,
+        c2: F::ZERO,
+    };
+}
+
+impl<F: CubicBase> Field for CubicExt<F> {
+    const LOG_ORDER: u64 = F::LOG_ORDER;
+    const ROOT_OF_UNITY: Self = CubicExt {
+        c0: F::ROOT_OF_UNITY,
+        c1: F::ZERO,
+        c2: F::ZERO,
+    };
+    const ZERO: Self = CubicExt {
+        c0: F::ZERO,
+        c1: F::ZERO,
+        c2: F::ZERO,
+    };
+    const BYTE_SIZE: usize = 3 * F::BYTE_SIZE;
+    const FIELD_CHOICE: FieldChoice = F::CUBIC_FIELD_CHOICE;
+    const CHARACTERISTIC_BITS: u32 = F::CHARACTERISTIC_BITS;
+
+    #[inline]
+    fn from_int(x: u64) -> Self {
+        CubicExt {
+            c0: F::from_int(x),
+            c1: F::ZERO,
+            c2: F::ZERO,
+        }
+    }
+
+    #[inline]
+    fn random_element() -> Self {
+        CubicExt {
+            c0: F::random_element(),
+            c1: F::random_element(),
+            c2: F::random_element(),
+        }
+    }
+
+    fn from_uniform_bytes(bytes: &[u8; 32]) -> Self {
+        CubicExt {
+            c0: uniform_coordinate(bytes, 0),
+            c1: uniform_coordinate(bytes, 1),
+            c2: uniform_coordinate(bytes, 2),
+        }
+    }
+
+    /// The adjugate of multiplication by the element, divided by its norm.
+    fn inverse(&self) -> Self {
+        let w = F::CUBIC_NON_RESIDUE;
+        let (c0, c1, c2) = (self.c0, self.c1, self.c2);
+        let t0 = c0 * c0 - w * c1 * c2;
+        let t1 = w * c2 * c2 - c0 * c1;
+        let t2 = c1 * c1 - c0 * c2;
+        let norm_inv = (c0 * t0 + w * (c2 * t1 + c1 * t2)).inverse();
+        CubicExt {
+            c0: t0 * norm_inv,
+            c1: t1 * norm_inv,
+            c2: t2 * norm_inv,
+        }
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero() && self.c2.is_zero()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut x = self.c0.to_bytes();
+        x.extend(self.c1.to_bytes());
+        x.extend(self.c2.to_bytes());
+        x
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::BYTE_SIZE {
+            return None;
+        }
+        let mut coordinates = bytes.chunks(F::BYTE_SIZE).map(F::from_bytes);
+        Some(CubicExt {
+            c0: coordinates.next()??,
+            c1: coordinates.next()??,
+            c2: coordinates.next()??,
+        })
+    }
+}
+
+impl<F: CubicBase> ExtensionField<F> for CubicExt<F> {
+    const DEGREE: usize = 3;
+
+    #[inline]
+    fn from_base(x: F) -> Self {
+        CubicExt {
+            c0: x,
+            c1: F::ZERO,
+            c2: F::ZERO,
+        }
+    }
+
+    #[inline]
+    fn to_base(&self) -> Option<F> {
+        if self.c1.is_zero() && self.c2.is_zero() {
+            Some(self.c0)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn mul_base(&self, x: F) -> Self {
+        CubicExt {
+            c0: self.c0 * x,
+            c1: self.c1 * x,
+            c2: self.c2 * x,
+        }
+    }
+}
+
+/// `c[0] + c[1] * u + c[2] * u^2 + c[3] * u^3` with
+/// `u^4 = F::QUARTIC_NON_RESIDUE`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QuarticExt<F> {
+    c: [F; 4],
+}
+
+impl<F: QuarticBase> QuarticExt<F> {
+    pub fn new(c: [F; 4]) -> Self {
+        QuarticExt { c }
+    }
+}
+
+/// Product in `F[v] / (v^2 - w)`.
+#[inline]
+fn mul_quadratic<F: Field>(a: (F, F), b: (F, F), w: F) -> (F, F) {
+    (a.0 * b.0 + w * a.1 * b.1, a.0 * b.1 + a.1 * b.0)
+}
+
+impl<F: QuarticBase> std::ops::Neg for QuarticExt<F> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        QuarticExt {
+            c: self.c.map(|x| -x),
+        }
+    }
+}
+
+impl<F: QuarticBase> std::ops::Add for QuarticExt<F> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        QuarticExt {
+            c: std::array::from_fn(|i| self.c[i] + rhs.c[i]),
+        }
+    }
+}
+
+impl<F: QuarticBase> std::ops::AddAssign for QuarticExt<F> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<F: QuarticBase> std::ops::Sub for QuarticExt<F> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        QuarticExt {
+            c: std::array::from_fn(|i| self.c[i] - rhs.c[i]),
+        }
+    }
+}
+
+impl<F: QuarticBase> std::ops::SubAssign for QuarticExt<F> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<F: QuarticBase> std::ops::Mul for QuarticExt<F> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        let (a, b) = (self.c, rhs.c);
+        let mut c = [F::ZERO; 4];
+        for i in 0..4 {
+            for j in 0..4 {
+                if i + j < 4 {
+                    c[i + j] += a[i] * b[j];
+                } else {
+                    c[i + j - 4] += F::QUARTIC_NON_RESIDUE * a[i] * b[j];
+                }
+            }
+        }
+        QuarticExt { c }
+    }
+}
+
+impl<F: QuarticBase> std::ops::MulAssign for QuarticExt<F> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<F: QuarticBase> std::fmt::Display for QuarticExt<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let [c0, c1, c2, c3] = self.c;
+        write!(f, "{} + {} * u + {} * u^2 + {} * u^3", c0, c1, c2, c3)
+    }
+}
+
+impl<F: QuarticBase + OddCharacteristicField> OddCharacteristicField for QuarticExt<F> {
+    const INVERSE_2: Self = QuarticExt {
+        c: [F::INVERSE_2, F::ZERO, F::ZERO, F::ZERO],
+    };
+}
+
+impl<F: QuarticBase> Field for QuarticExt<F> {
+    const LOG_ORDER: u64 = F::LOG_ORDER;
+    const ROOT_OF_UNITY: Self = QuarticExt {
+        c: [F::ROOT_OF_UNITY, F::ZERO, F::ZERO, F::ZERO],
+    };
+    const ZERO: Self = QuarticExt { c: [F::ZERO; 4] };
+    const BYTE_SIZE: usize = 4 * F::BYTE_SIZE;
+    const FIELD_CHOICE: FieldChoice = F::QUARTIC_FIELD_CHOICE;
+    const CHARACTERISTIC_BITS: u32 = F::CHARACTERISTIC_BITS;
+
+    #[inline]
+    fn from_int(x: u64) -> Self {
+        QuarticExt::from_base(F::from_int(x))
+    }
+
+    #[inline]
+    fn random_element() -> Self {
+        QuarticExt {
+            c: std::array::from_fn(|_| F::random_element()),
+        }
+    }
+
+    fn from_uniform_bytes(bytes: &[u8; 32]) -> Self {
+        QuarticExt {
+            c: std::array::from_fn(|i| uniform_coordinate(bytes, i as u8)),
+        }
+    }
+
+    /// With `a = A(u^2) + u * B(u^2)`, `a * a(-u) = A^2 - u^2 * B^2` lies in
+    /// the quadratic subfield `F[v] / (v^2 - W)`, `v = u^2`, where it is
+    /// inverted.
+    fn inverse(&self) -> Self {
+        let w = F::QUARTIC_NON_RESIDUE;
+        let [c0, c1, c2, c3] = self.c;
+        let (a, b) = ((c0, c2), (c1, c3));
+        let a2 = mul_quadratic(a, a, w);
+        let b2 = mul_quadratic(b, b, w);
+        let norm = (a2.0 - w * b2.1, a2.1 - b2.0);
+        let norm_inv = (norm.0 * norm.0 - w * norm.1 * norm.1).inverse();
+        let norm_inv = (norm.0 * norm_inv, -norm.1 * norm_inv);
+        let a = mul_quadratic(a, norm_inv, w);
+        let b = mul_quadratic((-b.0, -b.1), norm_inv, w);
+        QuarticExt {
+            c: [a.0, b.0, a.1, b.1],
+        }
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.c.iter().all(|x| x.is_zero())
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.c.iter().flat_map(|x| x.to_bytes()).collect()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::BYTE_SIZE {
+            return None;
+        }
+        let c = bytes
+            .chunks(F::BYTE_SIZE)
+            .map(F::from_bytes)
+            .collect::<Option<Vec<_>>>()?;
+        Some(QuarticExt {
+            c: c.try_into().ok()?,
+        })
+    }
+}
+
+impl<F: QuarticBase> ExtensionField<F> for QuarticExt<F> {
+    const DEGREE: usize = 4;
+
+    #[inline]
+    fn from_base(x: F) -> Self {
+        QuarticExt {
+            c: [x, F::ZERO, F::ZERO, F::ZERO],
+        }
+    }
+
+    #[inline]
+    fn to_base(&self) -> Option<F> {
+        if self.c[1..].iter().all(|x| x.is_zero()) {
+            Some(self.c[0])
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn mul_base(&self, x: F) -> Self {
+        QuarticExt {
+            c: self.c.map(|y| y * x),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::field_tests::*;
+    use super::super::{babybear::BabyBear, fp64::Fp64, koalabear::KoalaBear};
+    use super::*;
+
+    #[test]
+    fn quadratic() {
+        type T = QuadExt<Fp64>;
+        add_and_sub::<T>();
+        mult_and_inverse::<T>();
+        inverse_2::<Fp64, T>();
+        assigns::<T>();
+        pow_and_generator::<T>();
+        bytes_round_trip::<T>();
+        extension::<Fp64, T>();
+        let u = T::new(Fp64::from_int(0), Fp64::from_int(1));
+        assert_eq!(u * u, T::from_base(Fp64::QUADRATIC_NON_RESIDUE));
+        // W^((p - 1) / 2) = -1 for p = 2^64 - 2^32 + 1.
+        let euler = Fp64::QUADRATIC_NON_RESIDUE.pow((1 << 63) - (1 << 31));
+        assert_eq!(euler, -Fp64::from_int(1));
+    }
+
+    #[test]
+    fn cubic() {
+        type T = CubicExt<Fp64>;
+        add_and_sub::<T>();
+        mult_and_inverse::<T>();
+        inverse_2::<Fp64, T>();
+        assigns::<T>();
+        pow_and_generator::<T>();
+        bytes_round_trip::<T>();
+        extension::<Fp64, T>();
+        let u = T::new(Fp64::from_int(0), Fp64::from_int(1), Fp64::from_int(0));
+        assert_eq!(u * u * u, T::from_base(Fp64::CUBIC_NON_RESIDUE));
+        // W^((p - 1) / 3) is a nontrivial cube root of unity.
+        let root = Fp64::CUBIC_NON_RESIDUE.pow(((1u128 << 64) - (1 << 32)) as usize / 3);
+        assert_ne!(root, Fp64::from_int(1));
+    }
+
+    fn quartic<F: QuarticBase + OddCharacteristicField>() {
+        type T<F> = QuarticExt<F>;
+        add_and_sub::<T<F>>();
+        mult_and_inverse::<T<F>>();
+        inverse_2::<F, T<F>>();
+        assigns::<T<F>>();
+        pow_and_generator::<T<F>>();
+        bytes_round_trip::<T<F>>();
+        extension::<F, T<F>>();
+        let u = T::new([F::ZERO, F::from_int(1), F::ZERO, F::ZERO]);
+        assert_eq!(u * u * u * u, T::from_base(F::QUARTIC_NON_RESIDUE));
+    }
+
+    #[test]
+    fn babybear_quartic() {
+        quartic::<BabyBear>();
+        // W^((p - 1) / 2) = -1 for p = 2^31 - 2^27 + 1.
+        let euler = BabyBear::QUARTIC_NON_RESIDUE.pow(((1 << 31) - (1 << 27)) / 2);
+        assert_eq!(euler, -BabyBear::from_int(1));
+    }
+
+    #[test]
+    fn koalabear_quartic() {
+        quartic::<KoalaBear>();
+        // W^((p - 1) / 2) = -1 for p = 2^31 - 2^24 + 1.
+        let euler = KoalaBear::QUARTIC_NON_RESIDUE.pow(((1 << 31) - (1 << 24)) / 2);
+        assert_eq!(euler, -KoalaBear::from_int(1));
+    }
+}
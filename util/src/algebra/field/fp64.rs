@@ -100,17 +100,25 @@ impl std::fmt::Display for Fp64 {
     }
 }
 
-use super::Field;
+use super::extension::{CubicBase, QuadraticBase};
+use super::{Field, FieldChoice, OddCharacteristicField};
 use rand::Rng;
 
+impl OddCharacteristicField for Fp64 {
+    const INVERSE_2: Self = Fp64 {
+        real: 9223372034707292161,
+    };
+}
+
 impl Field for Fp64 {
     const LOG_ORDER: u64 = 32;
     const ROOT_OF_UNITY: Fp64 = Fp64 {
         real: 2741030659394132017u64,
     };
-    const INVERSE_2: Self = Fp64 {
-        real: 9223372034707292161,
-    };
+    const ZERO: Self = Fp64 { real: 0 };
+    const BYTE_SIZE: usize = 8;
+    const FIELD_CHOICE: FieldChoice = FieldChoice::Fp64;
+    const CHARACTERISTIC_BITS: u32 = 64;
 
     fn from_int(x: u64) -> Fp64 {
         if x >= MOD {
@@ -124,6 +132,13 @@ impl Field for Fp64 {
         Fp64 { real: r }
     }
 
+    fn from_uniform_bytes(bytes: &[u8; 32]) -> Self {
+        let x = u128::from_le_bytes(bytes[..16].try_into().unwrap());
+        Fp64 {
+            real: (x % MOD as u128) as u64,
+        }
+    }
+
     fn inverse(&self) -> Self {
         let mut x_gcd = 0i128;
         let mut y_gcd = 0i128;
@@ -141,6 +156,25 @@ impl Field for Fp64 {
         let x = self.real.to_le_bytes().to_vec();
         x
     }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let real = u64::from_le_bytes(bytes.try_into().ok()?);
+        if real >= MOD {
+            return None;
+        }
+        Some(Fp64 { real })
+    }
+}
+
+/// `7` is neither a square nor a cube modulo `2^64 - 2^32 + 1`.
+impl QuadraticBase for Fp64 {
+    const QUADRATIC_NON_RESIDUE: Self = Fp64 { real: 7 };
+    const QUADRATIC_FIELD_CHOICE: FieldChoice = FieldChoice::Fp64Quad;
+}
+
+impl CubicBase for Fp64 {
+    const CUBIC_NON_RESIDUE: Self = Fp64 { real: 7 };
+    const CUBIC_FIELD_CHOICE: FieldChoice = FieldChoice::Fp64Cubic;
 }
 
 impl Fp64 {
@@ -176,7 +210,9 @@ mod tests {
     fn test() {
         add_and_sub::<Fp64>();
         mult_and_inverse::<Fp64>();
+        inverse_2::<Fp64, Fp64>();
         assigns::<Fp64>();
         pow_and_generator::<Fp64>();
+        bytes_round_trip::<Fp64>();
     }
 }
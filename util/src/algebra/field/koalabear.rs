@@ -0,0 +1,39 @@
+use super::extension::QuarticBase;
+use super::prime31::{Prime31, Prime31Params};
+use super::FieldChoice;
+
+#[derive(Debug, Clone, Copy)]
+pub struct KoalaBearParams;
+
+impl Prime31Params for KoalaBearParams {
+    const MOD: u32 = 2130706433;
+    const LOG_ORDER: u64 = 24;
+    const ROOT_OF_UNITY: u32 = 1791270792;
+    const FIELD_CHOICE: FieldChoice = FieldChoice::KoalaBear;
+}
+
+/// The prime field of `2^31 - 2^24 + 1` elements, with a two-adic subgroup of
+/// order `2^24`.
+pub type KoalaBear = Prime31<KoalaBearParams>;
+
+/// `3` is not a square modulo `2^31 - 2^24 + 1`.
+impl QuarticBase for KoalaBear {
+    const QUARTIC_NON_RESIDUE: Self = KoalaBear::new(3);
+    const QUARTIC_FIELD_CHOICE: FieldChoice = FieldChoice::KoalaBearQuartic;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::field_tests::*;
+    use super::*;
+
+    #[test]
+    fn test() {
+        add_and_sub::<KoalaBear>();
+        mult_and_inverse::<KoalaBear>();
+        inverse_2::<KoalaBear, KoalaBear>();
+        assigns::<KoalaBear>();
+        pow_and_generator::<KoalaBear>();
+        bytes_round_trip::<KoalaBear>();
+    }
+}
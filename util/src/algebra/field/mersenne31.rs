@@ -0,0 +1,50 @@
+use super::prime31::{Prime31, Prime31Params};
+use super::FieldChoice;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Mersenne31Params;
+
+impl Prime31Params for Mersenne31Params {
+    const MOD: u32 = (1u32 << 31) - 1;
+    const LOG_ORDER: u64 = 1;
+    const ROOT_OF_UNITY: u32 = Self::MOD - 1;
+    const FIELD_CHOICE: FieldChoice = FieldChoice::Mersenne31;
+
+    /// `x mod 2^31 - 1` for `x < 2^62`.
+    #[inline]
+    fn reduce(x: u64) -> u32 {
+        let x = (x as u32 & Self::MOD) + (x >> 31) as u32;
+        let x = (x & Self::MOD) + (x >> 31);
+        if x >= Self::MOD {
+            x - Self::MOD
+        } else {
+            x
+        }
+    }
+}
+
+/// The prime field of `2^31 - 1` elements, the base field of
+/// `Mersenne31Ext`, where evaluation domains live.
+pub type Mersenne31 = Prime31<Mersenne31Params>;
+
+#[cfg(test)]
+mod tests {
+    use super::super::field_tests::*;
+    use super::super::Field;
+    use super::*;
+
+    #[test]
+    fn test() {
+        add_and_sub::<Mersenne31>();
+        mult_and_inverse::<Mersenne31>();
+        inverse_2::<Mersenne31, Mersenne31>();
+        assigns::<Mersenne31>();
+        bytes_round_trip::<Mersenne31>();
+        pow_and_generator::<Mersenne31>();
+        assert_eq!(Mersenne31::get_generator(2), -Mersenne31::from_int(1));
+        assert_eq!(
+            Mersenne31::from_int(Mersenne31Params::MOD as u64),
+            Mersenne31::from_int(0)
+        );
+    }
+}
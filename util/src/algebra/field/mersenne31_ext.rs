@@ -0,0 +1,211 @@
+use super::mersenne31::Mersenne31;
+use super::{ExtensionField, Field, FieldChoice, OddCharacteristicField};
+
+/// `Mersenne31[i]` with `i^2 = -1`, `-1` not being a square modulo
+/// `2^31 - 1`. Its two-adic subgroup has order `2^32`.
+#[derive(Debug, Clone, Copy)]
+pub struct Mersenne31Ext {
+    real: Mersenne31,
+    image: Mersenne31,
+}
+
+impl std::ops::Neg for Mersenne31Ext {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Mersenne31Ext {
+            real: -self.real,
+            image: -self.image,
+        }
+    }
+}
+
+impl std::ops::Add for Mersenne31Ext {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Mersenne31Ext {
+            real: self.real + rhs.real,
+            image: self.image + rhs.image,
+        }
+    }
+}
+
+impl std::ops::AddAssign for Mersenne31Ext {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::Sub for Mersenne31Ext {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Mersenne31Ext {
+            real: self.real - rhs.real,
+            image: self.image - rhs.image,
+        }
+    }
+}
+
+impl std::ops::SubAssign for Mersenne31Ext {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl std::ops::Mul for Mersenne31Ext {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        let (a, b) = (self.real * rhs.real, self.image * rhs.image);
+        Mersenne31Ext {
+            real: a - b,
+            image: (self.real + self.image) * (rhs.real + rhs.image) - a - b,
+        }
+    }
+}
+
+impl std::ops::MulAssign for Mersenne31Ext {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl std::fmt::Display for Mersenne31Ext {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "real: {}, image: {}", self.real, self.image)
+    }
+}
+
+impl std::cmp::PartialEq for Mersenne31Ext {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.real == rhs.real && self.image == rhs.image
+    }
+}
+
+impl OddCharacteristicField for Mersenne31Ext {
+    const INVERSE_2: Self = Mersenne31Ext {
+        real: Mersenne31::INVERSE_2,
+        image: Mersenne31::ZERO,
+    };
+}
+
+impl Field for Mersenne31Ext {
+    const LOG_ORDER: u64 = 32;
+    const ROOT_OF_UNITY: Self = Mersenne31Ext {
+        real: Mersenne31::new(21189756),
+        image: Mersenne31::new(42379512),
+    };
+    const ZERO: Self = Mersenne31Ext {
+        real: Mersenne31::ZERO,
+        image: Mersenne31::ZERO,
+    };
+    const BYTE_SIZE: usize = 8;
+    const FIELD_CHOICE: FieldChoice = FieldChoice::Mersenne31Ext;
+    const CHARACTERISTIC_BITS: u32 = 31;
+
+    #[inline]
+    fn from_int(x: u64) -> Self {
+        Mersenne31Ext {
+            real: Mersenne31::from_int(x),
+            image: Mersenne31::ZERO,
+        }
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.real.is_zero() && self.image.is_zero()
+    }
+
+    #[inline]
+    fn random_element() -> Self {
+        Mersenne31Ext {
+            real: Mersenne31::random_element(),
+            image: Mersenne31::random_element(),
+        }
+    }
+
+    #[inline]
+    fn from_uniform_bytes(bytes: &[u8; 32]) -> Self {
+        let mut image = [0; 32];
+        image[..16].copy_from_slice(&bytes[16..]);
+        Mersenne31Ext {
+            real: Mersenne31::from_uniform_bytes(bytes),
+            image: Mersenne31::from_uniform_bytes(&image),
+        }
+    }
+
+    /// The conjugate divided by the norm `real^2 + image^2`.
+    fn inverse(&self) -> Self {
+        let norm_inv = (self.real * self.real + self.image * self.image).inverse();
+        Mersenne31Ext {
+            real: self.real * norm_inv,
+            image: -self.image * norm_inv,
+        }
+    }
+
+    #[inline]
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut x = self.real.to_bytes();
+        x.extend(self.image.to_bytes());
+        x
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::BYTE_SIZE {
+            return None;
+        }
+        Some(Mersenne31Ext {
+            real: Mersenne31::from_bytes(&bytes[..4])?,
+            image: Mersenne31::from_bytes(&bytes[4..])?,
+        })
+    }
+}
+
+impl ExtensionField<Mersenne31> for Mersenne31Ext {
+    const DEGREE: usize = 2;
+
+    #[inline]
+    fn from_base(x: Mersenne31) -> Self {
+        Mersenne31Ext {
+            real: x,
+            image: Mersenne31::ZERO,
+        }
+    }
+
+    #[inline]
+    fn to_base(&self) -> Option<Mersenne31> {
+        if self.image.is_zero() {
+            Some(self.real)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn mul_base(&self, x: Mersenne31) -> Self {
+        Mersenne31Ext {
+            real: self.real * x,
+            image: self.image * x,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::field_tests::*;
+    use super::*;
+
+    #[test]
+    fn test() {
+        add_and_sub::<Mersenne31Ext>();
+        mult_and_inverse::<Mersenne31Ext>();
+        inverse_2::<Mersenne31, Mersenne31Ext>();
+        assigns::<Mersenne31Ext>();
+        pow_and_generator::<Mersenne31Ext>();
+        bytes_round_trip::<Mersenne31Ext>();
+        extension::<Mersenne31, Mersenne31Ext>();
+        let i = Mersenne31Ext {
+            real: Mersenne31::ZERO,
+            image: Mersenne31::from_int(1),
+        };
+        assert_eq!(i * i, -Mersenne31Ext::from_int(1));
+    }
+}
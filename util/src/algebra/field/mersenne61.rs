@@ -0,0 +1,168 @@
+use super::{Field, FieldChoice, OddCharacteristicField};
+use rand::Rng;
+
+/// The prime field of `2^61 - 1` elements, the base field of
+/// `Mersenne61Ext`. Its multiplicative group has no element of order 4, so
+/// evaluation domains of any useful size live in the extension.
+#[derive(Debug, Clone, Copy)]
+pub struct Mersenne61 {
+    pub(super) real: u64,
+}
+
+const MOD: u64 = (1u64 << 61) - 1;
+
+#[inline]
+fn try_sub(x: u64) -> u64 {
+    if x >= MOD {
+        x - MOD
+    } else {
+        x
+    }
+}
+
+/// `x mod 2^61 - 1` for `x < 2^122`.
+#[inline]
+pub(super) fn reduce(x: u128) -> u64 {
+    let x = (x as u64 & MOD) + (x >> 61) as u64;
+    try_sub((x & MOD) + (x >> 61))
+}
+
+impl std::ops::Neg for Mersenne61 {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Mersenne61 {
+            real: try_sub(self.real ^ MOD),
+        }
+    }
+}
+
+impl std::ops::Add for Mersenne61 {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Mersenne61 {
+            real: try_sub(self.real + rhs.real),
+        }
+    }
+}
+
+impl std::ops::AddAssign for Mersenne61 {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::ops::Sub for Mersenne61 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Mersenne61 {
+            real: try_sub(self.real + (rhs.real ^ MOD)),
+        }
+    }
+}
+
+impl std::ops::SubAssign for Mersenne61 {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl std::ops::Mul for Mersenne61 {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Mersenne61 {
+            real: reduce(self.real as u128 * rhs.real as u128),
+        }
+    }
+}
+
+impl std::ops::MulAssign for Mersenne61 {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl std::fmt::Display for Mersenne61 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.real)
+    }
+}
+
+impl std::cmp::PartialEq for Mersenne61 {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.real == rhs.real
+    }
+}
+
+impl OddCharacteristicField for Mersenne61 {
+    const INVERSE_2: Self = Mersenne61 { real: 1 << 60 };
+}
+
+impl Field for Mersenne61 {
+    const LOG_ORDER: u64 = 1;
+    const ROOT_OF_UNITY: Mersenne61 = Mersenne61 { real: MOD - 1 };
+    const ZERO: Self = Mersenne61 { real: 0 };
+    const BYTE_SIZE: usize = 8;
+    const FIELD_CHOICE: FieldChoice = FieldChoice::Mersenne61;
+    const CHARACTERISTIC_BITS: u32 = 61;
+
+    #[inline]
+    fn from_int(x: u64) -> Self {
+        Mersenne61 {
+            real: reduce(x as u128),
+        }
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.real == 0
+    }
+
+    #[inline]
+    fn random_element() -> Self {
+        Mersenne61 {
+            real: rand::thread_rng().gen_range(0..MOD),
+        }
+    }
+
+    #[inline]
+    fn from_uniform_bytes(bytes: &[u8; 32]) -> Self {
+        let x = u128::from_le_bytes(bytes[..16].try_into().unwrap());
+        Mersenne61 {
+            real: (x % MOD as u128) as u64,
+        }
+    }
+
+    fn inverse(&self) -> Self {
+        self.pow(MOD as usize - 2)
+    }
+
+    #[inline]
+    fn to_bytes(&self) -> Vec<u8> {
+        self.real.to_le_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let real = u64::from_le_bytes(bytes.try_into().ok()?);
+        if real >= MOD {
+            return None;
+        }
+        Some(Mersenne61 { real })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::field_tests::*;
+    use super::*;
+
+    #[test]
+    fn test() {
+        add_and_sub::<Mersenne61>();
+        mult_and_inverse::<Mersenne61>();
+        inverse_2::<Mersenne61, Mersenne61>();
+        assigns::<Mersenne61>();
+        bytes_round_trip::<Mersenne61>();
+        assert_eq!(Mersenne61::get_generator(2), -Mersenne61::from_int(1));
+        assert_eq!(Mersenne61::from_int(MOD), Mersenne61::from_int(0));
+    }
+}
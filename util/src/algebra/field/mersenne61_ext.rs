@@ -1,8 +1,16 @@
-use super::Field;
-use core::arch::x86_64::_mulx_u64;
+mod batch;
+#[cfg(target_arch = "x86_64")]
+mod x86_64;
+
+use super::mersenne61::{reduce, Mersenne61};
+use super::{ExtensionField, Field, FieldChoice, OddCharacteristicField};
+use batch::{Backend, Op};
 use rand::Rng;
 
+/// `real + image * i` with `i^2 = -1`, both parts reduced. The layout is
+/// fixed so that the vectorized backends can load slices as `u64` pairs.
 #[derive(Debug, Clone, Copy)]
+#[repr(C)]
 pub struct Mersenne61Ext {
     real: u64,
     image: u64,
@@ -66,10 +74,33 @@ impl std::ops::SubAssign for Mersenne61Ext {
     }
 }
 
+/// `x * y` folded once modulo `2^61 - 1`, below `2^63` for `x, y < 2^62`.
+#[cfg(all(target_arch = "x86_64", target_feature = "bmi2"))]
+#[inline]
+fn my_mult(x: u64, y: u64) -> u64 {
+    // SAFETY: the target has BMI2.
+    unsafe { mult_bmi2(x, y) }
+}
+
+#[cfg(not(all(target_arch = "x86_64", target_feature = "bmi2")))]
 #[inline]
 fn my_mult(x: u64, y: u64) -> u64 {
+    mult_portable(x, y)
+}
+
+#[cfg(any(test, not(all(target_arch = "x86_64", target_feature = "bmi2"))))]
+#[inline]
+fn mult_portable(x: u64, y: u64) -> u64 {
+    let product = x as u128 * y as u128;
+    (product >> 61) as u64 + (product as u64 & MOD)
+}
+
+#[cfg(all(target_arch = "x86_64", any(test, target_feature = "bmi2")))]
+#[target_feature(enable = "bmi2")]
+#[inline]
+unsafe fn mult_bmi2(x: u64, y: u64) -> u64 {
     let mut hi = 0;
-    let lo = unsafe { _mulx_u64(x, y, &mut hi) };
+    let lo = core::arch::x86_64::_mulx_u64(x, y, &mut hi);
     ((hi << 3) | (lo >> 61)) + (lo & MOD)
 }
 
@@ -117,16 +148,23 @@ impl std::cmp::PartialEq for Mersenne61Ext {
     }
 }
 
+impl OddCharacteristicField for Mersenne61Ext {
+    const INVERSE_2: Self = Mersenne61Ext {
+        real: 1152921504606846976,
+        image: 0,
+    };
+}
+
 impl Field for Mersenne61Ext {
     const LOG_ORDER: u64 = 62;
     const ROOT_OF_UNITY: Mersenne61Ext = Mersenne61Ext {
         real: 2147483648,
         image: 1033321771269002680,
     };
-    const INVERSE_2: Self = Mersenne61Ext {
-        real: 1152921504606846976,
-        image: 0,
-    };
+    const ZERO: Self = Mersenne61Ext { real: 0, image: 0 };
+    const BYTE_SIZE: usize = 16;
+    const FIELD_CHOICE: FieldChoice = FieldChoice::Mersenne61Ext;
+    const CHARACTERISTIC_BITS: u32 = 61;
 
     #[inline]
     fn from_int(x: u64) -> Self {
@@ -147,6 +185,16 @@ impl Field for Mersenne61Ext {
         }
     }
 
+    #[inline]
+    fn from_uniform_bytes(bytes: &[u8; 32]) -> Self {
+        let real = u128::from_le_bytes(bytes[..16].try_into().unwrap());
+        let image = u128::from_le_bytes(bytes[16..].try_into().unwrap());
+        Mersenne61Ext {
+            real: (real % MOD as u128) as u64,
+            image: (image % MOD as u128) as u64,
+        }
+    }
+
     fn inverse(&self) -> Self {
         let p = 2305843009213693951u128;
         let mut n = p * p - 2;
@@ -164,9 +212,65 @@ impl Field for Mersenne61Ext {
 
     #[inline]
     fn to_bytes(&self) -> Vec<u8> {
-        let x = self.real.to_le_bytes().to_vec();
+        let mut x = self.real.to_le_bytes().to_vec();
+        x.extend_from_slice(&self.image.to_le_bytes());
         x
     }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() != Self::BYTE_SIZE {
+            return None;
+        }
+        let real = u64::from_le_bytes(bytes[..8].try_into().unwrap());
+        let image = u64::from_le_bytes(bytes[8..].try_into().unwrap());
+        if real >= MOD || image >= MOD {
+            return None;
+        }
+        Some(Mersenne61Ext { real, image })
+    }
+
+    fn add_assign_slice(xs: &mut [Self], ys: &[Self]) {
+        batch::apply(Backend::detect(), Op::Add, xs, ys);
+    }
+
+    fn sub_assign_slice(xs: &mut [Self], ys: &[Self]) {
+        batch::apply(Backend::detect(), Op::Sub, xs, ys);
+    }
+
+    fn mul_assign_slice(xs: &mut [Self], ys: &[Self]) {
+        batch::apply(Backend::detect(), Op::Mul, xs, ys);
+    }
+}
+
+/// `Mersenne61Ext` is `Mersenne61[i]` with `i^2 = -1`, the real part being
+/// the base field.
+impl ExtensionField<Mersenne61> for Mersenne61Ext {
+    const DEGREE: usize = 2;
+
+    #[inline]
+    fn from_base(x: Mersenne61) -> Self {
+        Mersenne61Ext {
+            real: x.real,
+            image: 0,
+        }
+    }
+
+    #[inline]
+    fn to_base(&self) -> Option<Mersenne61> {
+        if self.image == 0 {
+            Some(Mersenne61 { real: self.real })
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn mul_base(&self, x: Mersenne61) -> Self {
+        Mersenne61Ext {
+            real: reduce(self.real as u128 * x.real as u128),
+            image: reduce(self.image as u128 * x.real as u128),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -178,7 +282,36 @@ mod tests {
     fn test() {
         add_and_sub::<Mersenne61Ext>();
         mult_and_inverse::<Mersenne61Ext>();
+        inverse_2::<Mersenne61, Mersenne61Ext>();
         assigns::<Mersenne61Ext>();
         pow_and_generator::<Mersenne61Ext>();
+        bytes_round_trip::<Mersenne61Ext>();
+        batched::<Mersenne61Ext>();
+        extension::<Mersenne61, Mersenne61Ext>();
+        assert_eq!(
+            ExtensionField::<Mersenne61>::to_base(&Mersenne61Ext::ROOT_OF_UNITY),
+            None
+        );
+    }
+
+    #[test]
+    fn portable_mult() {
+        let edges = [0, 1, MOD - 1, MOD, 2 * MOD - 1, 2 * MOD];
+        for x in edges {
+            for y in edges {
+                assert_eq!(
+                    mult_portable(x, y) as u128 % MOD as u128,
+                    x as u128 * y as u128 % MOD as u128
+                );
+            }
+        }
+        #[cfg(target_arch = "x86_64")]
+        if is_x86_feature_detected!("bmi2") {
+            let mut rng = rand::thread_rng();
+            for _i in 0..1000 {
+                let (x, y) = (rng.gen_range(0..2 * MOD), rng.gen_range(0..2 * MOD));
+                assert_eq!(mult_portable(x, y), unsafe { mult_bmi2(x, y) });
+            }
+        }
     }
 }
@@ -0,0 +1,142 @@
+//! Backends of the batched operations of `Mersenne61Ext`. The scalar one
+//! works everywhere, the vectorized ones are picked at runtime on x86_64
+//! CPUs that have them, and all of them give the same reduced values.
+
+use super::Mersenne61Ext;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Backend {
+    Scalar,
+    /// Two elements per 256-bit register.
+    #[cfg(target_arch = "x86_64")]
+    Avx2,
+    /// Four elements per 512-bit register.
+    #[cfg(target_arch = "x86_64")]
+    Avx512,
+}
+
+impl Backend {
+    /// The widest backend the running CPU supports.
+    #[inline]
+    pub(super) fn detect() -> Self {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx512f") {
+                return Backend::Avx512;
+            }
+            if is_x86_feature_detected!("avx2") {
+                return Backend::Avx2;
+            }
+        }
+        Backend::Scalar
+    }
+
+    /// Every backend the running CPU supports.
+    #[cfg(test)]
+    fn available() -> Vec<Self> {
+        #[cfg_attr(not(target_arch = "x86_64"), allow(unused_mut))]
+        let mut res = vec![Backend::Scalar];
+        #[cfg(target_arch = "x86_64")]
+        {
+            if is_x86_feature_detected!("avx2") {
+                res.push(Backend::Avx2);
+            }
+            if is_x86_feature_detected!("avx512f") {
+                res.push(Backend::Avx512);
+            }
+        }
+        res
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum Op {
+    Add,
+    Sub,
+    Mul,
+}
+
+impl Op {
+    #[inline]
+    pub(super) fn scalar(self, x: &mut Mersenne61Ext, y: Mersenne61Ext) {
+        match self {
+            Op::Add => *x += y,
+            Op::Sub => *x -= y,
+            Op::Mul => *x *= y,
+        }
+    }
+}
+
+/// `xs[i] = op(xs[i], ys[i])` for every `i` with `backend`, which the
+/// running CPU must support.
+pub(super) fn apply(backend: Backend, op: Op, xs: &mut [Mersenne61Ext], ys: &[Mersenne61Ext]) {
+    assert_eq!(xs.len(), ys.len());
+    match backend {
+        Backend::Scalar => {
+            for (x, y) in xs.iter_mut().zip(ys) {
+                op.scalar(x, *y);
+            }
+        }
+        // SAFETY: `detect` only returns the vectorized backends when the CPU
+        // has the features they are compiled with.
+        #[cfg(target_arch = "x86_64")]
+        Backend::Avx2 => unsafe { super::x86_64::apply_avx2(op, xs, ys) },
+        #[cfg(target_arch = "x86_64")]
+        Backend::Avx512 => unsafe { super::x86_64::apply_avx512(op, xs, ys) },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::MOD;
+    use super::*;
+    use crate::algebra::field::Field;
+
+    /// Reduced values with the carries and borrows of every operation.
+    fn edge_values() -> Vec<Mersenne61Ext> {
+        let parts = [0, 1, 2, 1 << 32, (1 << 32) - 1, 1 << 60, MOD - 2, MOD - 1];
+        parts
+            .iter()
+            .flat_map(|&real| {
+                parts
+                    .iter()
+                    .map(move |&image| Mersenne61Ext { real, image })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn backends_agree() {
+        let edges = edge_values();
+        let xs = edges
+            .iter()
+            .flat_map(|x| std::iter::repeat_n(*x, edges.len()))
+            .chain((0..1000).map(|_| Mersenne61Ext::random_element()))
+            .collect::<Vec<_>>();
+        let ys = edges
+            .iter()
+            .cycle()
+            .take(edges.len() * edges.len())
+            .copied()
+            .chain((0..1000).map(|_| Mersenne61Ext::random_element()))
+            .collect::<Vec<_>>();
+        for op in [Op::Add, Op::Sub, Op::Mul] {
+            let mut expected = xs.clone();
+            apply(Backend::Scalar, op, &mut expected, &ys);
+            for backend in Backend::available() {
+                // Odd lengths leave a tail for the scalar loop.
+                for len in [0, 1, 3, 5, 7, xs.len()] {
+                    let mut res = xs[..len].to_vec();
+                    apply(backend, op, &mut res, &ys[..len]);
+                    assert_eq!(res, expected[..len], "{:?} {:?}", backend, op);
+                    assert!(res.iter().all(|x| x.real < MOD && x.image < MOD));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn detected_backend_is_available() {
+        assert!(Backend::available().contains(&Backend::detect()));
+    }
+}
@@ -0,0 +1,157 @@
+//! AVX2 and AVX-512 kernels of the batched operations. A register holds the
+//! parts `[real, image]` of consecutive elements in its 64-bit lanes. The
+//! lanes have no 64-bit product, so a product of reduced parts is put
+//! together from the four products of their 32-bit halves: with
+//! `2^61 = 1` and `2^64 = 8`, `(x1 2^32 + x0)(y1 2^32 + y0)` is
+//! `8 x1 y1 + (m >> 29) + (m mod 2^29) 2^32 + x0 y0` for the middle
+//! term `m = x1 y0 + x0 y1`, which stays below `2^63`.
+
+use super::batch::Op;
+use super::{Mersenne61Ext, MOD};
+use core::arch::x86_64::*;
+
+/// Lane-wise `x - MOD` where `x >= MOD`, for `x < 2 * MOD`.
+#[inline]
+#[target_feature(enable = "avx2")]
+fn try_sub_avx2(x: __m256i) -> __m256i {
+    let modulus = _mm256_set1_epi64x(MOD as i64);
+    // The lanes are below `2^63`, so the signed comparison is the unsigned.
+    let reduced = _mm256_cmpgt_epi64(modulus, x);
+    _mm256_sub_epi64(x, _mm256_andnot_si256(reduced, modulus))
+}
+
+/// Lane-wise product of reduced parts, reduced.
+#[inline]
+#[target_feature(enable = "avx2")]
+fn mul_parts_avx2(x: __m256i, y: __m256i) -> __m256i {
+    let modulus = _mm256_set1_epi64x(MOD as i64);
+    let (x1, y1) = (_mm256_srli_epi64::<32>(x), _mm256_srli_epi64::<32>(y));
+    let low = _mm256_mul_epu32(x, y);
+    let middle = _mm256_add_epi64(_mm256_mul_epu32(x1, y), _mm256_mul_epu32(x, y1));
+    let high = _mm256_mul_epu32(x1, y1);
+    let middle_low = _mm256_and_si256(middle, _mm256_set1_epi64x((1 << 29) - 1));
+    let mut sum = _mm256_add_epi64(
+        _mm256_slli_epi64::<3>(high),
+        _mm256_srli_epi64::<29>(middle),
+    );
+    sum = _mm256_add_epi64(sum, _mm256_slli_epi64::<32>(middle_low));
+    sum = _mm256_add_epi64(sum, _mm256_srli_epi64::<61>(low));
+    sum = _mm256_add_epi64(sum, _mm256_and_si256(low, modulus));
+    let sum = _mm256_add_epi64(_mm256_and_si256(sum, modulus), _mm256_srli_epi64::<61>(sum));
+    try_sub_avx2(sum)
+}
+
+#[inline]
+#[target_feature(enable = "avx2")]
+fn op_avx2(op: Op, x: __m256i, y: __m256i) -> __m256i {
+    let modulus = _mm256_set1_epi64x(MOD as i64);
+    match op {
+        Op::Add => try_sub_avx2(_mm256_add_epi64(x, y)),
+        Op::Sub => try_sub_avx2(_mm256_add_epi64(x, _mm256_sub_epi64(modulus, y))),
+        Op::Mul => {
+            // `[ac, bd]` and `[ad, bc]` for `x = [a, b]` and `y = [c, d]`.
+            let straight = mul_parts_avx2(x, y);
+            let crossed = mul_parts_avx2(x, _mm256_shuffle_epi32::<0b01_00_11_10>(y));
+            let left = _mm256_unpacklo_epi64(straight, crossed);
+            let right = _mm256_unpackhi_epi64(straight, crossed);
+            // `[ac - bd, ad + bc]`, negating the real lanes of `[bd, bc]`.
+            let right = _mm256_blend_epi32::<0b0011_0011>(right, _mm256_sub_epi64(modulus, right));
+            try_sub_avx2(_mm256_add_epi64(left, right))
+        }
+    }
+}
+
+/// `batch::apply` with two elements per register.
+///
+/// # Safety
+///
+/// The CPU must support AVX2.
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn apply_avx2(op: Op, xs: &mut [Mersenne61Ext], ys: &[Mersenne61Ext]) {
+    let vectors = xs.len() / 2;
+    let x_ptr = xs.as_mut_ptr() as *mut __m256i;
+    let y_ptr = ys.as_ptr() as *const __m256i;
+    for i in 0..vectors {
+        // SAFETY: `Mersenne61Ext` is two `u64`, so vector `i` is elements
+        // `2i` and `2i + 1`, which are in both slices.
+        unsafe {
+            let x = _mm256_loadu_si256(x_ptr.add(i));
+            let y = _mm256_loadu_si256(y_ptr.add(i));
+            _mm256_storeu_si256(x_ptr.add(i), op_avx2(op, x, y));
+        }
+    }
+    for (x, y) in xs.iter_mut().zip(ys).skip(vectors * 2) {
+        op.scalar(x, *y);
+    }
+}
+
+/// Lane-wise `x - MOD` where `x >= MOD`, for `x < 2 * MOD`.
+#[inline]
+#[target_feature(enable = "avx512f")]
+fn try_sub_avx512(x: __m512i) -> __m512i {
+    let modulus = _mm512_set1_epi64(MOD as i64);
+    _mm512_mask_sub_epi64(x, _mm512_cmpge_epu64_mask(x, modulus), x, modulus)
+}
+
+/// Lane-wise product of reduced parts, reduced.
+#[inline]
+#[target_feature(enable = "avx512f")]
+fn mul_parts_avx512(x: __m512i, y: __m512i) -> __m512i {
+    let modulus = _mm512_set1_epi64(MOD as i64);
+    let (x1, y1) = (_mm512_srli_epi64::<32>(x), _mm512_srli_epi64::<32>(y));
+    let low = _mm512_mul_epu32(x, y);
+    let middle = _mm512_add_epi64(_mm512_mul_epu32(x1, y), _mm512_mul_epu32(x, y1));
+    let high = _mm512_mul_epu32(x1, y1);
+    let middle_low = _mm512_and_si512(middle, _mm512_set1_epi64((1 << 29) - 1));
+    let mut sum = _mm512_add_epi64(
+        _mm512_slli_epi64::<3>(high),
+        _mm512_srli_epi64::<29>(middle),
+    );
+    sum = _mm512_add_epi64(sum, _mm512_slli_epi64::<32>(middle_low));
+    sum = _mm512_add_epi64(sum, _mm512_srli_epi64::<61>(low));
+    sum = _mm512_add_epi64(sum, _mm512_and_si512(low, modulus));
+    let sum = _mm512_add_epi64(_mm512_and_si512(sum, modulus), _mm512_srli_epi64::<61>(sum));
+    try_sub_avx512(sum)
+}
+
+#[inline]
+#[target_feature(enable = "avx512f")]
+fn op_avx512(op: Op, x: __m512i, y: __m512i) -> __m512i {
+    let modulus = _mm512_set1_epi64(MOD as i64);
+    match op {
+        Op::Add => try_sub_avx512(_mm512_add_epi64(x, y)),
+        Op::Sub => try_sub_avx512(_mm512_add_epi64(x, _mm512_sub_epi64(modulus, y))),
+        Op::Mul => {
+            // Same as `op_avx2`.
+            let straight = mul_parts_avx512(x, y);
+            let crossed = mul_parts_avx512(x, _mm512_shuffle_epi32::<_MM_PERM_BADC>(y));
+            let left = _mm512_unpacklo_epi64(straight, crossed);
+            let right = _mm512_unpackhi_epi64(straight, crossed);
+            let right = _mm512_mask_sub_epi64(right, 0b0101_0101, modulus, right);
+            try_sub_avx512(_mm512_add_epi64(left, right))
+        }
+    }
+}
+
+/// `batch::apply` with four elements per register.
+///
+/// # Safety
+///
+/// The CPU must support AVX-512F.
+#[target_feature(enable = "avx512f")]
+pub(super) unsafe fn apply_avx512(op: Op, xs: &mut [Mersenne61Ext], ys: &[Mersenne61Ext]) {
+    let vectors = xs.len() / 4;
+    let x_ptr = xs.as_mut_ptr() as *mut __m512i;
+    let y_ptr = ys.as_ptr() as *const __m512i;
+    for i in 0..vectors {
+        // SAFETY: vector `i` is elements `4i` to `4i + 3`, see `apply_avx2`.
+        unsafe {
+            let x = _mm512_loadu_si512(x_ptr.add(i));
+            let y = _mm512_loadu_si512(y_ptr.add(i));
+            _mm512_storeu_si512(x_ptr.add(i), op_avx512(op, x, y));
+        }
+    }
+    for (x, y) in xs.iter_mut().zip(ys).skip(vectors * 4) {
+        op.scalar(x, *y);
+    }
+}
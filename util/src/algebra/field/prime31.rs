@@ -0,0 +1,150 @@
+use super::{Field, FieldChoice, OddCharacteristicField};
+use rand::Rng;
+use std::marker::PhantomData;
+
+/// Constants of a prime field below `2^31`. The fields only differ by
+/// them and possibly by a faster `reduce`.
+pub trait Prime31Params: std::fmt::Debug + Clone + Copy + Send + Sync + 'static {
+    /// The modulus, below `2^31`.
+    const MOD: u32;
+    const LOG_ORDER: u64;
+    const ROOT_OF_UNITY: u32;
+    const FIELD_CHOICE: FieldChoice;
+
+    /// `x mod MOD` for `x < 2^62`.
+    #[inline]
+    fn reduce(x: u64) -> u32 {
+        (x % Self::MOD as u64) as u32
+    }
+}
+
+/// An element of the prime field of `P::MOD` elements, in `[0, P::MOD)`.
+#[derive(Debug, Clone, Copy)]
+pub struct Prime31<P: Prime31Params> {
+    real: u32,
+    params: PhantomData<P>,
+}
+
+impl<P: Prime31Params> Prime31<P> {
+    /// The element `real`, which must be below `P::MOD`.
+    pub(super) const fn new(real: u32) -> Self {
+        Prime31 {
+            real,
+            params: PhantomData,
+        }
+    }
+
+    #[inline]
+    fn try_sub(x: u32) -> Self {
+        Self::new(if x >= P::MOD { x - P::MOD } else { x })
+    }
+}
+
+impl<P: Prime31Params> std::ops::Neg for Prime31<P> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::try_sub(P::MOD - self.real)
+    }
+}
+
+impl<P: Prime31Params> std::ops::Add for Prime31<P> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self::Output {
+        Self::try_sub(self.real + rhs.real)
+    }
+}
+
+impl<P: Prime31Params> std::ops::AddAssign for Prime31<P> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<P: Prime31Params> std::ops::Sub for Prime31<P> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self::try_sub(self.real + P::MOD - rhs.real)
+    }
+}
+
+impl<P: Prime31Params> std::ops::SubAssign for Prime31<P> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<P: Prime31Params> std::ops::Mul for Prime31<P> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        Self::new(P::reduce(self.real as u64 * rhs.real as u64))
+    }
+}
+
+impl<P: Prime31Params> std::ops::MulAssign for Prime31<P> {
+    fn mul_assign(&mut self, rhs: Self) {
+        *self = *self * rhs;
+    }
+}
+
+impl<P: Prime31Params> std::fmt::Display for Prime31<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.real)
+    }
+}
+
+impl<P: Prime31Params> std::cmp::PartialEq for Prime31<P> {
+    fn eq(&self, rhs: &Self) -> bool {
+        self.real == rhs.real
+    }
+}
+
+impl<P: Prime31Params> OddCharacteristicField for Prime31<P> {
+    const INVERSE_2: Self = Self::new(P::MOD / 2 + 1);
+}
+
+impl<P: Prime31Params> Field for Prime31<P> {
+    const LOG_ORDER: u64 = P::LOG_ORDER;
+    const ROOT_OF_UNITY: Self = Self::new(P::ROOT_OF_UNITY);
+    const ZERO: Self = Self::new(0);
+    const BYTE_SIZE: usize = 4;
+    const FIELD_CHOICE: FieldChoice = P::FIELD_CHOICE;
+    const CHARACTERISTIC_BITS: u32 = 32 - P::MOD.leading_zeros();
+
+    #[inline]
+    fn from_int(x: u64) -> Self {
+        Self::new((x % P::MOD as u64) as u32)
+    }
+
+    #[inline]
+    fn is_zero(&self) -> bool {
+        self.real == 0
+    }
+
+    #[inline]
+    fn random_element() -> Self {
+        Self::new(rand::thread_rng().gen_range(0..P::MOD))
+    }
+
+    #[inline]
+    fn from_uniform_bytes(bytes: &[u8; 32]) -> Self {
+        let x = u128::from_le_bytes(bytes[..16].try_into().unwrap());
+        Self::new((x % P::MOD as u128) as u32)
+    }
+
+    fn inverse(&self) -> Self {
+        self.pow(P::MOD as usize - 2)
+    }
+
+    #[inline]
+    fn to_bytes(&self) -> Vec<u8> {
+        self.real.to_le_bytes().to_vec()
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let real = u32::from_le_bytes(bytes.try_into().ok()?);
+        if real >= P::MOD {
+            return None;
+        }
+        Some(Self::new(real))
+    }
+}
@@ -9,7 +9,7 @@ pub struct Polynomial<T: Field> {
 impl<T: Field> Polynomial<T> {
     pub fn new(mut coefficients: Vec<T>) -> Polynomial<T> {
         let zero = T::from_int(0);
-        while *coefficients.last().unwrap() == zero {
+        while coefficients.last() == Some(&zero) {
             coefficients.pop();
         }
         Polynomial { coefficients }
@@ -43,6 +43,27 @@ impl<T: Field> Polynomial<T> {
         res
     }
 
+    /// Quotient and remainder of the division by `divisor`.
+    pub fn div_rem(&self, divisor: &Polynomial<T>) -> (Polynomial<T>, Polynomial<T>) {
+        let d = divisor.coefficients.len();
+        assert!(d > 0, "division by zero polynomial");
+        if self.coefficients.len() < d {
+            return (Polynomial::new(vec![]), self.clone());
+        }
+        let lead_inv = divisor.coefficients[d - 1].inverse();
+        let mut remainder = self.coefficients.clone();
+        let mut quotient = vec![T::from_int(0); remainder.len() - d + 1];
+        for i in (0..quotient.len()).rev() {
+            let q = remainder[i + d - 1] * lead_inv;
+            for (r, c) in remainder[i..].iter_mut().zip(&divisor.coefficients) {
+                *r -= q * *c;
+            }
+            quotient[i] = q;
+        }
+        remainder.truncate(d - 1);
+        (Polynomial::new(quotient), Polynomial::new(remainder))
+    }
+
     pub fn evaluation_over_coset(&self, coset: &Coset<T>) -> Vec<T> {
         coset.fft(self.coefficients.clone())
     }
@@ -137,6 +158,21 @@ impl<T: Field> MultilinearPolynomial<T> {
         res[0]
     }
 
+    /// Values over the hypercube, indexed like the coefficients.
+    pub fn evaluations(&self) -> Vec<T> {
+        let mut res = self.coefficients.clone();
+        let len = res.len();
+        for index in 0..self.variable_num() {
+            for i in 0..len {
+                if i & (1 << index) != 0 {
+                    let x = res[i - (1 << index)];
+                    res[i] += x;
+                }
+            }
+        }
+        res
+    }
+
     pub fn evaluate_as_polynomial(&self, point: T) -> T {
         let mut res = Field::from_int(0);
         for i in self.coefficients.iter().rev() {
@@ -193,4 +229,32 @@ mod test {
         let v = a + b + z * (a - b) * beta.inverse();
         assert_eq!(v * Mersenne61Ext::from_int(2).inverse(), c);
     }
+
+    #[test]
+    fn division() {
+        let a = Polynomial::<Fp64>::random_polynomial(20);
+        let b = Polynomial::random_polynomial(7);
+        let (q, r) = a.div_rem(&b);
+        assert_eq!(q.degree(), 13);
+        assert!(r.coefficients().len() < 7);
+        let x = Fp64::random_element();
+        assert_eq!(
+            a.evaluation_at(x),
+            q.evaluation_at(x) * b.evaluation_at(x) + r.evaluation_at(x)
+        );
+        let (q, r) = b.div_rem(&a);
+        assert!(q.coefficients().is_empty());
+        assert_eq!(r.coefficients(), b.coefficients());
+    }
+
+    #[test]
+    fn hypercube_evaluations() {
+        let poly = MultilinearPolynomial::<Fp64>::random_polynomial(6);
+        for (i, v) in poly.evaluations().into_iter().enumerate() {
+            let point = (0..6)
+                .map(|k| Fp64::from_int((i >> k & 1) as u64))
+                .collect();
+            assert_eq!(poly.evaluate(&point), v);
+        }
+    }
 }
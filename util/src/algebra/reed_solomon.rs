@@ -0,0 +1,188 @@
+//! Decoding of Reed–Solomon codewords with Gao's algorithm.
+//!
+//! A codeword is the list of values `f(x_i)` of a polynomial `f` of degree
+//! below `k` at `n` distinct points. With `g0 = prod_i (x - x_i)` and `g1`
+//! interpolating the received values, the extended Euclidean algorithm on
+//! `(g0, g1)` is stopped at the first remainder `g` of degree below
+//! `(n + k) / 2`, whose cofactor `v` of `g1` vanishes on the wrong values,
+//! and `f = g / v` as long as at most `(n - k) / 2` of them are wrong.
+
+use super::coset::Coset;
+use super::field::Field;
+use super::polynomial::Polynomial;
+
+/// The polynomial of degree below `xs.len()` taking the values `ys` at the
+/// distinct points `xs`, by Lagrange interpolation.
+pub fn interpolate<T: Field>(xs: &[T], ys: &[T]) -> Polynomial<T> {
+    let n = xs.len();
+    let master = vanishing(xs);
+    let mut res = vec![T::from_int(0); n];
+    for (x, y) in xs.iter().zip(ys) {
+        let (quotient, _) = master.div_rem(&Polynomial::new(vec![-*x, T::from_int(1)]));
+        let scale = *y * quotient.evaluation_at(*x).inverse();
+        for (r, q) in res.iter_mut().zip(quotient.coefficients()) {
+            *r += scale * *q;
+        }
+    }
+    Polynomial::new(res)
+}
+
+/// `prod_i (x - xs[i])`.
+fn vanishing<T: Field>(xs: &[T]) -> Polynomial<T> {
+    let mut res = vec![T::from_int(1)];
+    for x in xs {
+        let mut next = vec![T::from_int(0); res.len() + 1];
+        for (k, c) in res.iter().enumerate() {
+            next[k + 1] += *c;
+            next[k] -= *c * *x;
+        }
+        res = next;
+    }
+    Polynomial::new(res)
+}
+
+fn sub<T: Field>(a: &Polynomial<T>, b: &Polynomial<T>) -> Polynomial<T> {
+    let (a, b) = (a.coefficients(), b.coefficients());
+    let mut res = vec![T::from_int(0); a.len().max(b.len())];
+    for (r, x) in res.iter_mut().zip(a) {
+        *r += *x;
+    }
+    for (r, x) in res.iter_mut().zip(b) {
+        *r -= *x;
+    }
+    Polynomial::new(res)
+}
+
+/// The message polynomial from `g0` and `g1` of the module documentation.
+fn gao<T: Field>(
+    g0: Polynomial<T>,
+    g1: Polynomial<T>,
+    n: usize,
+    k: usize,
+) -> Option<Polynomial<T>> {
+    assert!(0 < k && k <= n);
+    // Degree below (n + k) / 2.
+    let max_len = (n + k).div_ceil(2);
+    let (mut r0, mut r1) = (g0, g1);
+    let (mut v0, mut v1) = (
+        Polynomial::new(vec![]),
+        Polynomial::new(vec![T::from_int(1)]),
+    );
+    while r1.coefficients().len() > max_len {
+        let (q, r) = r0.div_rem(&r1);
+        let v = sub(&v0, &Coset::mult(&q, &v1));
+        (r0, r1) = (r1, r);
+        (v0, v1) = (v1, v);
+    }
+    let (f, r) = r1.div_rem(&v1);
+    if r.coefficients().is_empty() && f.coefficients().len() <= k {
+        Some(f)
+    } else {
+        None
+    }
+}
+
+/// The indices at which `values` differ from `expected`, if there are at
+/// most `(n - k) / 2` of them.
+fn errors<T: Field>(values: &[T], expected: &[T], k: usize) -> Option<Vec<usize>> {
+    let res = (0..values.len())
+        .filter(|i| values[*i] != expected[*i])
+        .collect::<Vec<_>>();
+    if 2 * res.len() <= values.len() - k {
+        Some(res)
+    } else {
+        None
+    }
+}
+
+/// Decodes the values `ys` at the distinct points `xs` to the polynomial of
+/// degree below `k` they come from, returning it with the indices of the
+/// wrong values, or `None` if more than `(n - k) / 2` are wrong.
+pub fn decode<T: Field>(xs: &[T], ys: &[T], k: usize) -> Option<(Polynomial<T>, Vec<usize>)> {
+    assert_eq!(xs.len(), ys.len());
+    let f = gao(vanishing(xs), interpolate(xs, ys), xs.len(), k)?;
+    let expected = xs.iter().map(|x| f.evaluation_at(*x)).collect::<Vec<_>>();
+    let errors = errors(ys, &expected, k)?;
+    Some((f, errors))
+}
+
+/// `decode` for values over the whole of `coset`, interpolating them with
+/// an inverse FFT.
+pub fn decode_coset<T: Field>(
+    coset: &Coset<T>,
+    values: &[T],
+    k: usize,
+) -> Option<(Polynomial<T>, Vec<usize>)> {
+    let n = coset.size();
+    let mut g0 = vec![T::from_int(0); n + 1];
+    g0[0] = -coset.shift().pow(n);
+    g0[n] = T::from_int(1);
+    let g1 = Polynomial::new(coset.ifft(values.to_vec()));
+    let f = gao(Polynomial::new(g0), g1, n, k)?;
+    let errors = errors(values, &f.evaluation_over_coset(coset), k)?;
+    Some((f, errors))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::field::mersenne61_ext::Mersenne61Ext;
+    use rand::seq::index::sample;
+
+    type T = Mersenne61Ext;
+
+    /// Adds a random nonzero value at `error_num` random positions, returning
+    /// them in increasing order.
+    fn corrupt(values: &mut [T], error_num: usize) -> Vec<usize> {
+        let mut positions = sample(&mut rand::thread_rng(), values.len(), error_num).into_vec();
+        positions.sort();
+        for i in &positions {
+            values[*i] += T::random_element() + T::from_int(1);
+        }
+        positions
+    }
+
+    #[test]
+    fn interpolation() {
+        let f = Polynomial::<T>::random_polynomial(10);
+        let xs = (0..10).map(|_| T::random_element()).collect::<Vec<_>>();
+        let ys = xs.iter().map(|x| f.evaluation_at(*x)).collect::<Vec<_>>();
+        assert_eq!(interpolate(&xs, &ys).coefficients(), f.coefficients());
+    }
+
+    #[test]
+    fn decoding() {
+        let (n, k) = (40, 11);
+        let f = Polynomial::<T>::random_polynomial(k);
+        let xs = (0..n).map(|_| T::random_element()).collect::<Vec<_>>();
+        let ys = xs.iter().map(|x| f.evaluation_at(*x)).collect::<Vec<_>>();
+        for error_num in [0, 1, (n - k) / 2] {
+            let mut received = ys.clone();
+            let positions = corrupt(&mut received, error_num);
+            let (decoded, errors) = decode(&xs, &received, k).unwrap();
+            assert_eq!(decoded.coefficients(), f.coefficients());
+            assert_eq!(errors, positions);
+        }
+        let mut received = ys.clone();
+        corrupt(&mut received, (n - k) / 2 + 1);
+        assert!(decode(&xs, &received, k).is_none());
+    }
+
+    #[test]
+    fn coset_decoding() {
+        let (n, k) = (64, 16);
+        let coset = Coset::new(n, T::random_element());
+        let f = Polynomial::<T>::random_polynomial(k);
+        let values = f.evaluation_over_coset(&coset);
+        for error_num in [0, 5, (n - k) / 2] {
+            let mut received = values.clone();
+            let positions = corrupt(&mut received, error_num);
+            let (decoded, errors) = decode_coset(&coset, &received, k).unwrap();
+            assert_eq!(decoded.coefficients(), f.coefficients());
+            assert_eq!(errors, positions);
+        }
+        let mut received = values.clone();
+        corrupt(&mut received, (n - k) / 2 + 1);
+        assert!(decode_coset(&coset, &received, k).is_none());
+    }
+}
@@ -0,0 +1,382 @@
+//! Additive evaluation domains of binary fields, with the FFT of Lin, Chung
+//! and Han in their novel polynomial basis.
+//!
+//! For a basis `beta_0, ..., beta_{k-1}` over `GF(2)`, `W_i` is the
+//! polynomial vanishing on `span(beta_0, ..., beta_{i-1})`, which is
+//! `GF(2)`-linear, and `w_i = W_i / W_i(beta_i)`. The `j`-th basis
+//! polynomial is the product of `w_i` over the bits `i` of `j`. Writing
+//! `f = e(w_1) + w_0 * o(w_1)`, `e` and `o` are in the basis of the image
+//! of the domain by `w_1`, with basis `w_1(beta_1), ..., w_1(beta_{k-1})`,
+//! and since `w_0(x + beta_0) = w_0(x) + 1`, the values of `f` at `x` and
+//! `x + beta_0` are `e + w_0(x) * o` and that plus `o`.
+
+use super::domain::EvaluationDomain;
+use super::field::{BinaryField, ExtensionField, Field};
+use crate::parallel;
+use std::sync::{Arc, OnceLock};
+
+/// `shift + span(basis)`, element `i` being `shift` plus the `basis[k - 1 - t]`
+/// for the bits `t` of `i`, so that elements `i` and `i + size / 2` differ
+/// by `basis[0]`. The twiddle table is computed on first use and shared
+/// between clones, as for `Coset`.
+#[derive(Debug, Clone)]
+pub struct AffineSubspace<T: Field> {
+    shift: T,
+    basis: Vec<T>,
+    /// `W_i(beta_i)` and its inverse.
+    vanishing: Vec<T>,
+    vanishing_inv: Vec<T>,
+    twiddles: Arc<OnceLock<Vec<T>>>,
+}
+
+impl<T: Field> AffineSubspace<T> {
+    /// `shift + span(basis)` in a field of characteristic 2. Panics if
+    /// `basis` is not linearly independent over `GF(2)`.
+    pub fn new(shift: T, basis: Vec<T>) -> Self {
+        // `W_i(beta_j)` for `j >= i`, using
+        // `W_{i+1}(x) = W_i(x) * (W_i(x) + W_i(beta_i))`.
+        let mut values = basis.clone();
+        let mut vanishing = vec![];
+        for i in 0..basis.len() {
+            let w = values[i];
+            assert!(!w.is_zero(), "basis not linearly independent");
+            vanishing.push(w);
+            for v in &mut values[i + 1..] {
+                *v *= *v + w;
+            }
+        }
+        AffineSubspace {
+            shift,
+            basis,
+            vanishing_inv: vanishing.iter().map(|x| x.inverse()).collect(),
+            vanishing,
+            twiddles: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// `shift + span(T::basis(0), ..., T::basis(log_size - 1))`.
+    pub fn standard(log_size: usize, shift: T) -> Self
+    where
+        T: BinaryField,
+    {
+        assert!(log_size <= T::BITS);
+        AffineSubspace::new(shift, (0..log_size).map(T::basis).collect())
+    }
+
+    pub fn size(&self) -> usize {
+        1 << self.basis.len()
+    }
+
+    pub fn shift(&self) -> T {
+        self.shift
+    }
+
+    pub fn basis(&self) -> &Vec<T> {
+        &self.basis
+    }
+
+    pub fn element_at(&self, index: usize) -> T {
+        debug_assert!(index < self.size());
+        let k = self.basis.len();
+        (0..k)
+            .filter(|t| (index >> t) & 1 == 1)
+            .fold(self.shift, |acc, t| acc + self.basis[k - 1 - t])
+    }
+
+    pub fn all_elements(&self) -> Vec<T> {
+        let k = self.basis.len();
+        let mut res = vec![self.shift];
+        for t in 0..k {
+            let beta = self.basis[k - 1 - t];
+            res.extend((0..res.len()).map(|i| res[i] + beta).collect::<Vec<_>>());
+        }
+        res
+    }
+
+    /// `w_0(x), ..., w_{k-1}(x)`.
+    fn basis_polynomials(&self, x: T) -> Vec<T> {
+        let mut w = x;
+        let mut res = Vec::with_capacity(self.basis.len());
+        for (v, v_inv) in self.vanishing.iter().zip(&self.vanishing_inv) {
+            res.push(w * *v_inv);
+            w *= w + *v;
+        }
+        res
+    }
+
+    /// `w_1`, or `W_1` when there is no `beta_1` to normalize it with.
+    fn folding_map(&self, x: T) -> T {
+        let w = x * (x + self.basis[0]);
+        match self.vanishing_inv.get(1) {
+            Some(v_inv) => w * *v_inv,
+            None => w,
+        }
+    }
+
+    /// The image by `w_1`, whose element `i` is the image of elements `i`
+    /// and `i + size / 2`.
+    pub fn folded(&self) -> Self {
+        assert!(!self.basis.is_empty());
+        AffineSubspace::new(
+            self.folding_map(self.shift),
+            self.basis[1..]
+                .iter()
+                .map(|x| self.folding_map(*x))
+                .collect(),
+        )
+    }
+
+    /// The folded domains down to dimension 1, starting from this one.
+    fn folding_chain(&self) -> Vec<Self> {
+        let mut res = vec![self.clone()];
+        while res.len() < self.basis.len() {
+            let next = res.last().unwrap().folded();
+            res.push(next);
+        }
+        res
+    }
+
+    /// `w_0` at the first half of the elements.
+    pub fn twiddles(&self) -> &Vec<T> {
+        self.twiddles.get_or_init(|| {
+            let mut elements = self.all_elements();
+            elements.truncate(self.size() / 2);
+            let beta_inv = self.vanishing_inv[0];
+            parallel::map_slice(&elements, |x| *x * beta_inv)
+        })
+    }
+
+    /// Values over the domain of the polynomial with `coefficients` in the
+    /// novel basis. Layer `d` of the butterflies combines the polynomials
+    /// of the coefficients of indices `r` and `r + 2^d` modulo `2^(d + 1)`
+    /// over the domain folded `d` times, the value of the one of `r` at
+    /// element `i` being stored at `i * 2^d + r`.
+    pub fn fft(&self, mut coefficients: Vec<T>) -> Vec<T> {
+        let n = self.size();
+        assert!(coefficients.len() <= n);
+        coefficients.resize(n, T::ZERO);
+        let chain = self.folding_chain();
+        let mut values = coefficients;
+        for d in (0..self.basis.len()).rev() {
+            let twiddles = chain[d].twiddles();
+            let mask = (1 << d) - 1;
+            values = parallel::map(n, |p| {
+                let j = p % (n / 2);
+                let src = ((j >> d) << (d + 1)) | (j & mask);
+                let o = values[src + (1 << d)];
+                let v = values[src] + twiddles[j >> d] * o;
+                if p < n / 2 {
+                    v
+                } else {
+                    v + o
+                }
+            });
+        }
+        values
+    }
+
+    pub fn ifft(&self, evaluations: Vec<T>) -> Vec<T> {
+        let n = self.size();
+        assert_eq!(evaluations.len(), n);
+        let chain = self.folding_chain();
+        let mut values = evaluations;
+        for d in 0..self.basis.len() {
+            let twiddles = chain[d].twiddles();
+            let mask = (1 << d) - 1;
+            values = parallel::map(n, |p| {
+                let j = ((p >> (d + 1)) << d) | (p & mask);
+                let v = values[j];
+                let o = values[j + n / 2] - v;
+                if (p >> d) & 1 == 1 {
+                    o
+                } else {
+                    v - twiddles[p >> (d + 1)] * o
+                }
+            });
+        }
+        values
+    }
+
+    /// The subspace as one of the subfield `F`, if it lies in it.
+    pub fn to_base<F: Field>(&self) -> Option<AffineSubspace<F>>
+    where
+        T: ExtensionField<F>,
+    {
+        let basis = self
+            .basis
+            .iter()
+            .map(|x| x.to_base())
+            .collect::<Option<Vec<F>>>()?;
+        Some(AffineSubspace::new(self.shift.to_base()?, basis))
+    }
+}
+
+impl<T: BinaryField> EvaluationDomain<T> for AffineSubspace<T> {
+    fn with_shift(log_size: usize, shift: T) -> Option<Self> {
+        (log_size <= T::BITS).then(|| AffineSubspace::standard(log_size, shift))
+    }
+
+    fn size(&self) -> usize {
+        self.size()
+    }
+
+    fn folded(&self) -> Self {
+        self.folded()
+    }
+
+    fn compute_element_at(&self, index: usize) -> T {
+        self.element_at(index)
+    }
+
+    fn fft(&self, coefficients: Vec<T>) -> Vec<T> {
+        self.fft(coefficients)
+    }
+
+    fn ifft(&self, evaluations: Vec<T>) -> Vec<T> {
+        self.ifft(evaluations)
+    }
+
+    fn lies_in<F: Field>(&self) -> bool
+    where
+        T: ExtensionField<F>,
+    {
+        self.to_base::<F>().is_some()
+    }
+
+    fn fft_base<F: Field>(&self, coefficients: Vec<F>) -> Option<Vec<F>>
+    where
+        T: ExtensionField<F>,
+    {
+        Some(self.to_base::<F>()?.fft(coefficients))
+    }
+
+    fn ifft_base<F: Field>(&self, evaluations: Vec<F>) -> Option<Vec<F>>
+    where
+        T: ExtensionField<F>,
+    {
+        Some(self.to_base::<F>()?.ifft(evaluations))
+    }
+
+    fn evaluate_at(&self, coefficients: &[T], index: usize) -> T {
+        let w = self.basis_polynomials(self.element_at(index));
+        let mut basis_values = vec![T::from_int(1)];
+        for x in &w {
+            if basis_values.len() >= coefficients.len() {
+                break;
+            }
+            basis_values.extend(basis_values.iter().map(|y| *y * *x).collect::<Vec<_>>());
+        }
+        assert!(basis_values.len() >= coefficients.len());
+        coefficients
+            .iter()
+            .zip(&basis_values)
+            .fold(T::ZERO, |acc, (c, y)| acc + *c * *y)
+    }
+
+    fn fold_twiddles(&self) -> &[T] {
+        self.twiddles()
+    }
+
+    fn compute_fold_twiddle(&self, index: usize) -> T {
+        self.element_at(index) * self.vanishing_inv[0]
+    }
+
+    /// `c = 1` and the twiddle is `w_0(x)`.
+    #[inline]
+    fn split(x: T, nx: T, twiddle: T) -> (T, T) {
+        let o = nx - x;
+        (x - twiddle * o, o)
+    }
+
+    fn fold_scale(&self) -> T {
+        T::from_int(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::field::binary_tower::BinaryTower128;
+
+    type T = BinaryTower128;
+
+    /// Value at `x` of the polynomial with `coefficients` in the novel
+    /// basis, from its definition.
+    fn naive_evaluation(subspace: &AffineSubspace<T>, coefficients: &[T], x: T) -> T {
+        let k = subspace.basis().len();
+        let vanishing = |i: usize, x: T| {
+            (0..1 << i)
+                .map(|j| AffineSubspace::new(T::ZERO, subspace.basis()[..i].to_vec()).element_at(j))
+                .fold(T::from_int(1), |acc, u| acc * (x - u))
+        };
+        let w: Vec<_> = (0..k)
+            .map(|i| vanishing(i, x) * vanishing(i, subspace.basis()[i]).inverse())
+            .collect();
+        coefficients
+            .iter()
+            .enumerate()
+            .map(|(j, c)| {
+                (0..k)
+                    .filter(|i| (j >> i) & 1 == 1)
+                    .fold(*c, |acc, i| acc * w[i])
+            })
+            .fold(T::ZERO, |acc, y| acc + y)
+    }
+
+    #[test]
+    fn fft_and_ifft() {
+        let subspace = AffineSubspace::standard(5, T::random_element());
+        let coefficients: Vec<_> = (0..32).map(|_| T::random_element()).collect();
+        let evaluations = subspace.fft(coefficients.clone());
+        for (i, x) in subspace.all_elements().iter().enumerate() {
+            assert_eq!(*x, subspace.element_at(i));
+            assert_eq!(
+                evaluations[i],
+                naive_evaluation(&subspace, &coefficients, *x)
+            );
+            assert_eq!(evaluations[i], subspace.evaluate_at(&coefficients, i));
+        }
+        assert_eq!(subspace.ifft(evaluations), coefficients);
+
+        let basis = (0..4).map(|_| T::random_element()).collect();
+        let subspace = AffineSubspace::new(T::random_element(), basis);
+        let coefficients: Vec<_> = (0..5).map(|_| T::random_element()).collect();
+        let evaluations = subspace.fft(coefficients.clone());
+        for (i, y) in evaluations.iter().enumerate() {
+            let x = subspace.element_at(i);
+            assert_eq!(*y, naive_evaluation(&subspace, &coefficients, x));
+        }
+        let mut padded = coefficients.clone();
+        padded.resize(16, T::ZERO);
+        assert_eq!(subspace.ifft(evaluations), padded);
+    }
+
+    #[test]
+    fn folding() {
+        let subspace = AffineSubspace::standard(6, T::random_element());
+        let folded = subspace.folded();
+        assert_eq!(folded.size(), 32);
+        let half = subspace.size() / 2;
+        for i in 0..half {
+            let (x, nx) = (subspace.element_at(i), subspace.element_at(i + half));
+            assert_eq!(x + nx, subspace.basis()[0]);
+            assert_eq!(subspace.folding_map(x), folded.element_at(i));
+            assert_eq!(subspace.folding_map(nx), folded.element_at(i));
+            assert_eq!(subspace.twiddles()[i], subspace.compute_fold_twiddle(i));
+        }
+
+        // Folding the values of `f` by `r` gives those of `e + r * o`.
+        let coefficients: Vec<_> = (0..64).map(|_| T::random_element()).collect();
+        let values = subspace.fft(coefficients.clone());
+        let r = T::random_element();
+        let folded_values: Vec<_> = (0..half)
+            .map(|i| {
+                let (e, o) =
+                    AffineSubspace::split(values[i], values[i + half], subspace.twiddles()[i]);
+                e + r * o
+            })
+            .collect();
+        let folded_coefficients: Vec<_> = coefficients.chunks(2).map(|x| x[0] + r * x[1]).collect();
+        assert_eq!(folded.fft(folded_coefficients), folded_values);
+    }
+}
@@ -0,0 +1,205 @@
+use crate::algebra::field::Field;
+use crate::merkle_tree::MerkleHasher;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    UnexpectedEnd,
+    TrailingBytes,
+    BadMagic,
+    UnsupportedVersion(u8),
+    InvalidFieldElement,
+    InvalidLength,
+    DuplicateQueryIndex,
+    InvalidParams,
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DecodeError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            DecodeError::TrailingBytes => write!(f, "trailing bytes after proof"),
+            DecodeError::BadMagic => write!(f, "bad magic bytes"),
+            DecodeError::UnsupportedVersion(v) => write!(f, "unsupported version {}", v),
+            DecodeError::InvalidFieldElement => write!(f, "non-canonical field element"),
+            DecodeError::InvalidLength => write!(f, "invalid length"),
+            DecodeError::DuplicateQueryIndex => write!(f, "duplicate query index"),
+            DecodeError::InvalidParams => write!(f, "invalid protocol parameters"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Little-endian writer for the binary proof encodings. Every variable
+/// length item is prefixed by its length as a `u64`.
+#[derive(Debug, Clone, Default)]
+pub struct Encoder {
+    bytes: Vec<u8>,
+}
+
+impl Encoder {
+    pub fn new() -> Self {
+        Encoder { bytes: vec![] }
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn write_u8(&mut self, x: u8) {
+        self.bytes.push(x);
+    }
+
+    pub fn write_u64(&mut self, x: u64) {
+        self.bytes.extend_from_slice(&x.to_le_bytes());
+    }
+
+    pub fn write_usize(&mut self, x: usize) {
+        self.write_u64(x as u64);
+    }
+
+    pub fn write_raw(&mut self, bytes: &[u8]) {
+        self.bytes.extend_from_slice(bytes);
+    }
+
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        self.write_usize(bytes.len());
+        self.write_raw(bytes);
+    }
+
+    pub fn write_root(&mut self, root: &impl AsRef<[u8]>) {
+        self.write_raw(root.as_ref());
+    }
+
+    pub fn write_roots(&mut self, roots: &[impl AsRef<[u8]>]) {
+        self.write_usize(roots.len());
+        for root in roots {
+            self.write_root(root);
+        }
+    }
+
+    pub fn write_field<T: Field>(&mut self, x: &T) {
+        self.write_raw(&x.to_bytes());
+    }
+
+    pub fn write_fields<T: Field>(&mut self, v: &[T]) {
+        self.write_usize(v.len());
+        for x in v {
+            self.write_field(x);
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Decoder { bytes }
+    }
+
+    pub fn finish(self) -> Result<(), DecodeError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(DecodeError::TrailingBytes)
+        }
+    }
+
+    pub fn read_raw(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < len {
+            return Err(DecodeError::UnexpectedEnd);
+        }
+        let (res, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(res)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.read_raw(1)?[0])
+    }
+
+    pub fn read_u64(&mut self) -> Result<u64, DecodeError> {
+        Ok(u64::from_le_bytes(self.read_raw(8)?.try_into().unwrap()))
+    }
+
+    pub fn read_usize(&mut self) -> Result<usize, DecodeError> {
+        let x = self.read_u64()?;
+        usize::try_from(x).map_err(|_| DecodeError::InvalidLength)
+    }
+
+    /// Reads a length prefix for `item_size`-byte items, rejecting lengths
+    /// that cannot fit in the remaining input before anything is allocated.
+    pub fn read_len(&mut self, item_size: usize) -> Result<usize, DecodeError> {
+        let len = self.read_usize()?;
+        match len.checked_mul(item_size) {
+            Some(total) if total <= self.bytes.len() => Ok(len),
+            _ => Err(DecodeError::InvalidLength),
+        }
+    }
+
+    pub fn read_bytes(&mut self) -> Result<Vec<u8>, DecodeError> {
+        let len = self.read_len(1)?;
+        Ok(self.read_raw(len)?.to_vec())
+    }
+
+    pub fn read_root<H: MerkleHasher>(&mut self) -> Result<H::Hash, DecodeError> {
+        let bytes = self.read_raw(H::DIGEST_SIZE)?.to_vec();
+        H::Hash::try_from(bytes).map_err(|_| DecodeError::InvalidLength)
+    }
+
+    pub fn read_roots<H: MerkleHasher>(&mut self) -> Result<Vec<H::Hash>, DecodeError> {
+        let len = self.read_len(H::DIGEST_SIZE)?;
+        (0..len).map(|_| self.read_root::<H>()).collect()
+    }
+
+    pub fn read_field<T: Field>(&mut self) -> Result<T, DecodeError> {
+        T::from_bytes(self.read_raw(T::BYTE_SIZE)?).ok_or(DecodeError::InvalidFieldElement)
+    }
+
+    pub fn read_fields<T: Field>(&mut self) -> Result<Vec<T>, DecodeError> {
+        let len = self.read_len(T::BYTE_SIZE)?;
+        (0..len).map(|_| self.read_field()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::field::mersenne61_ext::Mersenne61Ext;
+    use crate::merkle_tree::{Blake3Algorithm, MERKLE_ROOT_SIZE};
+
+    #[test]
+    fn round_trip() {
+        let v = (0..10)
+            .map(|_| Mersenne61Ext::random_element())
+            .collect::<Vec<_>>();
+        let mut encoder = Encoder::new();
+        encoder.write_u8(7);
+        encoder.write_fields(&v);
+        encoder.write_bytes(b"abc");
+        encoder.write_roots(&[[3u8; MERKLE_ROOT_SIZE]]);
+        let bytes = encoder.into_bytes();
+
+        let mut decoder = Decoder::new(&bytes);
+        assert_eq!(decoder.read_u8(), Ok(7));
+        assert_eq!(decoder.read_fields::<Mersenne61Ext>(), Ok(v));
+        assert_eq!(decoder.read_bytes(), Ok(b"abc".to_vec()));
+        assert_eq!(
+            decoder.read_roots::<Blake3Algorithm>(),
+            Ok(vec![[3u8; MERKLE_ROOT_SIZE]])
+        );
+        assert_eq!(decoder.finish(), Ok(()));
+
+        let mut decoder = Decoder::new(&bytes[..bytes.len() - 1]);
+        decoder.read_u8().unwrap();
+        decoder.read_fields::<Mersenne61Ext>().unwrap();
+        decoder.read_bytes().unwrap();
+        assert_eq!(
+            decoder.read_roots::<Blake3Algorithm>(),
+            Err(DecodeError::InvalidLength)
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Complaint phase of `vss` and `avss`, run after the dealer's proofs are
+//! sent.
+//!
+//! A party whose share does not verify broadcasts a `Complaint` with its
+//! index, signed with its key over the root of the share commitment it
+//! received, so that nobody can complain in its name to have its share
+//! revealed. The dealer answers every complaint with a public `Response`
+//! revealing that party's share and the proofs of it, which every party
+//! checks against the commitments the complaining party received, all of
+//! them being broadcast. The dealer is disqualified if more than the
+//! threshold of parties complain, or if it fails to answer a complaint
+//! with a share that verifies. Complaints whose signature does not verify
+//! are ignored by everyone.
+
+use crate::algebra::{field::Field, polynomial::MultilinearPolynomial};
+use crate::query_result::QueryResult;
+use crate::verification_error::VerificationError;
+pub use ed25519_dalek::{Signature, SigningKey, VerifyingKey};
+use ed25519_dalek::{Signer, Verifier};
+
+const COMPLAINT_LABEL: &[u8] = b"AVSS complaint";
+
+/// A party of a dealing, as seen by the complaint phase.
+pub trait DisputeParty<T: Field> {
+    /// Checks the proofs of this party's share.
+    fn verify_share(
+        &self,
+        folding_proofs: &[QueryResult<T>],
+        function_proofs: &[QueryResult<T>],
+    ) -> Result<(), VerificationError>;
+
+    /// Checks the share the dealer revealed for this party after its
+    /// complaint, against the commitments this party received.
+    fn verify_response(&self, response: &Response<T>) -> Result<(), VerificationError>;
+
+    fn set_share(&mut self, final_poly: &MultilinearPolynomial<T>);
+
+    /// Root of the commitment to this party's share it received, empty
+    /// before the dealer committed.
+    fn share_commitment(&self) -> Vec<u8>;
+
+    fn signing_key(&self) -> &SigningKey;
+
+    /// Key the complaints of this party are checked with, published before
+    /// the dealing.
+    fn verifying_key(&self) -> VerifyingKey {
+        self.signing_key().verifying_key()
+    }
+}
+
+/// A fresh key for a party to sign its complaints with.
+pub fn random_signing_key() -> SigningKey {
+    SigningKey::generate(&mut rand::thread_rng())
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Complaint {
+    /// Index of the complaining party.
+    pub party: usize,
+    pub signature: Signature,
+}
+
+impl Complaint {
+    /// The complaint of `party` about the share committed under
+    /// `share_commitment`.
+    pub fn new(party: usize, key: &SigningKey, share_commitment: &[u8]) -> Self {
+        Complaint {
+            party,
+            signature: key.sign(&Self::message(party, share_commitment)),
+        }
+    }
+
+    /// Whether the complaining party signed this complaint with `key` about
+    /// the share committed under `share_commitment`.
+    pub fn verify(&self, key: &VerifyingKey, share_commitment: &[u8]) -> bool {
+        key.verify(
+            &Self::message(self.party, share_commitment),
+            &self.signature,
+        )
+        .is_ok()
+    }
+
+    fn message(party: usize, share_commitment: &[u8]) -> Vec<u8> {
+        let mut message = COMPLAINT_LABEL.to_vec();
+        message.extend_from_slice(&(party as u64).to_le_bytes());
+        message.extend_from_slice(share_commitment);
+        message
+    }
+}
+
+/// The dealer's public answer to a complaint.
+#[derive(Clone)]
+pub struct Response<T: Field> {
+    pub party: usize,
+    pub final_poly: MultilinearPolynomial<T>,
+    pub folding_proofs: Vec<QueryResult<T>>,
+    pub function_proofs: Vec<QueryResult<T>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Disqualification {
+    /// More parties complained than the threshold allows.
+    TooManyComplaints { complaints: usize, threshold: usize },
+    /// The complaint of this party was not answered.
+    MissingResponse(usize),
+    /// The share revealed for this party does not verify.
+    InvalidResponse(usize, VerificationError),
+}
+
+impl std::fmt::Display for Disqualification {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Disqualification::TooManyComplaints {
+                complaints,
+                threshold,
+            } => write!(
+                f,
+                "{} complaints exceed the threshold {}",
+                complaints, threshold
+            ),
+            Disqualification::MissingResponse(party) => {
+                write!(f, "no response to the complaint of party {}", party)
+            }
+            Disqualification::InvalidResponse(party, error) => {
+                write!(f, "invalid response to party {}: {}", party, error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Disqualification {}
+
+/// The complaints of the parties whose proofs fail, `folding_proofs` and
+/// `function_proofs` being the output of the dealer's `query`.
+pub fn complaints<T: Field, P: DisputeParty<T>>(
+    parties: &[P],
+    folding_proofs: &[Vec<QueryResult<T>>],
+    function_proofs: &[Vec<QueryResult<T>>],
+) -> Vec<Complaint> {
+    (0..parties.len())
+        .filter(|i| {
+            let folding = folding_proofs
+                .iter()
+                .map(|x| x[i % x.len()].clone())
+                .collect::<Vec<_>>();
+            let function = function_proofs
+                .iter()
+                .map(|x| x[i % x.len()].clone())
+                .collect::<Vec<_>>();
+            parties[*i].verify_share(&folding, &function).is_err()
+        })
+        .map(|i| {
+            let party = &parties[i];
+            Complaint::new(i, party.signing_key(), &party.share_commitment())
+        })
+        .collect()
+}
+
+/// Indices of the parties with a signed complaint among `complaints`,
+/// sorted and without duplicates.
+pub fn authenticated<T: Field, P: DisputeParty<T>>(
+    parties: &[P],
+    complaints: &[Complaint],
+) -> Vec<usize> {
+    let mut complaining = complaints
+        .iter()
+        .filter(|x| {
+            parties
+                .get(x.party)
+                .is_some_and(|party| x.verify(&party.verifying_key(), &party.share_commitment()))
+        })
+        .map(|x| x.party)
+        .collect::<Vec<_>>();
+    complaining.sort();
+    complaining.dedup();
+    complaining
+}
+
+/// Checks the dealer's `responses` to `complaints` as every party does,
+/// handing the revealed shares to the complaining parties if the dealer is
+/// not disqualified.
+pub fn resolve<T: Field, P: DisputeParty<T>>(
+    parties: &mut [P],
+    complaints: &[Complaint],
+    responses: &[Response<T>],
+    threshold: usize,
+) -> Result<(), Disqualification> {
+    let complaining = authenticated(parties, complaints);
+    if complaining.len() > threshold {
+        return Err(Disqualification::TooManyComplaints {
+            complaints: complaining.len(),
+            threshold,
+        });
+    }
+    let mut revealed = vec![];
+    for party in complaining {
+        let response = responses
+            .iter()
+            .find(|x| x.party == party)
+            .ok_or(Disqualification::MissingResponse(party))?;
+        parties[party]
+            .verify_response(response)
+            .map_err(|e| Disqualification::InvalidResponse(party, e))?;
+        revealed.push(response);
+    }
+    for response in revealed {
+        parties[response.party].set_share(&response.final_poly);
+    }
+    Ok(())
+}
@@ -1,14 +1,24 @@
 pub mod algebra {
     pub mod coset;
+    pub mod domain;
     pub mod field;
     pub mod polynomial;
+    pub mod reed_solomon;
+    pub mod subspace;
 }
+pub mod codec;
+pub mod dispute;
 pub mod merkle_tree;
+pub mod multilinear_pcs;
+pub mod parallel;
+pub mod params;
+pub mod poseidon;
+pub mod proof_of_work;
 pub mod query_result;
 pub mod random_oracle;
-
-pub const CODE_RATE: usize = 3;
-pub const SECURITY_BITS: usize = 100;
+pub mod sumcheck;
+pub mod transcript;
+pub mod verification_error;
 
 pub fn split_n(mut n: usize) -> Vec<usize> {
     let mut res = vec![];
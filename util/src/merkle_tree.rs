@@ -1,47 +1,132 @@
-use rs_merkle::{Hasher, MerkleProof, MerkleTree};
+use rand::Rng;
+use rs_merkle::{MerkleProof, MerkleTree};
+use sha2::Digest;
+use std::marker::PhantomData;
+
+use crate::parallel;
+
+/// Hash function used for Merkle leaves and inner nodes. The digest length
+/// is a property of the hasher, so roots and authentication paths are sized
+/// by `DIGEST_SIZE` rather than a fixed constant.
+pub trait MerkleHasher: Clone + std::fmt::Debug + 'static {
+    type Hash: Copy
+        + PartialEq
+        + std::fmt::Debug
+        + AsRef<[u8]>
+        + Into<Vec<u8>>
+        + TryFrom<Vec<u8>>
+        + Send
+        + Sync;
+    const DIGEST_SIZE: usize;
+
+    fn hash(data: &[u8]) -> Self::Hash;
+}
 
 #[derive(Debug, Clone)]
 pub struct Blake3Algorithm {}
 
-impl Hasher for Blake3Algorithm {
+impl MerkleHasher for Blake3Algorithm {
     type Hash = [u8; MERKLE_ROOT_SIZE];
+    const DIGEST_SIZE: usize = MERKLE_ROOT_SIZE;
 
     fn hash(data: &[u8]) -> [u8; MERKLE_ROOT_SIZE] {
         blake3::hash(data).into()
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct Sha256Algorithm {}
+
+impl MerkleHasher for Sha256Algorithm {
+    type Hash = [u8; 32];
+    const DIGEST_SIZE: usize = 32;
+
+    fn hash(data: &[u8]) -> [u8; 32] {
+        sha2::Sha256::digest(data).into()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Keccak256Algorithm {}
+
+impl MerkleHasher for Keccak256Algorithm {
+    type Hash = [u8; 32];
+    const DIGEST_SIZE: usize = 32;
+
+    fn hash(data: &[u8]) -> [u8; 32] {
+        sha3::Keccak256::digest(data).into()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct RsMerkleHasher<H: MerkleHasher>(PhantomData<H>);
+
+impl<H: MerkleHasher> rs_merkle::Hasher for RsMerkleHasher<H> {
+    type Hash = H::Hash;
+
+    fn hash(data: &[u8]) -> H::Hash {
+        H::hash(data)
+    }
+}
+
+/// Digest size of the default hasher, `Blake3Algorithm`.
 pub const MERKLE_ROOT_SIZE: usize = 32;
+/// Length of the random salt appended to each leaf of a hiding tree.
+pub const SALT_SIZE: usize = 16;
+pub type Salt = [u8; SALT_SIZE];
+
 #[derive(Clone)]
-pub struct MerkleTreeProver {
-    pub merkle_tree: MerkleTree<Blake3Algorithm>,
+pub struct MerkleTreeProver<H: MerkleHasher = Blake3Algorithm> {
+    merkle_tree: MerkleTree<RsMerkleHasher<H>>,
     leave_num: usize,
+    salts: Vec<Salt>,
 }
 
 #[derive(Debug, Clone)]
-pub struct MerkleTreeVerifier {
-    pub merkle_root: [u8; MERKLE_ROOT_SIZE],
+pub struct MerkleTreeVerifier<H: MerkleHasher = Blake3Algorithm> {
+    pub merkle_root: H::Hash,
     pub leave_number: usize,
 }
 
-impl MerkleTreeProver {
+impl<H: MerkleHasher> MerkleTreeProver<H> {
     pub fn new(leaf_values: Vec<Vec<u8>>) -> Self {
-        let leaves = leaf_values
-            .iter()
-            .map(|x| Blake3Algorithm::hash(x))
-            .collect::<Vec<_>>();
-        let merkle_tree = MerkleTree::<Blake3Algorithm>::from_leaves(&leaves);
+        let leaves = parallel::map_slice(&leaf_values, |x| H::hash(x));
+        let merkle_tree = MerkleTree::<RsMerkleHasher<H>>::from_leaves(&leaves);
         Self {
             merkle_tree,
             leave_num: leaf_values.len(),
+            salts: vec![],
         }
     }
 
+    /// Appends a random salt to every leaf, so that the root and the paths
+    /// reveal nothing about unopened leaves. Openings must be accompanied by
+    /// `salts`.
+    pub fn new_salted(mut leaf_values: Vec<Vec<u8>>) -> Self {
+        let mut rng = rand::thread_rng();
+        let salts: Vec<Salt> = (0..leaf_values.len()).map(|_| rng.gen()).collect();
+        for (leaf, salt) in leaf_values.iter_mut().zip(&salts) {
+            leaf.extend_from_slice(salt);
+        }
+        Self {
+            salts,
+            ..Self::new(leaf_values)
+        }
+    }
+
+    /// Salts of the given leaves, empty if the tree is not salted.
+    pub fn salts(&self, leaf_indices: &Vec<usize>) -> Vec<Salt> {
+        if self.salts.is_empty() {
+            return vec![];
+        }
+        leaf_indices.iter().map(|x| self.salts[*x]).collect()
+    }
+
     pub fn leave_num(&self) -> usize {
         self.leave_num
     }
 
-    pub fn commit(&self) -> [u8; MERKLE_ROOT_SIZE] {
+    pub fn commit(&self) -> H::Hash {
         self.merkle_tree.root().unwrap()
     }
 
@@ -50,11 +135,11 @@ impl MerkleTreeProver {
     }
 }
 
-impl MerkleTreeVerifier {
-    pub fn new(leave_number: usize, merkle_root: &[u8; MERKLE_ROOT_SIZE]) -> Self {
+impl<H: MerkleHasher> MerkleTreeVerifier<H> {
+    pub fn new(leave_number: usize, merkle_root: &H::Hash) -> Self {
         Self {
             leave_number,
-            merkle_root: merkle_root.clone(),
+            merkle_root: *merkle_root,
         }
     }
 
@@ -64,9 +149,11 @@ impl MerkleTreeVerifier {
         indices: &Vec<usize>,
         leaves: &Vec<Vec<u8>>,
     ) -> bool {
-        let proof = MerkleProof::<Blake3Algorithm>::try_from(proof_bytes).unwrap();
-        let leaves_to_prove: Vec<[u8; MERKLE_ROOT_SIZE]> =
-            leaves.iter().map(|x| Blake3Algorithm::hash(x)).collect();
+        let proof = match MerkleProof::<RsMerkleHasher<H>>::try_from(proof_bytes) {
+            Ok(proof) => proof,
+            Err(_) => return false,
+        };
+        let leaves_to_prove: Vec<H::Hash> = leaves.iter().map(|x| H::hash(x)).collect();
         proof.verify(
             self.merkle_root,
             indices,
@@ -80,9 +167,9 @@ impl MerkleTreeVerifier {
 mod tests {
     use super::*;
     use crate::algebra::field::{as_bytes_vec, mersenne61_ext::Mersenne61Ext, Field};
+    use crate::poseidon::PoseidonAlgorithm;
 
-    #[test]
-    fn commit_and_open() {
+    fn commit_and_open<H: MerkleHasher>() {
         let leaf_values = vec![
             as_bytes_vec(&[Mersenne61Ext::from_int(1), Mersenne61Ext::from_int(2)]),
             as_bytes_vec(&[Mersenne61Ext::from_int(3), Mersenne61Ext::from_int(4)]),
@@ -93,16 +180,50 @@ mod tests {
             as_bytes_vec(&[Mersenne61Ext::from_int(13), Mersenne61Ext::from_int(14)]),
         ];
         let leave_number = leaf_values.len();
-        let prover = MerkleTreeProver::new(leaf_values);
+        let prover = MerkleTreeProver::<H>::new(leaf_values);
         let root = prover.commit();
-        let verifier = MerkleTreeVerifier::new(leave_number, &root);
+        assert_eq!(root.as_ref().len(), H::DIGEST_SIZE);
+        let verifier = MerkleTreeVerifier::<H>::new(leave_number, &root);
         let leaf_indices = vec![2, 3];
         let proof_bytes = prover.open(&leaf_indices);
         let open_values = vec![
             as_bytes_vec(&[Mersenne61Ext::from_int(5), Mersenne61Ext::from_int(6)]),
             as_bytes_vec(&[Mersenne61Ext::from_int(7), Mersenne61Ext::from_int(8)]),
         ];
-        assert!(verifier.verify(proof_bytes, &leaf_indices, &open_values));
+        assert!(verifier.verify(proof_bytes.clone(), &leaf_indices, &open_values));
+        let wrong_values = vec![open_values[1].clone(), open_values[0].clone()];
+        assert!(!verifier.verify(proof_bytes, &leaf_indices, &wrong_values));
+    }
+
+    #[test]
+    fn all_hashers() {
+        commit_and_open::<Blake3Algorithm>();
+        commit_and_open::<Sha256Algorithm>();
+        commit_and_open::<Keccak256Algorithm>();
+        commit_and_open::<PoseidonAlgorithm<Mersenne61Ext>>();
+    }
+
+    #[test]
+    fn salted() {
+        let leaf_values: Vec<_> = (0..8)
+            .map(|x| as_bytes_vec(&[Mersenne61Ext::from_int(x)]))
+            .collect();
+        let prover = MerkleTreeProver::<Blake3Algorithm>::new_salted(leaf_values.clone());
+        let root = prover.commit();
+        assert_ne!(
+            root,
+            MerkleTreeProver::<Blake3Algorithm>::new_salted(leaf_values.clone()).commit()
+        );
+        let verifier = MerkleTreeVerifier::<Blake3Algorithm>::new(8, &root);
+        let leaf_indices = vec![1, 6];
+        let proof_bytes = prover.open(&leaf_indices);
+        let salts = prover.salts(&leaf_indices);
+        let mut leaves = vec![leaf_values[1].clone(), leaf_values[6].clone()];
+        assert!(!verifier.verify(proof_bytes.clone(), &leaf_indices, &leaves));
+        for (leaf, salt) in leaves.iter_mut().zip(&salts) {
+            leaf.extend_from_slice(salt);
+        }
+        assert!(verifier.verify(proof_bytes, &leaf_indices, &leaves));
     }
 
     #[test]
@@ -114,4 +235,16 @@ mod tests {
             hex_string
         );
     }
+
+    #[test]
+    fn sha256_and_keccak256() {
+        assert_eq!(
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+            hex::encode(Sha256Algorithm::hash(b""))
+        );
+        assert_eq!(
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470",
+            hex::encode(Keccak256Algorithm::hash(b""))
+        );
+    }
 }
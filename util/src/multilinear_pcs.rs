@@ -0,0 +1,38 @@
+use crate::algebra::{field::Field, polynomial::MultilinearPolynomial};
+use crate::merkle_tree::MerkleHasher;
+use crate::params::{ParamsError, ProtocolParams};
+use crate::verification_error::VerificationError;
+
+/// Flow shared by the multilinear polynomial commitment schemes of the
+/// workspace (`pcs`, `gemini-fri` and `virgo`), implemented by their public
+/// parameters so that applications and benchmarks can switch schemes.
+pub trait MultilinearPCS<T: Field, H: MerkleHasher>: Sized {
+    /// Prover state kept between `commit` and `open`.
+    type Prover;
+    type Proof;
+
+    /// Parameters for polynomials with `variable_num` variables, with
+    /// random evaluation domains.
+    fn setup(variable_num: usize, protocol: ProtocolParams) -> Result<Self, ParamsError>;
+
+    /// Fails if `polynomial` does not have the variable count of `setup`.
+    fn commit(
+        &self,
+        polynomial: MultilinearPolynomial<T>,
+    ) -> Result<(H::Hash, Self::Prover), ParamsError>;
+
+    /// Opening at `point`, which can be repeated for other points.
+    fn open(&self, prover: &Self::Prover, point: &[T]) -> Self::Proof;
+
+    /// Checks that `proof` opens `commitment` to `value` at `point`.
+    fn verify(
+        &self,
+        commitment: &H::Hash,
+        point: &[T],
+        value: T,
+        proof: &Self::Proof,
+    ) -> Result<(), VerificationError>;
+
+    /// Bytes sent by the prover in `proof`, not counting the commitment.
+    fn proof_size(proof: &Self::Proof) -> usize;
+}
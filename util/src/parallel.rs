@@ -0,0 +1,175 @@
+//! Data-parallel loops used by the provers. With the `parallel` feature they
+//! run on the current rayon thread pool, otherwise they are plain sequential
+//! loops, so callers need no `cfg` of their own.
+
+#[cfg(feature = "parallel")]
+pub use rayon;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// `(0..n).map(f).collect()`.
+pub fn map<R, F>(n: usize, f: F) -> Vec<R>
+where
+    R: Send,
+    F: Fn(usize) -> R + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    {
+        (0..n).into_par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        (0..n).map(f).collect()
+    }
+}
+
+/// `items.iter().map(f).collect()`.
+pub fn map_slice<A, R, F>(items: &[A], f: F) -> Vec<R>
+where
+    A: Sync,
+    R: Send,
+    F: Fn(&A) -> R + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    {
+        items.par_iter().map(f).collect()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.iter().map(f).collect()
+    }
+}
+
+/// Calls `f(i, &mut items[i])` for every index.
+pub fn for_each_mut<A, F>(items: &mut [A], f: F)
+where
+    A: Send,
+    F: Fn(usize, &mut A) + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    {
+        items.par_iter_mut().enumerate().for_each(|(i, x)| f(i, x));
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.iter_mut().enumerate().for_each(|(i, x)| f(i, x));
+    }
+}
+
+/// Calls `f(i, &mut xs[i], &mut ys[i])` for every index.
+pub fn zip_mut<A, B, F>(xs: &mut [A], ys: &mut [B], f: F)
+where
+    A: Send,
+    B: Send,
+    F: Fn(usize, &mut A, &mut B) + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    {
+        xs.par_iter_mut()
+            .zip(ys)
+            .enumerate()
+            .for_each(|(i, (x, y))| f(i, x, y));
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        xs.iter_mut()
+            .zip(ys)
+            .enumerate()
+            .for_each(|(i, (x, y))| f(i, x, y));
+    }
+}
+
+/// Calls `f(start, chunk)` on consecutive chunks of `chunk_size` elements,
+/// `start` being the index of the first one.
+pub fn for_each_chunk_mut<A, F>(items: &mut [A], chunk_size: usize, f: F)
+where
+    A: Send,
+    F: Fn(usize, &mut [A]) + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    {
+        items
+            .par_chunks_mut(chunk_size)
+            .enumerate()
+            .for_each(|(i, chunk)| f(i * chunk_size, chunk));
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        items
+            .chunks_mut(chunk_size)
+            .enumerate()
+            .for_each(|(i, chunk)| f(i * chunk_size, chunk));
+    }
+}
+
+/// Calls `f(start, x, y)` on the consecutive chunks `x` of `xs` and `y` of
+/// `ys` of `chunk_size` elements, `start` being the index of their first.
+pub fn zip_chunks_mut<A, B, F>(xs: &mut [A], ys: &mut [B], chunk_size: usize, f: F)
+where
+    A: Send,
+    B: Send,
+    F: Fn(usize, &mut [A], &mut [B]) + Send + Sync,
+{
+    #[cfg(feature = "parallel")]
+    {
+        xs.par_chunks_mut(chunk_size)
+            .zip(ys.par_chunks_mut(chunk_size))
+            .enumerate()
+            .for_each(|(i, (x, y))| f(i * chunk_size, x, y));
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        xs.chunks_mut(chunk_size)
+            .zip(ys.chunks_mut(chunk_size))
+            .enumerate()
+            .for_each(|(i, (x, y))| f(i * chunk_size, x, y));
+    }
+}
+
+/// Number of threads the loops above may use.
+pub fn num_threads() -> usize {
+    #[cfg(feature = "parallel")]
+    {
+        rayon::current_num_threads()
+    }
+    #[cfg(not(feature = "parallel"))]
+    {
+        1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_sequential() {
+        assert_eq!(
+            map(100, |i| i * i),
+            (0..100).map(|i| i * i).collect::<Vec<_>>()
+        );
+        let v = (0..64).collect::<Vec<usize>>();
+        assert_eq!(map_slice(&v, |x| x + 1), (1..65).collect::<Vec<_>>());
+        let mut w = v.clone();
+        for_each_mut(&mut w, |i, x| *x += i);
+        assert_eq!(w, (0..64).map(|i| 2 * i).collect::<Vec<_>>());
+        for_each_chunk_mut(&mut w, 8, |start, chunk| {
+            assert_eq!(chunk[0], 2 * start);
+            chunk.reverse()
+        });
+        assert_eq!(w[0], 14);
+        assert_eq!(w[63], 112);
+        let mut u = v.clone();
+        zip_mut(&mut u, &mut w, |i, x, y| {
+            assert_eq!(*x, i);
+            std::mem::swap(x, y);
+        });
+        assert_eq!(w, v);
+        zip_chunks_mut(&mut u, &mut w, 10, |start, x, y| {
+            assert_eq!(x.len(), y.len());
+            assert_eq!(y[0], start);
+            x.fill(start);
+        });
+        assert_eq!(u[63], 60);
+    }
+}
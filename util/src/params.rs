@@ -0,0 +1,603 @@
+use crate::algebra::{coset::Coset, domain::EvaluationDomain, field::Field};
+use crate::codec::{DecodeError, Decoder, Encoder};
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldChoice {
+    Mersenne61Ext,
+    Fp64,
+    Mersenne61,
+    Fp64Quad,
+    Fp64Cubic,
+    BabyBear,
+    BabyBearQuartic,
+    KoalaBear,
+    KoalaBearQuartic,
+    Mersenne31,
+    Mersenne31Ext,
+    BinaryTower128,
+}
+
+impl FieldChoice {
+    fn to_u8(self) -> u8 {
+        match self {
+            FieldChoice::Mersenne61Ext => 0,
+            FieldChoice::Fp64 => 1,
+            FieldChoice::Mersenne61 => 2,
+            FieldChoice::Fp64Quad => 3,
+            FieldChoice::Fp64Cubic => 4,
+            FieldChoice::BabyBear => 5,
+            FieldChoice::BabyBearQuartic => 6,
+            FieldChoice::KoalaBear => 7,
+            FieldChoice::KoalaBearQuartic => 8,
+            FieldChoice::Mersenne31 => 9,
+            FieldChoice::Mersenne31Ext => 10,
+            FieldChoice::BinaryTower128 => 11,
+        }
+    }
+
+    fn from_u8(x: u8) -> Option<Self> {
+        match x {
+            0 => Some(FieldChoice::Mersenne61Ext),
+            1 => Some(FieldChoice::Fp64),
+            2 => Some(FieldChoice::Mersenne61),
+            3 => Some(FieldChoice::Fp64Quad),
+            4 => Some(FieldChoice::Fp64Cubic),
+            5 => Some(FieldChoice::BabyBear),
+            6 => Some(FieldChoice::BabyBearQuartic),
+            7 => Some(FieldChoice::KoalaBear),
+            8 => Some(FieldChoice::KoalaBearQuartic),
+            9 => Some(FieldChoice::Mersenne31),
+            10 => Some(FieldChoice::Mersenne31Ext),
+            11 => Some(FieldChoice::BinaryTower128),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamsError {
+    InvalidLogBlowup,
+    InvalidSecurityBits,
+    InvalidGrindingBits,
+    TooFewQueries,
+    /// The polynomial has no more variables than `terminate_round`.
+    TooFewVariables,
+    /// The evaluation domain does not fit in the field's two-adic subgroup.
+    DomainTooLarge,
+    /// Polynomials over a subfield are committed over a domain outside it.
+    DomainNotInBaseField,
+    WrongField,
+    /// Hiding mode needs at least one masking variable, see
+    /// `ProtocolParams`.
+    HidingTerminateRound,
+    /// The queries of a round of a hiding proof reveal more values of its
+    /// oracles than their mask has coefficients.
+    HidingTooManyQueries,
+    InvalidFoldingArity,
+    /// An open point or a batch of polynomials disagrees on the variable
+    /// count, or the batch is empty.
+    VariableNumMismatch,
+    /// The field is too small for the challenges of the requested opening.
+    FieldTooSmall,
+    /// More secrets than the shared polynomial has coefficients.
+    TooManySecrets,
+    /// The scheme does not implement this feature, see `Capabilities`.
+    Unsupported(&'static str),
+}
+
+impl std::fmt::Display for ParamsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ParamsError::InvalidLogBlowup => write!(f, "log blowup must be in 1..=16"),
+            ParamsError::InvalidSecurityBits => write!(f, "security bits must be in 1..=256"),
+            ParamsError::InvalidGrindingBits => {
+                write!(
+                    f,
+                    "grinding bits must be at most 64 and below security bits"
+                )
+            }
+            ParamsError::TooFewQueries => write!(f, "query count does not reach security bits"),
+            ParamsError::TooFewVariables => write!(f, "terminate round exceeds variable count"),
+            ParamsError::DomainTooLarge => write!(f, "evaluation domain too large for field"),
+            ParamsError::DomainNotInBaseField => {
+                write!(f, "evaluation domain not in the base field")
+            }
+            ParamsError::WrongField => write!(f, "field does not match parameters"),
+            ParamsError::HidingTerminateRound => {
+                write!(f, "hiding mode requires a nonzero terminate round")
+            }
+            ParamsError::HidingTooManyQueries => {
+                write!(f, "hiding mask too small for the opened values")
+            }
+            ParamsError::InvalidFoldingArity => write!(f, "folding arity must be 2, 4, 8 or 16"),
+            ParamsError::VariableNumMismatch => write!(f, "variable counts do not match"),
+            ParamsError::FieldTooSmall => write!(f, "field too small for the opening"),
+            ParamsError::TooManySecrets => write!(f, "more secrets than coefficients"),
+            ParamsError::Unsupported(feature) => write!(f, "{} not supported by scheme", feature),
+        }
+    }
+}
+
+impl std::error::Error for ParamsError {}
+
+/// Features of `ProtocolParams` a scheme implements, checked by
+/// `ProtocolParams::require` when its provers and verifiers are built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    pub hiding: bool,
+    pub shared_trees: bool,
+    pub max_folding_arity: usize,
+    /// Whether folding may stop before a constant, at a nonzero
+    /// `terminate_round`.
+    pub terminate_round: bool,
+}
+
+/// Parameters every prover and verifier of a protocol instance must agree
+/// on. The rate of the Reed-Solomon code is `2^-log_blowup`, so each query
+/// contributes `log_blowup` bits of security and `query_num` is chosen so
+/// that queries plus grinding reach `security_bits`.
+///
+/// In `hiding` mode `terminate_round` random masking variables are appended
+/// to every committed polynomial, which `total_round` and
+/// `interpolate_cosets` account for, so that the final polynomial only
+/// holds the evaluation and the mask. Every round opens its oracles, which
+/// are partial evaluations of the masked polynomial, at `query_num` cosets,
+/// so `total_round` rejects parameters where a round reveals more values
+/// than the mask of its oracles has coefficients. The mask of the last
+/// round is the smallest, and about `2 * query_num * arity` values call for
+/// 7 masking variables at the default security. Only `pcs` supports it.
+///
+/// With `shared_trees`, the function and the folding committed in the same
+/// round share one Merkle tree whose leaves hold both values, so each query
+/// needs one authentication path per round instead of two. Only `pcs`, `vss`
+/// and `avss` support it.
+///
+/// Each round folds `2^log_folding_arity` times, consuming as many
+/// variables, and commits once, with leaves holding the values of a coset
+/// of that size. Only `pcs` supports arities other than 2.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProtocolParams {
+    pub log_blowup: usize,
+    pub security_bits: usize,
+    pub terminate_round: usize,
+    pub query_num: usize,
+    pub grinding_bits: usize,
+    pub field: FieldChoice,
+    pub hiding: bool,
+    pub shared_trees: bool,
+    pub log_folding_arity: usize,
+}
+
+impl Default for ProtocolParams {
+    fn default() -> Self {
+        ProtocolParams::new(3, 100, 1, 0, FieldChoice::Mersenne61Ext).unwrap()
+    }
+}
+
+impl ProtocolParams {
+    pub fn new(
+        log_blowup: usize,
+        security_bits: usize,
+        terminate_round: usize,
+        grinding_bits: usize,
+        field: FieldChoice,
+    ) -> Result<Self, ParamsError> {
+        if log_blowup == 0 || log_blowup > 16 {
+            return Err(ParamsError::InvalidLogBlowup);
+        }
+        if security_bits == 0 || security_bits > 256 {
+            return Err(ParamsError::InvalidSecurityBits);
+        }
+        if grinding_bits > 64 || grinding_bits >= security_bits {
+            return Err(ParamsError::InvalidGrindingBits);
+        }
+        let query_num = (security_bits - grinding_bits).div_ceil(log_blowup);
+        Ok(ProtocolParams {
+            log_blowup,
+            security_bits,
+            terminate_round,
+            query_num,
+            grinding_bits,
+            field,
+            hiding: false,
+            shared_trees: false,
+            log_folding_arity: 1,
+        })
+    }
+
+    pub fn with_hiding(mut self, hiding: bool) -> Result<Self, ParamsError> {
+        self.hiding = hiding;
+        self.validate()?;
+        Ok(self)
+    }
+
+    pub fn with_shared_trees(mut self, shared_trees: bool) -> Result<Self, ParamsError> {
+        self.shared_trees = shared_trees;
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Folds `arity` times per round, which must be 2, 4, 8 or 16.
+    pub fn with_folding_arity(mut self, arity: usize) -> Result<Self, ParamsError> {
+        if !arity.is_power_of_two() {
+            return Err(ParamsError::InvalidFoldingArity);
+        }
+        self.log_folding_arity = arity.trailing_zeros() as usize;
+        self.validate()?;
+        Ok(self)
+    }
+
+    pub fn folding_arity(&self) -> usize {
+        1 << self.log_folding_arity
+    }
+
+    /// Overrides the derived query count, e.g. to benchmark a fixed number
+    /// of queries. The count must still reach `security_bits`.
+    pub fn with_query_num(mut self, query_num: usize) -> Result<Self, ParamsError> {
+        self.query_num = query_num;
+        self.validate()?;
+        Ok(self)
+    }
+
+    pub fn validate(&self) -> Result<(), ParamsError> {
+        let expected = ProtocolParams::new(
+            self.log_blowup,
+            self.security_bits,
+            self.terminate_round,
+            self.grinding_bits,
+            self.field,
+        )?;
+        if self.query_num < expected.query_num {
+            return Err(ParamsError::TooFewQueries);
+        }
+        if self.hiding && self.terminate_round == 0 {
+            return Err(ParamsError::HidingTerminateRound);
+        }
+        if self.log_folding_arity == 0 || self.log_folding_arity > 4 {
+            return Err(ParamsError::InvalidFoldingArity);
+        }
+        Ok(())
+    }
+
+    /// Fails with `ParamsError::Unsupported` if these parameters use a
+    /// feature `capabilities` lacks.
+    pub fn require(&self, capabilities: Capabilities) -> Result<(), ParamsError> {
+        if self.hiding && !capabilities.hiding {
+            return Err(ParamsError::Unsupported("hiding mode"));
+        }
+        if self.shared_trees && !capabilities.shared_trees {
+            return Err(ParamsError::Unsupported("shared trees"));
+        }
+        if self.folding_arity() > capabilities.max_folding_arity {
+            return Err(ParamsError::Unsupported("folding arity"));
+        }
+        if self.terminate_round != 0 && !capabilities.terminate_round {
+            return Err(ParamsError::Unsupported("terminate round"));
+        }
+        Ok(())
+    }
+
+    pub fn check_field<T: Field>(&self) -> Result<(), ParamsError> {
+        if self.field == T::FIELD_CHOICE {
+            Ok(())
+        } else {
+            Err(ParamsError::WrongField)
+        }
+    }
+
+    /// Number of masking variables appended to the committed polynomials,
+    /// `terminate_round` in hiding mode.
+    pub fn mask_variable_num(&self) -> usize {
+        if self.hiding {
+            self.terminate_round
+        } else {
+            0
+        }
+    }
+
+    /// Number of variables actually committed for a polynomial with
+    /// `variable_num` variables.
+    pub fn committed_variable_num(&self, variable_num: usize) -> usize {
+        variable_num + self.mask_variable_num()
+    }
+
+    /// Number of folding rounds for a polynomial with `variable_num`
+    /// variables.
+    pub fn total_round(&self, variable_num: usize) -> Result<usize, ParamsError> {
+        let committed_variable_num = self.committed_variable_num(variable_num);
+        if committed_variable_num <= self.terminate_round {
+            return Err(ParamsError::TooFewVariables);
+        }
+        let total_round = committed_variable_num - self.terminate_round;
+        if self.hiding {
+            let rounds = self.folding_rounds(total_round);
+            for (i, round) in rounds.iter().enumerate() {
+                if self.revealed_values(variable_num, i, round)
+                    > self.mask_size(variable_num, round)
+                {
+                    return Err(ParamsError::HidingTooManyQueries);
+                }
+            }
+        }
+        Ok(total_round)
+    }
+
+    /// Values the folding round `index` of a proof for `variable_num`
+    /// variables reveals at most: a coset of `2^round.len()` values per
+    /// query of each oracle it opens, the committed function for the first
+    /// round and a function and a folding for the others.
+    pub fn revealed_values(
+        &self,
+        variable_num: usize,
+        index: usize,
+        round: &Range<usize>,
+    ) -> usize {
+        let log_size = self.committed_variable_num(variable_num) - round.start + self.log_blowup;
+        let leave_number = 1 << (log_size - round.len());
+        let oracle_num = if index == 0 { 1 } else { 2 };
+        self.query_num.min(leave_number) * (1 << round.len()) * oracle_num
+    }
+
+    /// Coefficients of the mask of the oracles of a folding round, those of
+    /// the masking variables in the partial evaluation at the start of the
+    /// round.
+    pub fn mask_size(&self, variable_num: usize, round: &Range<usize>) -> usize {
+        ((1 << self.mask_variable_num()) - 1) << (variable_num - round.start)
+    }
+
+    /// Folding rounds of `total_round` foldings by 2, each a range of them
+    /// committed together. All but the last one have `log_folding_arity`
+    /// steps.
+    pub fn folding_rounds(&self, total_round: usize) -> Vec<Range<usize>> {
+        (0..total_round)
+            .step_by(self.log_folding_arity)
+            .map(|start| start..total_round.min(start + self.log_folding_arity))
+            .collect()
+    }
+
+    /// Evaluation domains of the successive folding rounds, starting from a
+    /// coset of size `2^(variable_num + log_blowup)`.
+    pub fn interpolate_cosets<T: Field>(
+        &self,
+        variable_num: usize,
+        shift: T,
+    ) -> Result<Vec<Coset<T>>, ParamsError> {
+        self.interpolate_domains(variable_num, shift)
+    }
+
+    /// Same as `interpolate_cosets` with domains of any type, e.g.
+    /// `AffineSubspace` over a binary field.
+    pub fn interpolate_domains<T: Field, D: EvaluationDomain<T>>(
+        &self,
+        variable_num: usize,
+        shift: T,
+    ) -> Result<Vec<D>, ParamsError> {
+        self.check_field::<T>()?;
+        let variable_num = self.committed_variable_num(variable_num);
+        let first = D::with_shift(variable_num + self.log_blowup, shift)
+            .ok_or(ParamsError::DomainTooLarge)?;
+        let mut interpolate_domains = vec![first];
+        for i in 1..variable_num {
+            interpolate_domains.push(interpolate_domains[i - 1].folded());
+        }
+        Ok(interpolate_domains)
+    }
+
+    pub fn encode(&self, encoder: &mut Encoder) {
+        encoder.write_u8(self.field.to_u8());
+        encoder.write_usize(self.log_blowup);
+        encoder.write_usize(self.security_bits);
+        encoder.write_usize(self.terminate_round);
+        encoder.write_usize(self.query_num);
+        encoder.write_usize(self.grinding_bits);
+        encoder.write_u8(self.hiding as u8);
+        encoder.write_u8(self.shared_trees as u8);
+        encoder.write_u8(self.log_folding_arity as u8);
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+        let field = FieldChoice::from_u8(decoder.read_u8()?).ok_or(DecodeError::InvalidParams)?;
+        let params = ProtocolParams {
+            field,
+            log_blowup: decoder.read_usize()?,
+            security_bits: decoder.read_usize()?,
+            terminate_round: decoder.read_usize()?,
+            query_num: decoder.read_usize()?,
+            grinding_bits: decoder.read_usize()?,
+            hiding: read_bool(decoder)?,
+            shared_trees: read_bool(decoder)?,
+            log_folding_arity: decoder.read_u8()? as usize,
+        };
+        params.validate().map_err(|_| DecodeError::InvalidParams)?;
+        Ok(params)
+    }
+}
+
+fn read_bool(decoder: &mut Decoder) -> Result<bool, DecodeError> {
+    match decoder.read_u8()? {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(DecodeError::InvalidParams),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::field::{fp64::Fp64, mersenne61_ext::Mersenne61Ext};
+
+    #[test]
+    fn validation() {
+        let params = ProtocolParams::default();
+        assert_eq!(params.query_num, 34);
+        assert_eq!(params.total_round(10), Ok(9));
+        assert_eq!(params.total_round(1), Err(ParamsError::TooFewVariables));
+
+        let params = ProtocolParams::new(1, 100, 1, 20, FieldChoice::Fp64).unwrap();
+        assert_eq!(params.query_num, 80);
+        assert_eq!(params.with_query_num(79), Err(ParamsError::TooFewQueries));
+        assert!(params.with_query_num(100).is_ok());
+        assert_eq!(
+            ProtocolParams::new(0, 100, 1, 0, FieldChoice::Fp64),
+            Err(ParamsError::InvalidLogBlowup)
+        );
+        assert_eq!(
+            ProtocolParams::new(3, 100, 1, 100, FieldChoice::Fp64),
+            Err(ParamsError::InvalidGrindingBits)
+        );
+        assert_eq!(
+            params
+                .interpolate_cosets(10, Mersenne61Ext::random_element())
+                .err(),
+            Some(ParamsError::WrongField)
+        );
+        assert_eq!(
+            params.interpolate_cosets(32, Fp64::random_element()).err(),
+            Some(ParamsError::DomainTooLarge)
+        );
+        assert_eq!(
+            params
+                .interpolate_cosets(10, Fp64::random_element())
+                .unwrap()
+                .len(),
+            10
+        );
+
+        let hiding = ProtocolParams::new(3, 100, 7, 0, FieldChoice::Mersenne61Ext)
+            .unwrap()
+            .with_hiding(true)
+            .unwrap();
+        assert_eq!(hiding.committed_variable_num(10), 17);
+        assert_eq!(hiding.total_round(10), Ok(10));
+        for params in [hiding, hiding.with_folding_arity(16).unwrap()] {
+            for variable_num in 1..16 {
+                let total_round = params.total_round(variable_num).unwrap();
+                for (i, round) in params.folding_rounds(total_round).iter().enumerate() {
+                    assert!(
+                        params.revealed_values(variable_num, i, round)
+                            <= params.mask_size(variable_num, round)
+                    );
+                }
+            }
+        }
+        // Beyond the first round, the last round opens 2 values of 2 oracles
+        // per query, while its mask has `2 * (2^k - 1)` coefficients.
+        for terminate_round in 1..7 {
+            let params =
+                ProtocolParams::new(3, 100, terminate_round, 0, FieldChoice::Mersenne61Ext)
+                    .unwrap()
+                    .with_hiding(true)
+                    .unwrap();
+            for variable_num in [2, 10, 20] {
+                assert_eq!(
+                    params.total_round(variable_num),
+                    Err(ParamsError::HidingTooManyQueries)
+                );
+            }
+        }
+        assert_eq!(
+            hiding
+                .interpolate_cosets(10, Mersenne61Ext::random_element())
+                .unwrap()[0]
+                .size(),
+            1 << 20
+        );
+        assert_eq!(
+            ProtocolParams::new(3, 100, 0, 0, FieldChoice::Mersenne61Ext)
+                .unwrap()
+                .with_hiding(true),
+            Err(ParamsError::HidingTerminateRound)
+        );
+
+        let params = ProtocolParams::default();
+        assert_eq!(
+            params.with_folding_arity(4).unwrap().folding_rounds(7),
+            vec![0..2, 2..4, 4..6, 6..7]
+        );
+        assert_eq!(
+            params.with_folding_arity(8).unwrap().folding_rounds(7),
+            vec![0..3, 3..6, 6..7]
+        );
+        assert_eq!(params.folding_rounds(3), vec![0..1, 1..2, 2..3]);
+        assert_eq!(
+            params.with_folding_arity(1),
+            Err(ParamsError::InvalidFoldingArity)
+        );
+        assert_eq!(
+            params.with_folding_arity(32),
+            Err(ParamsError::InvalidFoldingArity)
+        );
+    }
+
+    #[test]
+    fn capabilities() {
+        let binary = Capabilities {
+            hiding: false,
+            shared_trees: true,
+            max_folding_arity: 2,
+            terminate_round: true,
+        };
+        let params = ProtocolParams::default();
+        assert_eq!(params.require(binary), Ok(()));
+        assert_eq!(
+            params.with_shared_trees(true).unwrap().require(binary),
+            Ok(())
+        );
+        assert_eq!(
+            params.with_hiding(true).unwrap().require(binary),
+            Err(ParamsError::Unsupported("hiding mode"))
+        );
+        assert_eq!(
+            params.with_folding_arity(4).unwrap().require(binary),
+            Err(ParamsError::Unsupported("folding arity"))
+        );
+        let constant = Capabilities {
+            shared_trees: false,
+            terminate_round: false,
+            ..binary
+        };
+        assert_eq!(
+            params.with_shared_trees(true).unwrap().require(constant),
+            Err(ParamsError::Unsupported("shared trees"))
+        );
+        assert_eq!(
+            params.require(constant),
+            Err(ParamsError::Unsupported("terminate round"))
+        );
+
+        let params = ProtocolParams {
+            query_num: 1,
+            ..params
+        };
+        assert_eq!(
+            params.with_shared_trees(true),
+            Err(ParamsError::TooFewQueries)
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let params = ProtocolParams::new(4, 128, 1, 16, FieldChoice::Fp64)
+            .unwrap()
+            .with_hiding(true)
+            .unwrap()
+            .with_shared_trees(true)
+            .unwrap()
+            .with_folding_arity(8)
+            .unwrap();
+        let mut encoder = Encoder::new();
+        params.encode(&mut encoder);
+        let bytes = encoder.into_bytes();
+        let mut decoder = Decoder::new(&bytes);
+        assert_eq!(ProtocolParams::decode(&mut decoder), Ok(params));
+        assert_eq!(decoder.finish(), Ok(()));
+
+        let mut bad = bytes.clone();
+        bad[1 + 8 * 3] = 0;
+        assert_eq!(
+            ProtocolParams::decode(&mut Decoder::new(&bad)),
+            Err(DecodeError::InvalidParams)
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use crate::algebra::field::Field;
+use crate::merkle_tree::MerkleHasher;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::{OnceLock, RwLock};
+
+const WIDTH: usize = 4;
+const RATE: usize = 2;
+const FULL_ROUNDS: usize = 8;
+const PARTIAL_ROUNDS: usize = 22;
+const DIGEST_SIZE: usize = 32;
+
+/// Poseidon-style sponge over the field `T`, for use as an algebraic Merkle
+/// hasher. The S-box is the inversion map `x -> x^{-1}` (with `0 -> 0`), which
+/// is a permutation in any field, and the linear layer is the Poseidon2 `M4`
+/// matrix. Round constants are derived deterministically from a fixed seed.
+///
+/// The round numbers have not been tuned against a concrete security analysis;
+/// this hasher is meant for comparing prover cost and for recursive
+/// verification experiments, not as a drop-in replacement for Blake3.
+#[derive(Debug, Clone)]
+pub struct PoseidonAlgorithm<T: Field> {
+    _marker: PhantomData<T>,
+}
+
+fn derive_round_constants<T: Field>() -> Vec<[T; WIDTH]> {
+    let mut seed = 0x506f_7365_6964_6f6eu64;
+    let mut next = || {
+        // splitmix64
+        seed = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = seed;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        T::from_int((z ^ (z >> 31)) >> 8)
+    };
+    (0..FULL_ROUNDS + PARTIAL_ROUNDS)
+        .map(|_| [next(), next(), next(), next()])
+        .collect()
+}
+
+/// Input bytes packed into each field element: at most 7, which `from_int`
+/// takes, and few enough to stay below the characteristic of `T`, so that
+/// distinct inputs give distinct limbs.
+fn limb_bytes<T: Field>() -> usize {
+    let limb_bytes = (T::CHARACTERISTIC_BITS as usize - 1) / 8;
+    assert!(limb_bytes > 0, "characteristic too small to pack bytes");
+    limb_bytes.min(7)
+}
+
+/// Round constants of `T`, derived on first use and shared by every hash.
+fn round_constants<T: Field + 'static>() -> &'static [[T; WIDTH]] {
+    type Cache = RwLock<HashMap<TypeId, &'static (dyn Any + Send + Sync)>>;
+    static CACHE: OnceLock<Cache> = OnceLock::new();
+    let cache = CACHE.get_or_init(Default::default);
+    let cached = cache.read().unwrap().get(&TypeId::of::<T>()).copied();
+    let constants = cached.unwrap_or_else(|| {
+        *cache
+            .write()
+            .unwrap()
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::leak(Box::new(derive_round_constants::<T>())))
+    });
+    constants.downcast_ref::<Vec<[T; WIDTH]>>().unwrap()
+}
+
+fn sbox<T: Field>(x: T) -> T {
+    if x.is_zero() {
+        x
+    } else {
+        x.inverse()
+    }
+}
+
+fn linear_layer<T: Field>(state: &mut [T; WIDTH]) {
+    const M4: [[u64; WIDTH]; WIDTH] = [[5, 7, 1, 3], [4, 6, 1, 1], [1, 3, 5, 7], [1, 1, 4, 6]];
+    let old = *state;
+    for i in 0..WIDTH {
+        state[i] = T::from_int(0);
+        for j in 0..WIDTH {
+            state[i] += T::from_int(M4[i][j]) * old[j];
+        }
+    }
+}
+
+fn permute<T: Field>(state: &mut [T; WIDTH], constants: &[[T; WIDTH]]) {
+    let half_full = FULL_ROUNDS / 2;
+    for (round, c) in constants.iter().enumerate() {
+        for i in 0..WIDTH {
+            state[i] += c[i];
+        }
+        if round < half_full || round >= half_full + PARTIAL_ROUNDS {
+            for x in state.iter_mut() {
+                *x = sbox(*x);
+            }
+        } else {
+            state[0] = sbox(state[0]);
+        }
+        linear_layer(state);
+    }
+}
+
+impl<T: Field + 'static> MerkleHasher for PoseidonAlgorithm<T> {
+    type Hash = [u8; DIGEST_SIZE];
+    const DIGEST_SIZE: usize = DIGEST_SIZE;
+
+    fn hash(data: &[u8]) -> [u8; DIGEST_SIZE] {
+        let constants = round_constants::<T>();
+        let mut state = [T::from_int(0); WIDTH];
+        // Domain-separate inputs of different lengths through the capacity.
+        state[RATE] = T::from_int(data.len() as u64);
+        let limbs = data
+            .chunks(limb_bytes::<T>())
+            .map(|chunk| {
+                let mut bytes = [0u8; 8];
+                bytes[..chunk.len()].copy_from_slice(chunk);
+                T::from_int(u64::from_le_bytes(bytes))
+            })
+            .collect::<Vec<_>>();
+        for block in limbs.chunks(RATE) {
+            for (i, x) in block.iter().enumerate() {
+                state[i] += *x;
+            }
+            permute(&mut state, constants);
+        }
+        if limbs.is_empty() {
+            permute(&mut state, constants);
+        }
+        let mut output = vec![];
+        while output.len() < DIGEST_SIZE {
+            for x in &state[..RATE] {
+                output.extend(x.to_bytes());
+            }
+            if output.len() < DIGEST_SIZE {
+                permute(&mut state, constants);
+            }
+        }
+        output[..DIGEST_SIZE].try_into().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::field::{
+        babybear::BabyBearParams,
+        fp64::Fp64,
+        koalabear::KoalaBearParams,
+        mersenne31::Mersenne31Params,
+        mersenne61_ext::Mersenne61Ext,
+        prime31::{Prime31, Prime31Params},
+    };
+
+    fn distinct<T: Field + 'static>() {
+        let a = PoseidonAlgorithm::<T>::hash(b"data");
+        assert_eq!(a, PoseidonAlgorithm::<T>::hash(b"data"));
+        assert_ne!(a, PoseidonAlgorithm::<T>::hash(b"datb"));
+        assert_ne!(a, PoseidonAlgorithm::<T>::hash(b"data\0"));
+        assert_ne!(
+            PoseidonAlgorithm::<T>::hash(b""),
+            PoseidonAlgorithm::<T>::hash(b"\0")
+        );
+    }
+
+    /// Inputs whose 7-byte limbs would only differ by the modulus.
+    fn no_reduction_collision<P: Prime31Params>() {
+        let (a, b) = (1u64.to_le_bytes(), (1 + P::MOD as u64).to_le_bytes());
+        assert_ne!(
+            PoseidonAlgorithm::<Prime31<P>>::hash(&a[..7]),
+            PoseidonAlgorithm::<Prime31<P>>::hash(&b[..7])
+        );
+    }
+
+    #[test]
+    fn deterministic_and_distinct() {
+        distinct::<Mersenne61Ext>();
+        distinct::<Fp64>();
+        distinct::<Prime31<BabyBearParams>>();
+    }
+
+    #[test]
+    fn cached_constants() {
+        let constants = round_constants::<Fp64>();
+        assert!(std::ptr::eq(constants, round_constants::<Fp64>()));
+        assert_eq!(constants, derive_round_constants::<Fp64>());
+        assert_eq!(
+            round_constants::<Mersenne61Ext>(),
+            derive_round_constants::<Mersenne61Ext>()
+        );
+    }
+
+    #[test]
+    fn small_fields() {
+        no_reduction_collision::<BabyBearParams>();
+        no_reduction_collision::<KoalaBearParams>();
+        no_reduction_collision::<Mersenne31Params>();
+    }
+}
@@ -0,0 +1,32 @@
+fn leading_zeros(seed: &[u8; 32], nonce: u64) -> u32 {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(seed);
+    hasher.update(&nonce.to_le_bytes());
+    let hash: [u8; 32] = hasher.finalize().into();
+    u64::from_be_bytes(hash[..8].try_into().unwrap()).leading_zeros()
+}
+
+/// Finds the smallest nonce such that `blake3(seed || nonce)` starts with
+/// `bits` zero bits. Takes about `2^bits` hashes.
+pub fn grind(seed: &[u8; 32], bits: usize) -> u64 {
+    assert!(bits <= 64);
+    (0..).find(|nonce| verify(seed, bits, *nonce)).unwrap()
+}
+
+pub fn verify(seed: &[u8; 32], bits: usize, nonce: u64) -> bool {
+    leading_zeros(seed, nonce) as usize >= bits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grind_and_verify() {
+        let seed = [7u8; 32];
+        let nonce = grind(&seed, 10);
+        assert!(verify(&seed, 10, nonce));
+        assert!((0..nonce).all(|x| !verify(&seed, 10, x)));
+        assert!(verify(&seed, 0, 12345));
+    }
+}
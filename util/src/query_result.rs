@@ -1,5 +1,7 @@
-use crate::algebra::field::{as_bytes_vec, Field};
-use crate::merkle_tree::MerkleTreeVerifier;
+use crate::algebra::field::{as_bytes_vec, ExtensionField, Field};
+use crate::codec::{DecodeError, Decoder, Encoder};
+use crate::merkle_tree::{MerkleHasher, MerkleTreeVerifier, Salt, SALT_SIZE};
+use crate::verification_error::VerificationError;
 use std::collections::HashMap;
 use std::mem::size_of;
 
@@ -7,32 +9,183 @@ use std::mem::size_of;
 pub struct QueryResult<T: Field> {
     pub proof_bytes: Vec<u8>,
     pub proof_values: HashMap<usize, T>,
+    /// Leaf salts of a hiding tree, in the order of the sorted leaf indices.
+    pub salts: Vec<Salt>,
 }
 
 impl<T: Field> QueryResult<T> {
-    pub fn verify_merkle_tree(
+    pub fn value_at(&self, index: usize) -> Result<T, VerificationError> {
+        self.proof_values
+            .get(&index)
+            .copied()
+            .ok_or(VerificationError::MissingQueryValue(index))
+    }
+
+    pub fn verify_merkle_tree<H: MerkleHasher>(
+        &self,
+        leaf_indices: &Vec<usize>,
+        merkle_verifier: &MerkleTreeVerifier<H>,
+    ) -> Result<(), VerificationError> {
+        self.verify_batch_merkle_tree(leaf_indices, merkle_verifier, 1)
+    }
+
+    /// Checks a tree whose leaves hold `[v_j[i], v_j[i + n]]` for each of
+    /// `batch_size` functions `v_j`, where the value of `v_j` at position
+    /// `x` is stored under index `j * 2n + x`.
+    pub fn verify_batch_merkle_tree<H: MerkleHasher>(
         &self,
         leaf_indices: &Vec<usize>,
-        merkle_verifier: &MerkleTreeVerifier,
-    ) -> bool {
-        let leaves: Vec<Vec<u8>> = leaf_indices
+        merkle_verifier: &MerkleTreeVerifier<H>,
+        batch_size: usize,
+    ) -> Result<(), VerificationError> {
+        self.verify_coset_merkle_tree(leaf_indices, merkle_verifier, batch_size, 2, false)
+    }
+
+    /// Same as `verify_batch_merkle_tree` for leaves holding the `arity`
+    /// values `v_j[i + t * n]` of each function. The leaves of a `hiding`
+    /// tree carry one salt each, the others none.
+    pub fn verify_coset_merkle_tree<H: MerkleHasher>(
+        &self,
+        leaf_indices: &Vec<usize>,
+        merkle_verifier: &MerkleTreeVerifier<H>,
+        batch_size: usize,
+        arity: usize,
+        hiding: bool,
+    ) -> Result<(), VerificationError> {
+        let len = merkle_verifier.leave_number;
+        let salt_num = if hiding { leaf_indices.len() } else { 0 };
+        if self.salts.len() != salt_num {
+            return Err(VerificationError::WrongProofShape);
+        }
+        let leaves = leaf_indices
             .iter()
-            .map(|x| {
-                as_bytes_vec(&[
-                    self.proof_values.get(x).unwrap().clone(),
-                    self.proof_values
-                        .get(&(x + merkle_verifier.leave_number))
-                        .unwrap()
-                        .clone(),
-                ])
+            .enumerate()
+            .map(|(i, x)| {
+                let values = (0..batch_size)
+                    .flat_map(|j| (0..arity).map(move |t| (j * arity + t) * len + x))
+                    .map(|index| self.value_at(index))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let mut leaf = as_bytes_vec(&values);
+                if let Some(salt) = self.salts.get(i) {
+                    leaf.extend_from_slice(salt);
+                }
+                Ok(leaf)
             })
+            .collect::<Result<Vec<_>, _>>()?;
+        if merkle_verifier.verify(self.proof_bytes.clone(), leaf_indices, &leaves) {
+            Ok(())
+        } else {
+            Err(VerificationError::BadMerklePath)
+        }
+    }
+
+    /// Values of `sum_j coefficients[j] * v_j` at the opened positions of a
+    /// batch laid out as in `verify_batch_merkle_tree`.
+    pub fn linear_combination(
+        &self,
+        domain_size: usize,
+        coefficients: &[T],
+    ) -> Result<QueryResult<T>, VerificationError> {
+        let mut proof_values = HashMap::new();
+        for index in self.proof_values.keys().filter(|x| **x < domain_size) {
+            let mut value = T::from_int(0);
+            for (j, c) in coefficients.iter().enumerate() {
+                value += *c * self.value_at(j * domain_size + index)?;
+            }
+            proof_values.insert(*index, value);
+        }
+        Ok(QueryResult {
+            proof_bytes: vec![],
+            proof_values,
+            salts: vec![],
+        })
+    }
+
+    /// Values of the `k`-th function of a leaf holding several functions on
+    /// a domain of `domain_size` elements.
+    pub fn column(&self, domain_size: usize, k: usize) -> QueryResult<T> {
+        let proof_values = self
+            .proof_values
+            .iter()
+            .filter(|(index, _)| **index / domain_size == k)
+            .map(|(index, value)| (index - k * domain_size, *value))
             .collect();
-        let res = merkle_verifier.verify(self.proof_bytes.clone(), leaf_indices, &leaves);
-        assert!(res);
-        res
+        QueryResult {
+            proof_bytes: vec![],
+            proof_values,
+            salts: vec![],
+        }
+    }
+
+    /// The same answer with the values mapped into an extension of `T`.
+    pub fn lift<E: ExtensionField<T>>(&self) -> QueryResult<E> {
+        QueryResult {
+            proof_bytes: self.proof_bytes.clone(),
+            proof_values: self
+                .proof_values
+                .iter()
+                .map(|(index, value)| (*index, E::from_base(*value)))
+                .collect(),
+            salts: self.salts.clone(),
+        }
+    }
+
+    /// The same answer over a subfield of `T`, if all values lie in it.
+    pub fn to_base<B: Field>(&self) -> Option<QueryResult<B>>
+    where
+        T: ExtensionField<B>,
+    {
+        Some(QueryResult {
+            proof_bytes: self.proof_bytes.clone(),
+            proof_values: self
+                .proof_values
+                .iter()
+                .map(|(index, value)| Some((*index, value.to_base()?)))
+                .collect::<Option<_>>()?,
+            salts: self.salts.clone(),
+        })
     }
 
     pub fn proof_size(&self) -> usize {
-        self.proof_bytes.len() + self.proof_values.len() * size_of::<T>()
+        self.proof_bytes.len()
+            + self.proof_values.len() * size_of::<T>()
+            + self.salts.len() * SALT_SIZE
+    }
+
+    pub fn encode(&self, encoder: &mut Encoder) {
+        encoder.write_bytes(&self.proof_bytes);
+        let mut values = self.proof_values.iter().collect::<Vec<_>>();
+        values.sort_by_key(|(index, _)| **index);
+        encoder.write_usize(values.len());
+        for (index, value) in values {
+            encoder.write_usize(*index);
+            encoder.write_field(value);
+        }
+        encoder.write_bytes(&self.salts.concat());
+    }
+
+    pub fn decode(decoder: &mut Decoder) -> Result<Self, DecodeError> {
+        let proof_bytes = decoder.read_bytes()?;
+        let len = decoder.read_len(8 + T::BYTE_SIZE)?;
+        let mut proof_values = HashMap::with_capacity(len);
+        for _ in 0..len {
+            let index = decoder.read_usize()?;
+            let value = decoder.read_field()?;
+            if proof_values.insert(index, value).is_some() {
+                return Err(DecodeError::DuplicateQueryIndex);
+            }
+        }
+        let salts = decoder.read_bytes()?;
+        if !salts.len().is_multiple_of(SALT_SIZE) {
+            return Err(DecodeError::InvalidLength);
+        }
+        Ok(QueryResult {
+            proof_bytes,
+            proof_values,
+            salts: salts
+                .chunks_exact(SALT_SIZE)
+                .map(|x| x.try_into().unwrap())
+                .collect(),
+        })
     }
 }
@@ -1,12 +1,29 @@
 use crate::algebra::field::Field;
-use rand::Rng;
+use crate::params::ProtocolParams;
+use crate::proof_of_work;
+use crate::transcript::Transcript;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
+/// Source of the verifier's challenges.
+///
+/// `RandomOracle::new` draws every challenge up front, which simulates an
+/// interactive verifier shared by both sides. `RandomOracle::from_transcript`
+/// instead derives each challenge from a Fiat-Shamir transcript of everything
+/// absorbed so far, so prover and verifier only need to agree on the label.
+///
+/// With `with_grinding`, the prover must find a proof-of-work nonce before
+/// the queries are sampled; `ProtocolParams` accounts for the grinding bits
+/// when choosing the query count.
 #[derive(Debug, Clone)]
 pub struct RandomOracle<T: Field> {
     pub beta: T,
     pub rlc: T,
     pub folding_challenges: Vec<T>,
     pub query_list: Vec<usize>,
+    query_num: usize,
+    grinding_bits: usize,
+    pow_seed: [u8; 32],
+    transcript: Option<Transcript>,
 }
 
 impl<T: Field> RandomOracle<T> {
@@ -22,6 +39,129 @@ impl<T: Field> RandomOracle<T> {
                 .into_iter()
                 .map(|_| rand::thread_rng().gen())
                 .collect(),
+            query_num,
+            grinding_bits: 0,
+            pow_seed: rand::thread_rng().gen(),
+            transcript: None,
         }
     }
+
+    pub fn from_transcript(transcript: Transcript, query_num: usize) -> Self {
+        RandomOracle {
+            beta: T::from_int(0),
+            rlc: T::from_int(0),
+            folding_challenges: vec![],
+            query_list: vec![],
+            query_num,
+            grinding_bits: 0,
+            pow_seed: [0; 32],
+            transcript: Some(transcript),
+        }
+    }
+
+    pub fn from_params(total_round: usize, params: &ProtocolParams) -> Self {
+        Self::new(total_round, params.query_num).with_grinding(params.grinding_bits)
+    }
+
+    pub fn with_grinding(mut self, grinding_bits: usize) -> Self {
+        self.grinding_bits = grinding_bits;
+        self
+    }
+
+    /// Draws the queries of an interactive oracle from `seed`, so that
+    /// proof sizes, which only depend on the queries, are reproducible. An
+    /// oracle with fewer queries gets a prefix of the same list.
+    pub fn with_query_seed(mut self, seed: u64) -> Self {
+        assert!(self.is_interactive());
+        let mut rng = StdRng::seed_from_u64(seed);
+        self.query_list = (0..self.query_num).map(|_| rng.gen()).collect();
+        self
+    }
+
+    pub fn query_num(&self) -> usize {
+        self.query_num
+    }
+
+    pub fn grinding_bits(&self) -> usize {
+        self.grinding_bits
+    }
+
+    pub fn is_interactive(&self) -> bool {
+        self.transcript.is_none()
+    }
+
+    pub fn absorb_root(&mut self, root: &impl AsRef<[u8]>) {
+        if let Some(transcript) = self.transcript.as_mut() {
+            transcript.append_root(root);
+        }
+    }
+
+    pub fn absorb_field_elements(&mut self, elements: &[T]) {
+        if let Some(transcript) = self.transcript.as_mut() {
+            transcript.append_field_elements(elements);
+        }
+    }
+
+    pub fn folding_challenge(&mut self, round: usize) -> T {
+        if let Some(transcript) = self.transcript.as_mut() {
+            assert_eq!(self.folding_challenges.len(), round);
+            self.folding_challenges.push(transcript.challenge());
+        }
+        self.folding_challenges[round]
+    }
+
+    /// Point the committed functions are evaluated at, e.g. by Gemini.
+    pub fn beta_challenge(&mut self) -> T {
+        if let Some(transcript) = self.transcript.as_mut() {
+            self.beta = transcript.challenge();
+        }
+        self.beta
+    }
+
+    /// Coefficient for random linear combinations, e.g. of a batch of
+    /// committed polynomials.
+    pub fn rlc_challenge(&mut self) -> T {
+        if let Some(transcript) = self.transcript.as_mut() {
+            self.rlc = transcript.challenge();
+        }
+        self.rlc
+    }
+
+    fn pow_seed(&self) -> [u8; 32] {
+        match self.transcript.as_ref() {
+            Some(transcript) => transcript.state(),
+            None => self.pow_seed,
+        }
+    }
+
+    /// Prover side of the proof of work; call right before `sample_queries`.
+    pub fn grind(&mut self) -> u64 {
+        if self.grinding_bits == 0 {
+            return 0;
+        }
+        let nonce = proof_of_work::grind(&self.pow_seed(), self.grinding_bits);
+        if let Some(transcript) = self.transcript.as_mut() {
+            transcript.append_bytes(&nonce.to_le_bytes());
+        }
+        nonce
+    }
+
+    /// Verifier side of `grind`.
+    pub fn check_proof_of_work(&mut self, nonce: u64) -> bool {
+        if self.grinding_bits == 0 {
+            return true;
+        }
+        let valid = proof_of_work::verify(&self.pow_seed(), self.grinding_bits, nonce);
+        if let Some(transcript) = self.transcript.as_mut() {
+            transcript.append_bytes(&nonce.to_le_bytes());
+        }
+        valid
+    }
+
+    pub fn sample_queries(&mut self) -> Vec<usize> {
+        if let Some(transcript) = self.transcript.as_mut() {
+            self.query_list = transcript.challenge_indices(self.query_num);
+        }
+        self.query_list.clone()
+    }
 }
@@ -0,0 +1,222 @@
+//! Sumcheck protocol for `sum_{x in {0,1}^n} g(f_1(x), ..., f_k(x))` with
+//! multilinear `f_i` and a combining function `g` of total degree at most
+//! `degree`, such as their product.
+//!
+//! Variables are bound in order, the first one being the lowest bit of the
+//! hypercube indices as in `MultilinearPolynomial::folding`. Each round
+//! polynomial is sent as its values at `0, 1, ..., degree`, appended to the
+//! transcript before the challenge is drawn. The claimed sum is not
+//! appended, the caller binds it beforehand. The protocol ends with the
+//! claim `g(f_1(r), ..., f_k(r)) = value` at the point `r` of the
+//! challenges, which the caller discharges, for instance with `pcs`
+//! openings of the `f_i` at `r`.
+
+use crate::algebra::{field::Field, polynomial::MultilinearPolynomial};
+use crate::parallel;
+use crate::transcript::Transcript;
+use crate::verification_error::VerificationError;
+use std::mem::size_of;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SumcheckProof<T> {
+    /// Values of each round polynomial at `0, 1, ..., degree`.
+    pub rounds: Vec<Vec<T>>,
+}
+
+impl<T: Field> SumcheckProof<T> {
+    pub fn proof_size(&self) -> usize {
+        self.rounds.iter().map(|x| x.len()).sum::<usize>() * size_of::<T>()
+    }
+}
+
+/// Claim the verifier reduces the sum to: `g(f_1(point), ...) = value`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SumcheckClaim<T> {
+    pub point: Vec<T>,
+    pub value: T,
+}
+
+/// Runs the prover on `polynomials`, returning the proof, the point and the
+/// values of the polynomials there.
+pub fn prove<T: Field>(
+    polynomials: &[MultilinearPolynomial<T>],
+    degree: usize,
+    combine: impl Fn(&[T]) -> T,
+    transcript: &mut Transcript,
+) -> (SumcheckProof<T>, Vec<T>, Vec<T>) {
+    let tables = polynomials.iter().map(|x| x.evaluations()).collect();
+    prove_tables(tables, degree, combine, transcript)
+}
+
+/// `prove` for polynomials given by their values over the hypercube.
+pub fn prove_tables<T: Field>(
+    mut tables: Vec<Vec<T>>,
+    degree: usize,
+    combine: impl Fn(&[T]) -> T,
+    transcript: &mut Transcript,
+) -> (SumcheckProof<T>, Vec<T>, Vec<T>) {
+    let len = tables[0].len();
+    assert_eq!(len & (len - 1), 0);
+    assert!(tables.iter().all(|x| x.len() == len));
+    let mut rounds = vec![];
+    let mut point = vec![];
+    let mut args = vec![T::from_int(0); tables.len()];
+    let mut steps = vec![T::from_int(0); tables.len()];
+    while tables[0].len() > 1 {
+        let half = tables[0].len() / 2;
+        let mut round = vec![T::from_int(0); degree + 1];
+        for i in 0..half {
+            // Values of the tables at 0, 1, ..., degree along the variable.
+            for (k, table) in tables.iter().enumerate() {
+                args[k] = table[2 * i];
+                steps[k] = table[2 * i + 1] - table[2 * i];
+            }
+            for (t, v) in round.iter_mut().enumerate() {
+                if t > 0 {
+                    for (x, step) in args.iter_mut().zip(&steps) {
+                        *x += *step;
+                    }
+                }
+                *v += combine(&args);
+            }
+        }
+        transcript.append_field_elements(&round);
+        let r: T = transcript.challenge();
+        for table in tables.iter_mut() {
+            let folded = parallel::map(half, |i| {
+                table[2 * i] + r * (table[2 * i + 1] - table[2 * i])
+            });
+            *table = folded;
+        }
+        rounds.push(round);
+        point.push(r);
+    }
+    let values = tables.iter().map(|x| x[0]).collect();
+    (SumcheckProof { rounds }, point, values)
+}
+
+/// Value at `x` of the polynomial taking `values` at `0, 1, ...`.
+fn interpolate<T: Field>(values: &[T], x: T) -> T {
+    let mut res = T::from_int(0);
+    for (i, v) in values.iter().enumerate() {
+        let mut numerator = *v;
+        let mut denominator = T::from_int(1);
+        for j in 0..values.len() {
+            if j != i {
+                numerator *= x - T::from_int(j as u64);
+                denominator *= T::from_int(i as u64) - T::from_int(j as u64);
+            }
+        }
+        res += numerator * denominator.inverse();
+    }
+    res
+}
+
+/// Checks the rounds of `proof` against `sum`, returning the claim left to
+/// the caller.
+pub fn verify<T: Field>(
+    sum: T,
+    variable_num: usize,
+    degree: usize,
+    proof: &SumcheckProof<T>,
+    transcript: &mut Transcript,
+) -> Result<SumcheckClaim<T>, VerificationError> {
+    if proof.rounds.len() != variable_num || proof.rounds.iter().any(|x| x.len() != degree + 1) {
+        return Err(VerificationError::WrongProofShape);
+    }
+    let mut value = sum;
+    let mut point = vec![];
+    for round in &proof.rounds {
+        if round[0] + round[1] != value {
+            return Err(VerificationError::EvaluationMismatch);
+        }
+        transcript.append_field_elements(round);
+        let r: T = transcript.challenge();
+        value = interpolate(round, r);
+        point.push(r);
+    }
+    Ok(SumcheckClaim { point, value })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::field::mersenne61_ext::Mersenne61Ext;
+
+    type T = Mersenne61Ext;
+
+    fn brute_force_sum(polynomials: &[MultilinearPolynomial<T>], combine: impl Fn(&[T]) -> T) -> T {
+        let variable_num = polynomials[0].variable_num();
+        let mut sum = T::from_int(0);
+        for i in 0..1 << variable_num {
+            let point = (0..variable_num)
+                .map(|k| T::from_int((i >> k & 1) as u64))
+                .collect();
+            let values = polynomials
+                .iter()
+                .map(|x| x.evaluate(&point))
+                .collect::<Vec<_>>();
+            sum += combine(&values);
+        }
+        sum
+    }
+
+    fn check(polynomials: &[MultilinearPolynomial<T>], degree: usize, combine: fn(&[T]) -> T) {
+        let variable_num = polynomials[0].variable_num();
+        let sum = brute_force_sum(polynomials, combine);
+        let (proof, point, values) =
+            prove(polynomials, degree, combine, &mut Transcript::new(b"test"));
+        let claim = verify(
+            sum,
+            variable_num,
+            degree,
+            &proof,
+            &mut Transcript::new(b"test"),
+        )
+        .unwrap();
+        assert_eq!(claim.point, point);
+        for (polynomial, value) in polynomials.iter().zip(&values) {
+            assert_eq!(polynomial.evaluate(&point), *value);
+        }
+        assert_eq!(claim.value, combine(&values));
+
+        assert_eq!(
+            verify(
+                sum + T::from_int(1),
+                variable_num,
+                degree,
+                &proof,
+                &mut Transcript::new(b"test")
+            ),
+            Err(VerificationError::EvaluationMismatch)
+        );
+        assert_eq!(
+            verify(
+                sum,
+                variable_num + 1,
+                degree,
+                &proof,
+                &mut Transcript::new(b"test")
+            ),
+            Err(VerificationError::WrongProofShape)
+        );
+    }
+
+    #[test]
+    fn product() {
+        let polynomials = (0..3)
+            .map(|_| MultilinearPolynomial::random_polynomial(6))
+            .collect::<Vec<_>>();
+        check(&polynomials, 3, |x| x[0] * x[1] * x[2]);
+        check(&polynomials[..1], 1, |x| x[0]);
+    }
+
+    #[test]
+    fn combination() {
+        let polynomials = (0..4)
+            .map(|_| MultilinearPolynomial::random_polynomial(5))
+            .collect::<Vec<_>>();
+        check(&polynomials, 2, |x| x[0] * x[1] + x[2] * x[3] + x[0]);
+        check(&polynomials, 4, |x| x[0] * x[1] * x[2] * x[3] - x[1] * x[2]);
+    }
+}
@@ -0,0 +1,80 @@
+use crate::algebra::field::{as_bytes_vec, Field};
+
+#[derive(Debug, Clone)]
+pub struct Transcript {
+    hasher: blake3::Hasher,
+}
+
+impl Transcript {
+    pub fn new(label: &[u8]) -> Self {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(&(label.len() as u64).to_le_bytes());
+        hasher.update(label);
+        Transcript { hasher }
+    }
+
+    pub fn append_bytes(&mut self, bytes: &[u8]) {
+        self.hasher.update(&(bytes.len() as u64).to_le_bytes());
+        self.hasher.update(bytes);
+    }
+
+    pub fn append_root(&mut self, root: &impl AsRef<[u8]>) {
+        self.append_bytes(root.as_ref());
+    }
+
+    pub fn append_field_elements<T: Field>(&mut self, elements: &[T]) {
+        self.append_bytes(&as_bytes_vec(elements));
+    }
+
+    /// Digest of everything appended so far, used as the grinding seed.
+    pub fn state(&self) -> [u8; 32] {
+        self.hasher.finalize().into()
+    }
+
+    fn squeeze(&mut self, output: &mut [u8]) {
+        self.hasher.finalize_xof().fill(output);
+        // Feed the output back so that consecutive squeezes differ.
+        self.append_bytes(output);
+    }
+
+    pub fn challenge<T: Field>(&mut self) -> T {
+        let mut bytes = [0u8; 32];
+        self.squeeze(&mut bytes);
+        T::from_uniform_bytes(&bytes)
+    }
+
+    pub fn challenge_indices(&mut self, num: usize) -> Vec<usize> {
+        let mut bytes = vec![0u8; num * 8];
+        self.squeeze(&mut bytes);
+        bytes
+            .chunks_exact(8)
+            .map(|x| u64::from_le_bytes(x.try_into().unwrap()) as usize)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algebra::field::mersenne61_ext::Mersenne61Ext;
+    use crate::merkle_tree::MERKLE_ROOT_SIZE;
+
+    #[test]
+    fn deterministic() {
+        let mut a = Transcript::new(b"test");
+        let mut b = Transcript::new(b"test");
+        a.append_root(&[1u8; MERKLE_ROOT_SIZE]);
+        b.append_root(&[1u8; MERKLE_ROOT_SIZE]);
+        let x: Mersenne61Ext = a.challenge();
+        let y: Mersenne61Ext = b.challenge();
+        assert_eq!(x, y);
+        assert_eq!(a.challenge_indices(10), b.challenge_indices(10));
+        let z: Mersenne61Ext = a.challenge();
+        assert_ne!(x, z);
+
+        let mut c = Transcript::new(b"test");
+        c.append_root(&[2u8; MERKLE_ROOT_SIZE]);
+        let w: Mersenne61Ext = c.challenge();
+        assert_ne!(x, w);
+    }
+}
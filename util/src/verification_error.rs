@@ -0,0 +1,45 @@
+/// Reasons a verifier rejects a proof. Verifiers return these instead of
+/// panicking so that malformed or malicious proofs can be handled by the
+/// caller.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationError {
+    /// A Merkle authentication path does not lead to the committed root.
+    BadMerklePath,
+    /// The query answer does not contain the value at this position.
+    MissingQueryValue(usize),
+    /// Folding consistency fails in this round.
+    FoldingMismatch(usize),
+    /// The final polynomial has a higher degree than allowed.
+    FinalPolynomialDegree,
+    /// The claimed evaluation does not match the opening.
+    EvaluationMismatch,
+    /// Number of roots, query results or values differs from the protocol.
+    WrongProofShape,
+    /// The grinding nonce does not have enough leading zero bits.
+    InvalidProofOfWork,
+    /// The proof was produced with different protocol parameters.
+    ParameterMismatch,
+}
+
+impl std::fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            VerificationError::BadMerklePath => write!(f, "bad merkle path"),
+            VerificationError::MissingQueryValue(index) => {
+                write!(f, "missing query value at {}", index)
+            }
+            VerificationError::FoldingMismatch(round) => {
+                write!(f, "folding mismatch at round {}", round)
+            }
+            VerificationError::FinalPolynomialDegree => {
+                write!(f, "final polynomial degree too high")
+            }
+            VerificationError::EvaluationMismatch => write!(f, "evaluation mismatch"),
+            VerificationError::WrongProofShape => write!(f, "wrong proof shape"),
+            VerificationError::InvalidProofOfWork => write!(f, "invalid proof of work"),
+            VerificationError::ParameterMismatch => write!(f, "protocol parameters mismatch"),
+        }
+    }
+}
+
+impl std::error::Error for VerificationError {}
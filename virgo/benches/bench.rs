@@ -4,145 +4,223 @@ use criterion::*;
 
 use util::{
     algebra::{
-        coset::Coset, field::mersenne61_ext::Mersenne61Ext, field::Field,
+        coset::Coset,
+        field::{
+            babybear::BabyBear,
+            extension::{QuadExt, QuarticExt},
+            fp64::Fp64,
+            koalabear::KoalaBear,
+            mersenne31_ext::Mersenne31Ext,
+            mersenne61_ext::Mersenne61Ext,
+            Field,
+        },
         polynomial::MultilinearPolynomial,
     },
+    merkle_tree::Blake3Algorithm,
+    multilinear_pcs::MultilinearPCS,
     random_oracle::RandomOracle,
 };
-use virgo::{prover::FriProver, verifier::FriVerifier};
+use virgo::{prover::FriProver, verifier::FriVerifier, VirgoParams};
 
-use util::{CODE_RATE, SECURITY_BITS};
+use util::params::ProtocolParams;
 
-fn commit(criterion: &mut Criterion, variable_num: usize) {
+fn commit<T: Field>(criterion: &mut Criterion, variable_num: usize) {
     let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
-    let mut interpolate_cosets = vec![Coset::new(
-        1 << (variable_num + CODE_RATE),
-        Mersenne61Ext::random_element(),
-    )];
-    for i in 1..variable_num {
-        interpolate_cosets.push(interpolate_cosets[i - 1].pow(2));
-    }
-    let random_oracle = RandomOracle::new(variable_num, SECURITY_BITS / CODE_RATE);
-    let vector_interpolation_coset = Coset::new(1 << variable_num, Mersenne61Ext::random_element());
-    criterion.bench_function(&format!("bench virgo commit {}", variable_num), move |b| {
-        b.iter_batched(
-            || polynomial.clone(),
-            |p| {
-                let prover = FriProver::new(
-                    variable_num,
-                    &interpolate_cosets,
-                    &vector_interpolation_coset,
-                    p,
-                    &random_oracle,
-                );
-                prover.commit_first_polynomial();
-            },
-            BatchSize::SmallInput,
-        );
-    });
+    let params = ProtocolParams::new(3, 100, 0, 0, T::FIELD_CHOICE).unwrap();
+    let interpolate_cosets = params
+        .interpolate_cosets(variable_num, T::random_element())
+        .unwrap();
+    let random_oracle = RandomOracle::from_params(variable_num, &params);
+    let vector_interpolation_coset = Coset::new(1 << variable_num, T::random_element());
+    criterion.bench_function(
+        &format!("virgo commit {:?} {}", params.field, variable_num),
+        move |b| {
+            b.iter_batched(
+                || polynomial.clone(),
+                |p| {
+                    let prover: FriProver<_> = FriProver::new(
+                        &params,
+                        &interpolate_cosets,
+                        &vector_interpolation_coset,
+                        p,
+                        &random_oracle,
+                    )
+                    .unwrap();
+                    prover.commit_first_polynomial();
+                },
+                BatchSize::SmallInput,
+            );
+        },
+    );
 }
 
 fn bench_commit(c: &mut Criterion) {
     for i in 5..21 {
-        commit(c, i);
+        commit::<Mersenne61Ext>(c, i);
+        commit::<QuadExt<Fp64>>(c, i);
+        commit::<QuarticExt<BabyBear>>(c, i);
+        commit::<QuarticExt<KoalaBear>>(c, i);
+        commit::<Mersenne31Ext>(c, i);
     }
 }
 
-fn open(criterion: &mut Criterion, variable_num: usize) {
+fn open<T: Field>(criterion: &mut Criterion, variable_num: usize) {
     let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
-    let mut interpolate_cosets = vec![Coset::new(
-        1 << (variable_num + CODE_RATE),
-        Mersenne61Ext::random_element(),
-    )];
-    for i in 1..variable_num {
-        interpolate_cosets.push(interpolate_cosets[i - 1].pow(2));
-    }
-    let random_oracle = RandomOracle::new(variable_num, SECURITY_BITS / CODE_RATE);
-    let vector_interpolation_coset = Coset::new(1 << variable_num, Mersenne61Ext::random_element());
-    let prover = FriProver::new(
-        variable_num,
+    let params = ProtocolParams::new(3, 100, 0, 0, T::FIELD_CHOICE).unwrap();
+    let interpolate_cosets = params
+        .interpolate_cosets(variable_num, T::random_element())
+        .unwrap();
+    let random_oracle = RandomOracle::from_params(variable_num, &params);
+    let vector_interpolation_coset = Coset::new(1 << variable_num, T::random_element());
+    let prover: FriProver<_> = FriProver::new(
+        &params,
         &interpolate_cosets,
         &vector_interpolation_coset,
         polynomial,
         &random_oracle,
-    );
+    )
+    .unwrap();
     let commit = prover.commit_first_polynomial();
     let mut verifier = FriVerifier::new(
+        &params,
         variable_num,
         &interpolate_cosets,
         &vector_interpolation_coset,
         commit,
         &random_oracle,
-    );
+    )
+    .unwrap();
     let open_point = verifier.get_open_point();
-    criterion.bench_function(&format!("virgo prove {}", variable_num), |b| {
-        b.iter_batched(
-            || (prover.clone(), verifier.clone()),
-            |(mut p, mut v)| {
-                p.commit_functions(&mut v, &open_point);
-                p.prove();
-                p.commit_foldings(&mut v);
-                p.query();
-            },
-            BatchSize::SmallInput,
-        )
-    });
+    criterion.bench_function(
+        &format!("virgo prove {:?} {}", params.field, variable_num),
+        |b| {
+            b.iter_batched(
+                || (prover.clone(), verifier.clone()),
+                |(mut p, mut v)| {
+                    p.commit_functions(&mut v, &open_point);
+                    p.prove();
+                    p.commit_foldings(&mut v);
+                    p.query();
+                },
+                BatchSize::SmallInput,
+            )
+        },
+    );
 }
 
 fn bench_open(c: &mut Criterion) {
     for i in 5..21 {
-        open(c, i);
+        open::<Mersenne61Ext>(c, i);
+        open::<QuadExt<Fp64>>(c, i);
+        open::<QuarticExt<BabyBear>>(c, i);
+        open::<QuarticExt<KoalaBear>>(c, i);
+        open::<Mersenne31Ext>(c, i);
     }
 }
 
-fn verify(criterion: &mut Criterion, variable_num: usize) {
+fn verify<T: Field>(criterion: &mut Criterion, variable_num: usize) {
     let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
-    let mut interpolate_cosets = vec![Coset::new(
-        1 << (variable_num + CODE_RATE),
-        Mersenne61Ext::random_element(),
-    )];
-    for i in 1..variable_num {
-        interpolate_cosets.push(interpolate_cosets[i - 1].pow(2));
-    }
-    let random_oracle = RandomOracle::new(variable_num, SECURITY_BITS / CODE_RATE);
-    let vector_interpolation_coset = Coset::new(1 << variable_num, Mersenne61Ext::random_element());
-    let mut prover = FriProver::new(
-        variable_num,
+    let params = ProtocolParams::new(3, 100, 0, 0, T::FIELD_CHOICE).unwrap();
+    let interpolate_cosets = params
+        .interpolate_cosets(variable_num, T::random_element())
+        .unwrap();
+    let random_oracle = RandomOracle::from_params(variable_num, &params);
+    let vector_interpolation_coset = Coset::new(1 << variable_num, T::random_element());
+    let mut prover: FriProver<_> = FriProver::new(
+        &params,
         &interpolate_cosets,
         &vector_interpolation_coset,
         polynomial,
         &random_oracle,
-    );
+    )
+    .unwrap();
     let commit = prover.commit_first_polynomial();
     let mut verifier = FriVerifier::new(
+        &params,
         variable_num,
         &interpolate_cosets,
         &vector_interpolation_coset,
         commit,
         &random_oracle,
-    );
+    )
+    .unwrap();
     let open_point = verifier.get_open_point();
     prover.commit_functions(&mut verifier, &open_point);
     prover.prove();
     prover.commit_foldings(&mut verifier);
     let (folding_proofs, function_proofs, v_value) = prover.query();
-    criterion.bench_function(&format!("virgo verify {}", variable_num), |b| {
-        b.iter(|| {
-            assert!(verifier.verify(&folding_proofs, &v_value, &function_proofs));
-        })
-    });
+    criterion.bench_function(
+        &format!("virgo verify {:?} {}", params.field, variable_num),
+        |b| {
+            b.iter(|| {
+                verifier
+                    .verify(&folding_proofs, &v_value, &function_proofs)
+                    .unwrap();
+            })
+        },
+    );
 }
 
 fn bench_verify(c: &mut Criterion) {
     for i in 5..21 {
-        verify(c, i);
+        verify::<Mersenne61Ext>(c, i);
+        verify::<QuadExt<Fp64>>(c, i);
+        verify::<QuarticExt<BabyBear>>(c, i);
+        verify::<QuarticExt<KoalaBear>>(c, i);
+        verify::<Mersenne31Ext>(c, i);
+    }
+}
+
+/// Full Virgo proof, GKR for the public polynomial included.
+fn end_to_end<T: Field + 'static>(criterion: &mut Criterion, variable_num: usize) {
+    let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
+    let params = ProtocolParams::new(3, 100, 0, 0, T::FIELD_CHOICE).unwrap();
+    let scheme =
+        <VirgoParams<T> as MultilinearPCS<_, Blake3Algorithm>>::setup(variable_num, params)
+            .unwrap();
+    let point: Vec<_> = (0..variable_num).map(|_| T::random_element()).collect();
+    let value = polynomial.evaluate(&point);
+    criterion.bench_function(
+        &format!("virgo total prove {:?} {}", params.field, variable_num),
+        |b| {
+            b.iter_batched(
+                || polynomial.clone(),
+                |p| {
+                    let (_, prover): (_, FriProver<_, Blake3Algorithm>) = scheme.commit(p).unwrap();
+                    scheme.open(&prover, &point)
+                },
+                BatchSize::SmallInput,
+            )
+        },
+    );
+    let (commitment, prover): (_, FriProver<_, Blake3Algorithm>) =
+        scheme.commit(polynomial).unwrap();
+    let proof = scheme.open(&prover, &point);
+    criterion.bench_function(
+        &format!("virgo total verify {:?} {}", params.field, variable_num),
+        |b| b.iter(|| scheme.verify(&commitment, &point, value, &proof).unwrap()),
+    );
+    println!(
+        "virgo total proof size of {} variables is {} bytes",
+        variable_num,
+        proof.proof_size()
+    );
+}
+
+fn bench_end_to_end(c: &mut Criterion) {
+    for i in 5..20 {
+        end_to_end::<Mersenne61Ext>(c, i);
+        end_to_end::<QuadExt<Fp64>>(c, i);
+        end_to_end::<QuarticExt<BabyBear>>(c, i);
+        end_to_end::<QuarticExt<KoalaBear>>(c, i);
+        end_to_end::<Mersenne31Ext>(c, i);
     }
 }
 
 criterion_group! {
     name = benches;
     config = Criterion::default().sample_size(10);
-    targets = bench_commit, bench_open, bench_verify
+    targets = bench_commit, bench_open, bench_verify, bench_end_to_end
 }
 
 criterion_main!(benches);
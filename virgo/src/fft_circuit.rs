@@ -0,0 +1,253 @@
+//! Circuit computing, from the tensor of the open point, the queried values
+//! on the first FRI coset of its interpolation `v` over the vector coset.
+//!
+//! The coefficients of `v` are obtained by an inverse transform by
+//! decimation in frequency, which leaves them in bit-reversed order. They
+//! are scaled by the coset shifts and spread over the FRI coset, which is
+//! what the first stages of a transform by decimation in time do to
+//! zero-padded input, and the remaining stages of that transform give `v`
+//! in natural order, from which the last layer selects the queried values.
+//! The wiring predicates of all layers but the last factor over the bits
+//! of the indices, so the verifier evaluates them in logarithmic time.
+
+use crate::gkr::{Circuit, Gate, GateLayer, Layer};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use util::algebra::{coset::Coset, field::Field};
+use util::transcript::Transcript;
+
+const TRANSCRIPT_LABEL: &[u8] = b"Virgo v values";
+
+/// `prod_{i in S} point_i` for every subset `S` given by the bits of the
+/// index.
+pub fn tensor<T: Field>(point: &[T]) -> Vec<T> {
+    let mut res = vec![T::from_int(1)];
+    for z in point {
+        let high = res.iter().map(|x| *x * *z).collect::<Vec<_>>();
+        res.extend(high);
+    }
+    res
+}
+
+/// Multilinear extension of `tensor(point)` at `r`.
+pub fn tensor_at<T: Field>(point: &[T], r: &[T]) -> T {
+    let mut res = T::from_int(1);
+    for (z, r) in point.iter().zip(r) {
+        res *= T::from_int(1) - *r + *r * *z;
+    }
+    res
+}
+
+/// `eq(z_i, x_i) eq(z_i, y_i)` weighted by `c` when the bit is set, for an
+/// output bit copied to both inputs.
+fn copied_bit<T: Field>(z: T, x: T, y: T, c: T) -> T {
+    let one = T::from_int(1);
+    (one - z) * (one - x) * (one - y) + z * x * y * c
+}
+
+/// Radix-2 butterflies between the values whose indices differ at `bit`,
+/// with twiddles `root^j` for `j` the bits of the index below it.
+struct Butterfly<T: Field> {
+    vars: usize,
+    bit: usize,
+    /// Primitive `2^(bit + 1)`-th root of unity.
+    root: T,
+    /// `(x + y, (x - y) root^j)` when decimating in frequency, otherwise
+    /// `(x + root^j y, x - root^j y)`.
+    in_frequency: bool,
+    twiddles: OnceLock<Vec<T>>,
+}
+
+impl<T: Field> Butterfly<T> {
+    fn new(vars: usize, bit: usize, root: T, in_frequency: bool) -> Self {
+        Butterfly {
+            vars,
+            bit,
+            root,
+            in_frequency,
+            twiddles: OnceLock::new(),
+        }
+    }
+
+    fn twiddles(&self) -> &Vec<T> {
+        self.twiddles.get_or_init(|| {
+            let mut res = vec![T::from_int(1)];
+            for i in 1..1 << self.bit {
+                res.push(res[i - 1] * self.root);
+            }
+            res
+        })
+    }
+}
+
+impl<T: Field> Layer<T> for Butterfly<T> {
+    fn input_vars(&self) -> usize {
+        self.vars
+    }
+
+    fn output_vars(&self) -> usize {
+        self.vars
+    }
+
+    fn gate(&self, z: usize) -> Gate<T> {
+        let half = 1 << self.bit;
+        let w = self.twiddles()[z & (half - 1)];
+        let one = T::from_int(1);
+        let (a, b) = match (self.in_frequency, z & half != 0) {
+            (true, false) => (one, one),
+            (true, true) => (w, -w),
+            (false, false) => (one, w),
+            (false, true) => (one, -w),
+        };
+        Gate::Linear {
+            x: z & !half,
+            y: z | half,
+            a,
+            b,
+        }
+    }
+
+    fn predicates(&self, z: &[T], x: &[T], y: &[T]) -> [T; 3] {
+        let one = T::from_int(1);
+        let s = self.bit;
+        let mut high = (one - x[s]) * y[s];
+        for i in s + 1..self.vars {
+            high *= copied_bit(z[i], x[i], y[i], one);
+        }
+        let mut low = one;
+        let mut low_twiddled = one;
+        let mut w = self.root;
+        for i in 0..s {
+            low *= copied_bit(z[i], x[i], y[i], one);
+            low_twiddled *= copied_bit(z[i], x[i], y[i], w);
+            w *= w;
+        }
+        let (left, right) = if self.in_frequency {
+            (
+                (one - z[s]) * low + z[s] * low_twiddled,
+                (one - z[s]) * low - z[s] * low_twiddled,
+            )
+        } else {
+            (low, (one - z[s] - z[s]) * low_twiddled)
+        };
+        [T::from_int(0), high * left, high * right]
+    }
+}
+
+/// Spreads values on `vars` variables over `vars + blowup` ones, `z`
+/// reading `z >> blowup` multiplied by `ratio^bitrev(z >> blowup) / 2^vars`.
+struct Spread<T: Field> {
+    vars: usize,
+    blowup: usize,
+    ratio: T,
+    scales: OnceLock<Vec<T>>,
+}
+
+impl<T: Field> Spread<T> {
+    fn scales(&self) -> &Vec<T> {
+        self.scales.get_or_init(|| {
+            let mut powers = vec![T::from_int(1 << self.vars).inverse()];
+            for i in 1..1 << self.vars {
+                powers.push(powers[i - 1] * self.ratio);
+            }
+            (0..1 << self.vars)
+                .map(|q| powers[bit_reverse(q, self.vars)])
+                .collect()
+        })
+    }
+}
+
+fn bit_reverse(x: usize, bits: usize) -> usize {
+    if bits == 0 {
+        0
+    } else {
+        x.reverse_bits() >> (usize::BITS as usize - bits)
+    }
+}
+
+impl<T: Field> Layer<T> for Spread<T> {
+    fn input_vars(&self) -> usize {
+        self.vars
+    }
+
+    fn output_vars(&self) -> usize {
+        self.vars + self.blowup
+    }
+
+    fn gate(&self, z: usize) -> Gate<T> {
+        let q = z >> self.blowup;
+        Gate::Linear {
+            x: q,
+            y: q,
+            a: self.scales()[q],
+            b: T::from_int(0),
+        }
+    }
+
+    fn predicates(&self, z: &[T], x: &[T], y: &[T]) -> [T; 3] {
+        let mut left = T::from_int(1 << self.vars).inverse();
+        let mut c = self.ratio;
+        for k in (0..self.vars).rev() {
+            left *= copied_bit(z[self.blowup + k], x[k], y[k], c);
+            c *= c;
+        }
+        [T::from_int(0), left, T::from_int(0)]
+    }
+}
+
+/// The circuit of the module documentation, selecting the values of `v`
+/// at `positions` of `fri_coset`, whose size is a multiple of that of
+/// `vector_coset`.
+pub fn v_circuit<T: Field + 'static>(
+    vector_coset: &Coset<T>,
+    fri_coset: &Coset<T>,
+    positions: &[usize],
+) -> Circuit<T> {
+    let vars = vector_coset.size().trailing_zeros() as usize;
+    let fri_vars = fri_coset.size().trailing_zeros() as usize;
+    let blowup = fri_vars - vars;
+    let mut layers: Vec<Box<dyn Layer<T>>> = vec![];
+    let omega_inv = vector_coset.generator().inverse();
+    for bit in (0..vars).rev() {
+        let root = omega_inv.pow(vector_coset.size() >> (bit + 1));
+        layers.push(Box::new(Butterfly::new(vars, bit, root, true)));
+    }
+    layers.push(Box::new(Spread {
+        vars,
+        blowup,
+        ratio: fri_coset.shift() * vector_coset.shift().inverse(),
+        scales: OnceLock::new(),
+    }));
+    for bit in blowup..fri_vars {
+        let root = fri_coset.generator().pow(fri_coset.size() >> (bit + 1));
+        layers.push(Box::new(Butterfly::new(fri_vars, bit, root, false)));
+    }
+    let one = T::from_int(1);
+    layers.push(Box::new(GateLayer {
+        input_vars: fri_vars,
+        gates: positions
+            .iter()
+            .map(|k| Gate::Linear {
+                x: *k,
+                y: *k,
+                a: one,
+                b: T::from_int(0),
+            })
+            .collect(),
+    }));
+    Circuit::new(layers)
+}
+
+pub(crate) fn transcript<T: Field>(open_point: &[T]) -> Transcript {
+    let mut transcript = Transcript::new(TRANSCRIPT_LABEL);
+    transcript.append_field_elements(open_point);
+    transcript
+}
+
+/// Queried positions in increasing order, with the values at them.
+pub(crate) fn sorted_values<T: Field>(values: &HashMap<usize, T>) -> (Vec<usize>, Vec<T>) {
+    let mut positions = values.keys().copied().collect::<Vec<_>>();
+    positions.sort();
+    let values = positions.iter().map(|k| values[k]).collect();
+    (positions, values)
+}
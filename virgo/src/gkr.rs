@@ -0,0 +1,313 @@
+//! GKR protocol for layered arithmetic circuits.
+//!
+//! Every gate reads two values of the layer below, so the values `W` of a
+//! layer are given by those `V` of the layer below as
+//! `W(z) = sum_{x,y} mul(z,x,y) V(x) V(y) + left(z,x,y) V(x) + right(z,x,y) V(y)`
+//! with the multilinear extensions of the wiring predicates. A claim on a
+//! random combination of `W` is reduced by a sumcheck over `x` and then one
+//! over `y` to the values `V(u)` and `V(v)` at the points they end on,
+//! which are merged into a claim on the layer below with random weights.
+//! The two values reached on the input are checked by the caller.
+
+use std::mem::size_of;
+use util::algebra::field::Field;
+use util::parallel;
+use util::sumcheck::{self, SumcheckProof};
+use util::transcript::Transcript;
+use util::verification_error::VerificationError;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Gate<T> {
+    /// `a * V(x) + b * V(y)`.
+    Linear { x: usize, y: usize, a: T, b: T },
+    /// `V(x) * V(y)`.
+    Mul { x: usize, y: usize },
+}
+
+impl<T: Field> Gate<T> {
+    fn evaluate(&self, values: &[T]) -> T {
+        match *self {
+            Gate::Linear { x, y, a, b } => a * values[x] + b * values[y],
+            Gate::Mul { x, y } => values[x] * values[y],
+        }
+    }
+}
+
+pub trait Layer<T: Field>: Send + Sync {
+    fn input_vars(&self) -> usize;
+
+    fn output_vars(&self) -> usize;
+
+    /// Number of gates, the remaining values of the layer being zero.
+    fn gate_num(&self) -> usize {
+        1 << self.output_vars()
+    }
+
+    fn gate(&self, z: usize) -> Gate<T>;
+
+    /// `[mul, left, right]` at `(z, x, y)`. The default sums over the gates,
+    /// layers with a regular wiring evaluate them in closed form instead.
+    fn predicates(&self, z: &[T], x: &[T], y: &[T]) -> [T; 3] {
+        gate_predicates(self, z, x, y)
+    }
+}
+
+/// The wiring predicates of `layer` at `(z, x, y)`, summed over its gates.
+pub fn gate_predicates<T: Field, L: Layer<T> + ?Sized>(
+    layer: &L,
+    z: &[T],
+    x: &[T],
+    y: &[T],
+) -> [T; 3] {
+    let mut res = [T::from_int(0); 3];
+    for k in 0..layer.gate_num() {
+        match layer.gate(k) {
+            Gate::Linear { x: i, y: j, a, b } => {
+                let e = eq_at(z, k) * eq_at(x, i) * eq_at(y, j);
+                res[1] += e * a;
+                res[2] += e * b;
+            }
+            Gate::Mul { x: i, y: j } => res[0] += eq_at(z, k) * eq_at(x, i) * eq_at(y, j),
+        }
+    }
+    res
+}
+
+/// Layer given by the list of its gates.
+pub struct GateLayer<T> {
+    pub input_vars: usize,
+    pub gates: Vec<Gate<T>>,
+}
+
+impl<T: Field> Layer<T> for GateLayer<T> {
+    fn input_vars(&self) -> usize {
+        self.input_vars
+    }
+
+    fn output_vars(&self) -> usize {
+        self.gates.len().next_power_of_two().trailing_zeros() as usize
+    }
+
+    fn gate_num(&self) -> usize {
+        self.gates.len()
+    }
+
+    fn gate(&self, z: usize) -> Gate<T> {
+        self.gates[z]
+    }
+}
+
+/// `eq(point, z)` for every `z` of the hypercube, the first coordinate of
+/// `point` being the lowest bit of `z`.
+pub fn eq_table<T: Field>(point: &[T]) -> Vec<T> {
+    let mut res = vec![T::from_int(1)];
+    for r in point {
+        let high = res.iter().map(|x| *x * *r).collect::<Vec<_>>();
+        for (x, y) in res.iter_mut().zip(&high) {
+            *x -= *y;
+        }
+        res.extend(high);
+    }
+    res
+}
+
+pub fn eq_at<T: Field>(point: &[T], z: usize) -> T {
+    let mut res = T::from_int(1);
+    for (i, r) in point.iter().enumerate() {
+        res *= if (z >> i) & 1 == 1 {
+            *r
+        } else {
+            T::from_int(1) - *r
+        };
+    }
+    res
+}
+
+/// Layers from the one reading the input up to the output layer.
+pub struct Circuit<T: Field> {
+    layers: Vec<Box<dyn Layer<T>>>,
+}
+
+impl<T: Field> Circuit<T> {
+    pub fn new(layers: Vec<Box<dyn Layer<T>>>) -> Self {
+        assert!(!layers.is_empty());
+        for i in 1..layers.len() {
+            assert_eq!(layers[i].input_vars(), layers[i - 1].output_vars());
+        }
+        Circuit { layers }
+    }
+
+    pub fn layers(&self) -> &Vec<Box<dyn Layer<T>>> {
+        &self.layers
+    }
+
+    /// Values of every layer, starting with `input`. All but the output
+    /// layer are padded with zeros to a power of two.
+    pub fn evaluate(&self, input: Vec<T>) -> Vec<Vec<T>> {
+        let mut values = vec![input];
+        for layer in &self.layers {
+            let below = values.last_mut().unwrap();
+            assert!(below.len() <= 1 << layer.input_vars());
+            below.resize(1 << layer.input_vars(), T::from_int(0));
+            let below = &*below;
+            let next = parallel::map(layer.gate_num(), |z| layer.gate(z).evaluate(below));
+            values.push(next);
+        }
+        values
+    }
+}
+
+#[derive(Clone)]
+pub struct LayerProof<T> {
+    /// Sumchecks over `x` and then `y`.
+    pub phases: [SumcheckProof<T>; 2],
+    /// `V(u)` and `V(v)` for the points `u` and `v` of the two sumchecks.
+    pub values: [T; 2],
+}
+
+/// One `LayerProof` per layer, from the output layer down.
+#[derive(Clone)]
+pub struct GkrProof<T> {
+    pub layers: Vec<LayerProof<T>>,
+}
+
+impl<T: Field> GkrProof<T> {
+    pub fn proof_size(&self) -> usize {
+        self.layers
+            .iter()
+            .map(|x| x.phases[0].proof_size() + x.phases[1].proof_size() + 2 * size_of::<T>())
+            .sum()
+    }
+}
+
+/// Sumcheck of `sum_x f(x) h(x) + c(x)`, returning the proof, the point it
+/// ends on and the value of `f` there.
+fn prove_phase<T: Field>(
+    transcript: &mut Transcript,
+    f: Vec<T>,
+    h: Vec<T>,
+    c: Vec<T>,
+) -> (SumcheckProof<T>, Vec<T>, T) {
+    let (proof, point, values) =
+        sumcheck::prove_tables(vec![f, h, c], 2, |x| x[0] * x[1] + x[2], transcript);
+    (proof, point, values[0])
+}
+
+/// Evaluates `circuit` on `input` and proves the result, returning the
+/// values of the output layer. They are appended to `transcript` first, so
+/// the verifier must start from the same transcript state.
+pub fn prove<T: Field>(
+    circuit: &Circuit<T>,
+    input: Vec<T>,
+    transcript: &mut Transcript,
+) -> (Vec<T>, GkrProof<T>) {
+    let values = circuit.evaluate(input);
+    let outputs = values.last().unwrap().clone();
+    transcript.append_field_elements(&outputs);
+    let output_vars = circuit.layers.last().unwrap().output_vars();
+    let g = (0..output_vars)
+        .map(|_| transcript.challenge())
+        .collect::<Vec<T>>();
+    let mut weights = eq_table(&g);
+    let mut layers = vec![];
+    for (i, layer) in circuit.layers.iter().enumerate().rev() {
+        let size = 1 << layer.input_vars();
+        let below = values[i].clone();
+
+        let mut h = vec![T::from_int(0); size];
+        let mut c = vec![T::from_int(0); size];
+        for (z, w) in weights.iter().take(layer.gate_num()).enumerate() {
+            let w = *w;
+            match layer.gate(z) {
+                Gate::Linear { x, y, a, b } => {
+                    h[x] += w * a;
+                    c[x] += w * b * below[y];
+                }
+                Gate::Mul { x, y } => h[x] += w * below[y],
+            }
+        }
+        let (phase_x, u, value_u) = prove_phase(transcript, below.clone(), h, c);
+
+        let eq_u = eq_table(&u);
+        let mut h = vec![T::from_int(0); size];
+        let mut c = vec![T::from_int(0); size];
+        for (z, w) in weights.iter().take(layer.gate_num()).enumerate() {
+            match layer.gate(z) {
+                Gate::Linear { x, y, a, b } => {
+                    let w = *w * eq_u[x];
+                    h[y] += w * b;
+                    c[y] += w * a * value_u;
+                }
+                Gate::Mul { x, y } => h[y] += *w * eq_u[x] * value_u,
+            }
+        }
+        let (phase_y, v, value_v) = prove_phase(transcript, below, h, c);
+
+        let values = [value_u, value_v];
+        transcript.append_field_elements(&values);
+        let alpha: T = transcript.challenge();
+        let beta: T = transcript.challenge();
+        if i > 0 {
+            let eq_v = eq_table(&v);
+            weights = parallel::map(size, |k| alpha * eq_u[k] + beta * eq_v[k]);
+        }
+        layers.push(LayerProof {
+            phases: [phase_x, phase_y],
+            values,
+        });
+    }
+    (outputs, GkrProof { layers })
+}
+
+/// Checks that `circuit` outputs `outputs`, `input` being the multilinear
+/// extension of its input.
+pub fn verify<T: Field>(
+    circuit: &Circuit<T>,
+    outputs: &[T],
+    input: impl Fn(&[T]) -> T,
+    proof: &GkrProof<T>,
+    transcript: &mut Transcript,
+) -> Result<(), VerificationError> {
+    let output_layer = circuit.layers.last().unwrap();
+    if outputs.len() != output_layer.gate_num() || proof.layers.len() != circuit.layers.len() {
+        return Err(VerificationError::WrongProofShape);
+    }
+    transcript.append_field_elements(outputs);
+    let g = (0..output_layer.output_vars())
+        .map(|_| transcript.challenge())
+        .collect::<Vec<T>>();
+    let mut claim = outputs
+        .iter()
+        .zip(eq_table(&g))
+        .fold(T::from_int(0), |acc, (y, e)| acc + *y * e);
+    let mut points = vec![(T::from_int(1), g)];
+    for (layer, layer_proof) in circuit.layers.iter().rev().zip(&proof.layers) {
+        let n = layer.input_vars();
+        let [phase_x, phase_y] = &layer_proof.phases;
+        let claim_u = sumcheck::verify(claim, n, 2, phase_x, transcript)?;
+        let claim_v = sumcheck::verify(claim_u.value, n, 2, phase_y, transcript)?;
+        let (u, v) = (claim_u.point, claim_v.point);
+        let [value_u, value_v] = layer_proof.values;
+        let mut predicates = [T::from_int(0); 3];
+        for (coefficient, z) in &points {
+            for (p, q) in predicates.iter_mut().zip(layer.predicates(z, &u, &v)) {
+                *p += *coefficient * q;
+            }
+        }
+        let [mul, left, right] = predicates;
+        if claim_v.value != value_v * (mul * value_u + right) + left * value_u {
+            return Err(VerificationError::EvaluationMismatch);
+        }
+
+        transcript.append_field_elements(&layer_proof.values);
+        let alpha: T = transcript.challenge();
+        let beta: T = transcript.challenge();
+        claim = alpha * value_u + beta * value_v;
+        points = vec![(alpha, u), (beta, v)];
+    }
+    let values = proof.layers.last().unwrap().values;
+    if input(&points[0].1) != values[0] || input(&points[1].1) != values[1] {
+        return Err(VerificationError::EvaluationMismatch);
+    }
+    Ok(())
+}
@@ -1,55 +1,218 @@
+pub mod fft_circuit;
+pub mod gkr;
 pub mod prover;
 pub mod verifier;
 
+use gkr::GkrProof;
+use prover::FriProver;
+use std::collections::HashMap;
+use std::mem::size_of;
+use util::algebra::{coset::Coset, field::Field, polynomial::MultilinearPolynomial};
+use util::merkle_tree::{Blake3Algorithm, MerkleHasher};
+use util::multilinear_pcs::MultilinearPCS;
+use util::params::{Capabilities, ParamsError, ProtocolParams};
+use util::query_result::QueryResult;
+use util::random_oracle::RandomOracle;
+use util::transcript::Transcript;
+use util::verification_error::VerificationError;
+use verifier::FriVerifier;
+
+/// Features of `ProtocolParams` the Virgo prover and verifier implement.
+pub const CAPABILITIES: Capabilities = Capabilities {
+    hiding: false,
+    shared_trees: false,
+    max_folding_arity: 2,
+    terminate_round: false,
+};
+
+const TRANSCRIPT_LABEL: &[u8] = b"Virgo";
+
+/// Public parameters of Virgo openings. The challenges of every opening are
+/// derived from its Fiat-Shamir transcript.
+#[derive(Clone)]
+pub struct VirgoParams<T: Field> {
+    pub variable_num: usize,
+    pub protocol: ProtocolParams,
+    pub interpolate_cosets: Vec<Coset<T>>,
+    pub vector_interpolation_coset: Coset<T>,
+}
+
+/// Everything the prover sends after the polynomial commitment.
+/// `v_values` are the values at the queries of the public polynomial
+/// interpolating the open point's tensor, proved by `gkr`.
+#[derive(Clone)]
+pub struct VirgoProof<T: Field, H: MerkleHasher = Blake3Algorithm> {
+    pub params: ProtocolParams,
+    pub h_root: H::Hash,
+    pub folding_roots: Vec<H::Hash>,
+    pub final_value: T,
+    pub pow_nonce: u64,
+    pub folding_proofs: Vec<QueryResult<T>>,
+    pub function_proofs: Vec<QueryResult<T>>,
+    pub v_values: HashMap<usize, T>,
+    pub gkr: GkrProof<T>,
+}
+
+impl<T: Field, H: MerkleHasher> VirgoProof<T, H> {
+    pub fn proof_size(&self) -> usize {
+        (self.folding_roots.len() + 1) * H::DIGEST_SIZE
+            + size_of::<T>()
+            + size_of::<u64>()
+            + self.v_values.len() * size_of::<T>()
+            + self.gkr.proof_size()
+            + self
+                .folding_proofs
+                .iter()
+                .chain(&self.function_proofs)
+                .map(|x| x.proof_size())
+                .sum::<usize>()
+    }
+}
+
+impl<T: Field> VirgoParams<T> {
+    /// Fiat-Shamir oracle used for non-interactive openings.
+    pub fn oracle(&self) -> RandomOracle<T> {
+        RandomOracle::from_transcript(Transcript::new(TRANSCRIPT_LABEL), self.protocol.query_num)
+            .with_grinding(self.protocol.grinding_bits)
+    }
+}
+
+impl<T: Field + 'static, H: MerkleHasher> MultilinearPCS<T, H> for VirgoParams<T> {
+    type Prover = FriProver<T, H>;
+    type Proof = VirgoProof<T, H>;
+
+    /// The polynomial is folded down to a constant, so the terminate round
+    /// of `protocol` must be 0.
+    fn setup(variable_num: usize, protocol: ProtocolParams) -> Result<Self, ParamsError> {
+        protocol.require(CAPABILITIES)?;
+        Ok(VirgoParams {
+            variable_num,
+            protocol,
+            interpolate_cosets: protocol.interpolate_cosets(variable_num, T::random_element())?,
+            vector_interpolation_coset: Coset::new(1 << variable_num, T::random_element()),
+        })
+    }
+
+    fn commit(
+        &self,
+        polynomial: MultilinearPolynomial<T>,
+    ) -> Result<(H::Hash, Self::Prover), ParamsError> {
+        if polynomial.variable_num() != self.variable_num {
+            return Err(ParamsError::VariableNumMismatch);
+        }
+        let prover = FriProver::new(
+            &self.protocol,
+            &self.interpolate_cosets,
+            &self.vector_interpolation_coset,
+            polynomial,
+            &self.oracle(),
+        )?;
+        Ok((prover.commit_first_polynomial(), prover))
+    }
+
+    fn open(&self, prover: &Self::Prover, point: &[T]) -> Self::Proof {
+        prover.open(&point.to_vec())
+    }
+
+    fn verify(
+        &self,
+        commitment: &H::Hash,
+        point: &[T],
+        value: T,
+        proof: &Self::Proof,
+    ) -> Result<(), VerificationError> {
+        if point.len() != self.variable_num || proof.folding_roots.len() + 1 != self.variable_num {
+            return Err(VerificationError::WrongProofShape);
+        }
+        let mut verifier = FriVerifier::<T, H>::new(
+            &self.protocol,
+            self.variable_num,
+            &self.interpolate_cosets,
+            &self.vector_interpolation_coset,
+            *commitment,
+            &self.oracle(),
+        )
+        .map_err(|_| VerificationError::ParameterMismatch)?;
+        verifier.set_open_point(point.to_vec());
+        verifier.receive_params(&proof.params);
+        verifier.set_h_root(proof.h_root);
+        verifier.set_evaluation(value);
+        for (i, root) in proof.folding_roots.iter().enumerate() {
+            verifier.receive_folding_root(self.interpolate_cosets[i + 1].size() / 2, *root);
+        }
+        verifier.set_final_value(proof.final_value);
+        verifier.set_pow_nonce(proof.pow_nonce);
+        verifier.verify(
+            &proof.folding_proofs,
+            &proof.v_values,
+            &proof.function_proofs,
+        )?;
+        verifier.verify_v_values(&proof.v_values, &proof.gkr)
+    }
+
+    fn proof_size(proof: &Self::Proof) -> usize {
+        proof.proof_size()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{prover::FriProver, verifier::FriVerifier};
+    use crate::fft_circuit::{tensor, tensor_at, v_circuit};
+    use crate::gkr::{self, gate_predicates, Circuit, Gate, GateLayer, Layer};
+    use crate::{prover::FriProver, verifier::FriVerifier, VirgoParams};
     use std::mem::size_of;
     use util::{
         algebra::{
             coset::Coset, field::mersenne61_ext::Mersenne61Ext, field::Field,
             polynomial::MultilinearPolynomial,
         },
-        merkle_tree::MERKLE_ROOT_SIZE,
+        merkle_tree::{Blake3Algorithm, Keccak256Algorithm, MerkleHasher, Sha256Algorithm},
+        multilinear_pcs::MultilinearPCS,
+        poseidon::PoseidonAlgorithm,
         random_oracle::RandomOracle,
+        transcript::Transcript,
     };
 
-    use util::{CODE_RATE, SECURITY_BITS};
-    fn output_proof_size(variable_num: usize) -> usize {
+    use util::params::{FieldChoice, ParamsError, ProtocolParams};
+    fn output_proof_size<H: MerkleHasher>(variable_num: usize, grinding_bits: usize) -> usize {
         let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
-        let mut interpolate_cosets = vec![Coset::new(
-            1 << (variable_num + CODE_RATE),
-            Mersenne61Ext::random_element(),
-        )];
-        for i in 1..variable_num {
-            interpolate_cosets.push(interpolate_cosets[i - 1].pow(2));
-        }
-        let random_oracle = RandomOracle::new(variable_num, SECURITY_BITS / CODE_RATE);
+        let params =
+            ProtocolParams::new(3, 100, 0, grinding_bits, FieldChoice::Mersenne61Ext).unwrap();
+        let interpolate_cosets = params
+            .interpolate_cosets(variable_num, Mersenne61Ext::random_element())
+            .unwrap();
+        let random_oracle = RandomOracle::from_params(variable_num, &params).with_query_seed(0);
         let vector_interpolation_coset =
             Coset::new(1 << variable_num, Mersenne61Ext::random_element());
-        let mut prover = FriProver::new(
-            variable_num,
+        let mut prover: FriProver<_, H> = FriProver::new(
+            &params,
             &interpolate_cosets,
             &vector_interpolation_coset,
             polynomial,
             &random_oracle,
-        );
+        )
+        .unwrap();
         let commit = prover.commit_first_polynomial();
         let mut verifier = FriVerifier::new(
+            &params,
             variable_num,
             &interpolate_cosets,
             &vector_interpolation_coset,
             commit,
             &random_oracle,
-        );
+        )
+        .unwrap();
         let open_point = verifier.get_open_point();
         prover.commit_functions(&mut verifier, &open_point);
         prover.prove();
         prover.commit_foldings(&mut verifier);
         let (folding_proofs, function_proofs, v_value) = prover.query();
-        assert!(verifier.verify(&folding_proofs, &v_value, &function_proofs));
+        assert_eq!(
+            verifier.verify(&folding_proofs, &v_value, &function_proofs),
+            Ok(())
+        );
         folding_proofs.iter().map(|x| x.proof_size()).sum::<usize>()
-            + (variable_num + 1) * MERKLE_ROOT_SIZE
+            + (variable_num + 1) * H::DIGEST_SIZE
             + size_of::<Mersenne61Ext>()
             + function_proofs
                 .iter()
@@ -60,11 +223,181 @@ mod tests {
     #[test]
     fn test_virgo_proof_size() {
         for i in 5..21 {
-            let proof_size = output_proof_size(i);
+            let proof_size = output_proof_size::<Blake3Algorithm>(i, 0);
             println!(
                 "virgo pcs proof size of {} variables is {} bytes",
                 i, proof_size
             );
         }
     }
+
+    #[test]
+    fn pluggable_hasher() {
+        output_proof_size::<Sha256Algorithm>(8, 0);
+        output_proof_size::<Keccak256Algorithm>(8, 0);
+        output_proof_size::<PoseidonAlgorithm<Mersenne61Ext>>(8, 0);
+    }
+
+    #[test]
+    fn grinding() {
+        let with_grinding = output_proof_size::<Blake3Algorithm>(10, 12);
+        assert!(with_grinding < output_proof_size::<Blake3Algorithm>(10, 0));
+    }
+
+    #[test]
+    fn send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<FriProver<Mersenne61Ext>>();
+        assert_send_sync::<FriVerifier<Mersenne61Ext>>();
+    }
+
+    #[test]
+    fn multilinear_pcs() {
+        type Scheme = VirgoParams<Mersenne61Ext>;
+        let variable_num = 8;
+        let params = <Scheme as MultilinearPCS<_, Blake3Algorithm>>::setup(
+            variable_num,
+            ProtocolParams::new(3, 100, 0, 0, FieldChoice::Mersenne61Ext).unwrap(),
+        )
+        .unwrap();
+        let polynomial = MultilinearPolynomial::random_polynomial(variable_num);
+        let (commitment, prover) = params.commit(polynomial.clone()).unwrap();
+        for _ in 0..2 {
+            let point: Vec<_> = (0..variable_num)
+                .map(|_| Mersenne61Ext::random_element())
+                .collect();
+            let value = polynomial.evaluate(&point);
+            let proof = params.open(&prover, &point);
+            assert_eq!(params.verify(&commitment, &point, value, &proof), Ok(()));
+            assert_ne!(
+                params.verify(
+                    &commitment,
+                    &point,
+                    value + Mersenne61Ext::from_int(1),
+                    &proof
+                ),
+                Ok(())
+            );
+            assert!(<Scheme as MultilinearPCS<_, Blake3Algorithm>>::proof_size(&proof) > 0);
+        }
+        let unsupported = [
+            (ProtocolParams::default(), "terminate round"),
+            (
+                ProtocolParams::default().with_hiding(true).unwrap(),
+                "hiding mode",
+            ),
+            (
+                ProtocolParams::default().with_shared_trees(true).unwrap(),
+                "shared trees",
+            ),
+            (
+                ProtocolParams::default().with_folding_arity(4).unwrap(),
+                "folding arity",
+            ),
+        ];
+        for (protocol, feature) in unsupported {
+            assert_eq!(
+                <Scheme as MultilinearPCS<_, Blake3Algorithm>>::setup(variable_num, protocol).err(),
+                Some(ParamsError::Unsupported(feature))
+            );
+        }
+    }
+
+    #[test]
+    fn gkr() {
+        type T = Mersenne61Ext;
+        let input = (0..8).map(|_| T::random_element()).collect::<Vec<_>>();
+        let linear = |x, y| Gate::Linear {
+            x,
+            y,
+            a: T::random_element(),
+            b: T::random_element(),
+        };
+        let layers: Vec<Box<dyn Layer<T>>> = vec![
+            Box::new(GateLayer {
+                input_vars: 3,
+                gates: vec![Gate::Mul { x: 0, y: 7 }, linear(3, 3), linear(5, 1)],
+            }),
+            Box::new(GateLayer {
+                input_vars: 2,
+                gates: vec![Gate::Mul { x: 2, y: 1 }, linear(0, 3)],
+            }),
+        ];
+        let circuit = Circuit::new(layers);
+        let input_at = |r: &[T]| {
+            (0..8)
+                .map(|k| input[k] * gkr::eq_at(r, k))
+                .fold(T::from_int(0), |acc, x| acc + x)
+        };
+        let (outputs, proof) = gkr::prove(&circuit, input.clone(), &mut Transcript::new(b"test"));
+        assert_eq!(outputs, circuit.evaluate(input.clone()).pop().unwrap());
+        assert_eq!(
+            gkr::verify(
+                &circuit,
+                &outputs,
+                input_at,
+                &proof,
+                &mut Transcript::new(b"test")
+            ),
+            Ok(())
+        );
+
+        let mut wrong_outputs = outputs.clone();
+        wrong_outputs[1] += T::from_int(1);
+        assert_ne!(
+            gkr::verify(
+                &circuit,
+                &wrong_outputs,
+                input_at,
+                &proof,
+                &mut Transcript::new(b"test")
+            ),
+            Ok(())
+        );
+        let mut wrong_proof = proof.clone();
+        wrong_proof.layers[1].values[0] += T::from_int(1);
+        assert_ne!(
+            gkr::verify(
+                &circuit,
+                &outputs,
+                input_at,
+                &wrong_proof,
+                &mut Transcript::new(b"test")
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn fft_circuit() {
+        type T = Mersenne61Ext;
+        let (vars, blowup) = (4, 3);
+        let point = (0..vars).map(|_| T::random_element()).collect::<Vec<_>>();
+        let vector_coset = Coset::new(1 << vars, T::random_element());
+        let fri_coset = Coset::new(1 << (vars + blowup), T::random_element());
+        let positions = vec![0, 5, 17, 64, 127];
+        let circuit = v_circuit(&vector_coset, &fri_coset, &positions);
+
+        let v = fri_coset.fft(vector_coset.ifft(tensor(&point)));
+        let outputs = circuit.evaluate(tensor(&point)).pop().unwrap();
+        assert_eq!(outputs, positions.iter().map(|k| v[*k]).collect::<Vec<_>>());
+
+        for layer in circuit.layers() {
+            let random = |n| (0..n).map(|_| T::random_element()).collect::<Vec<_>>();
+            let z = random(layer.output_vars());
+            let x = random(layer.input_vars());
+            let y = random(layer.input_vars());
+            assert_eq!(
+                layer.predicates(&z, &x, &y),
+                gate_predicates(layer.as_ref(), &z, &x, &y)
+            );
+        }
+
+        let r = (0..vars).map(|_| T::random_element()).collect::<Vec<_>>();
+        let mle = tensor(&point)
+            .iter()
+            .enumerate()
+            .fold(T::from_int(0), |acc, (k, x)| acc + *x * gkr::eq_at(&r, k));
+        assert_eq!(tensor_at(&point, &r), mle);
+    }
 }
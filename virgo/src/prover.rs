@@ -1,9 +1,12 @@
 use std::collections::HashMap;
 
+use super::fft_circuit::{self, v_circuit};
+use super::gkr::{self, GkrProof};
 use super::verifier::FriVerifier;
+use super::{VirgoProof, CAPABILITIES};
 use util::{
     algebra::polynomial::{MultilinearPolynomial, Polynomial, VanishingPolynomial},
-    merkle_tree::MERKLE_ROOT_SIZE,
+    params::{ParamsError, ProtocolParams},
     random_oracle::RandomOracle,
 };
 
@@ -13,23 +16,22 @@ use util::{
         coset::Coset,
         field::{as_bytes_vec, Field},
     },
-    merkle_tree::MerkleTreeProver,
+    merkle_tree::{Blake3Algorithm, MerkleHasher, MerkleTreeProver},
+    parallel,
 };
 
 #[derive(Clone)]
-struct InterpolateValue<T: Field> {
+struct InterpolateValue<T: Field, H: MerkleHasher> {
     value: Vec<T>,
-    merkle_tree: MerkleTreeProver,
+    merkle_tree: MerkleTreeProver<H>,
 }
 
-impl<T: Field> InterpolateValue<T> {
+impl<T: Field, H: MerkleHasher> InterpolateValue<T, H> {
     fn new(value: Vec<T>) -> Self {
         let len = value.len() / 2;
-        let merkle_tree = MerkleTreeProver::new(
-            (0..len)
-                .map(|i| as_bytes_vec(&[value[i], value[i + len]]))
-                .collect(),
-        );
+        let merkle_tree = MerkleTreeProver::new(parallel::map(len, |i| {
+            as_bytes_vec(&[value[i], value[i + len]])
+        }));
         Self { value, merkle_tree }
     }
 
@@ -37,7 +39,7 @@ impl<T: Field> InterpolateValue<T> {
         self.merkle_tree.leave_num()
     }
 
-    fn commit(&self) -> [u8; MERKLE_ROOT_SIZE] {
+    fn commit(&self) -> H::Hash {
         self.merkle_tree.commit()
     }
 
@@ -51,87 +53,104 @@ impl<T: Field> InterpolateValue<T> {
         QueryResult {
             proof_bytes,
             proof_values,
+            salts: vec![],
         }
     }
 }
 
 #[derive(Clone)]
-pub struct FriProver<T: Field> {
+pub struct FriProver<T: Field, H: MerkleHasher = Blake3Algorithm> {
+    params: ProtocolParams,
     total_round: usize,
     vector_interpolation_coset: Coset<T>,
     fri_cosets: Vec<Coset<T>>,
-    function_h: Option<InterpolateValue<T>>,
-    function_u: InterpolateValue<T>,
+    function_h: Option<InterpolateValue<T, H>>,
+    function_u: InterpolateValue<T, H>,
     interpolation_v: Option<Vec<T>>,
+    open_point: Vec<T>,
     poly_u: Polynomial<T>,
     polynomial: MultilinearPolynomial<T>,
-    foldings: Vec<InterpolateValue<T>>,
+    foldings: Vec<InterpolateValue<T, H>>,
     oracle: RandomOracle<T>,
+    pow_nonce: u64,
     evaluation: Option<T>,
     final_value: Option<T>,
 }
 
-impl<T: Field> FriProver<T> {
+impl<T: Field, H: MerkleHasher> FriProver<T, H> {
+    /// The polynomial is folded down to a constant, so
+    /// `params.terminate_round` must be 0.
     pub fn new(
-        total_round: usize,
+        params: &ProtocolParams,
         fri_cosets: &Vec<Coset<T>>,
         vector_interpolation_coset: &Coset<T>,
         polynomial: MultilinearPolynomial<T>,
         oracle: &RandomOracle<T>,
-    ) -> FriProver<T> {
+    ) -> Result<FriProver<T, H>, ParamsError> {
+        params.check_field::<T>()?;
+        params.require(CAPABILITIES)?;
+        let total_round = params.total_round(polynomial.variable_num())?;
         assert_eq!(
             vector_interpolation_coset.size(),
             1 << polynomial.variable_num()
         );
         let interpolation = vector_interpolation_coset.ifft(polynomial.coefficients().clone());
-        FriProver {
+        Ok(FriProver {
+            params: *params,
             total_round,
             vector_interpolation_coset: vector_interpolation_coset.clone(),
             fri_cosets: fri_cosets.clone(),
             function_h: None,
             function_u: InterpolateValue::new(fri_cosets[0].fft(interpolation.clone())),
             interpolation_v: None,
+            open_point: vec![],
             poly_u: Polynomial::new(interpolation),
             polynomial,
             foldings: vec![],
             oracle: oracle.clone(),
+            pow_nonce: 0,
             evaluation: None,
             final_value: None,
-        }
+        })
     }
 
-    pub fn commit_first_polynomial(&self) -> [u8; MERKLE_ROOT_SIZE] {
+    pub fn commit_first_polynomial(&self) -> H::Hash {
         self.function_u.commit()
     }
 
-    pub fn commit_functions(&mut self, verifier: &mut FriVerifier<T>, open_point: &Vec<T>) {
+    pub fn commit_functions(&mut self, verifier: &mut FriVerifier<T, H>, open_point: &Vec<T>) {
+        self.compute_h(open_point);
+        verifier.receive_params(&self.params);
+        verifier.set_h_root(self.function_h.as_ref().unwrap().commit());
+        verifier.set_evaluation(self.evaluation.unwrap());
+    }
+
+    fn compute_h(&mut self, open_point: &Vec<T>) {
         assert_eq!(open_point.len(), self.total_round);
-        let mut public_vector = vec![T::from_int(1)];
-        for i in open_point {
-            let len = public_vector.len();
-            for j in 0..len {
-                public_vector.push(public_vector[j] * i.clone());
-            }
-        }
+        self.oracle.absorb_root(&self.commit_first_polynomial());
+        self.oracle.absorb_field_elements(open_point);
+        let public_vector = fft_circuit::tensor(open_point);
         let poly_v = Polynomial::new(self.vector_interpolation_coset.ifft(public_vector));
         assert!(poly_v.degree() < self.vector_interpolation_coset.size());
         let h = Coset::mult(&self.poly_u, &poly_v)
             .over_vanish_polynomial(&VanishingPolynomial::new(&self.vector_interpolation_coset));
         assert!(h.degree() < self.vector_interpolation_coset.size());
         let function_h = InterpolateValue::new(self.fri_cosets[0].fft(h.coefficients().clone()));
-        verifier.set_h_root(function_h.commit());
+        let evaluation = self.polynomial.evaluate(open_point);
+        self.oracle.absorb_root(&function_h.commit());
+        self.oracle.absorb_field_elements(&[evaluation]);
         self.function_h = Some(function_h);
         self.interpolation_v = Some(self.fri_cosets[0].fft(poly_v.coefficients().clone()));
-        let evaluation = self.polynomial.evaluate(open_point);
         self.evaluation = Some(evaluation);
-        verifier.set_evaluation(evaluation);
+        self.open_point = open_point.clone();
     }
 
-    pub fn commit_foldings(&self, verifier: &mut FriVerifier<T>) {
+    pub fn commit_foldings(&self, verifier: &mut FriVerifier<T, H>) {
         for i in 0..(self.total_round - 1) {
             verifier.receive_folding_root(self.foldings[i].leave_num(), self.foldings[i].commit());
         }
-        verifier.set_final_value(self.final_value.unwrap())
+        verifier.set_final_value(self.final_value.unwrap());
+        verifier.set_pow_nonce(self.pow_nonce);
     }
 
     fn initial_interpolation(&self) -> Vec<T> {
@@ -160,44 +179,43 @@ impl<T: Field> FriProver<T> {
     }
 
     fn evaluation_next_domain(&self, round: usize, challenge: T) -> Vec<T> {
-        let mut res = vec![];
         let coset = &self.fri_cosets[round];
         let len = coset.size();
-        if round == 0 {
-            let function = self.initial_interpolation();
-            for i in 0..(len / 2) {
-                let x = function[i];
-                let nx = function[i + len / 2];
-                let new_v = (x + nx) + challenge * (x - nx) * coset.element_inv_at(i);
-                res.push(new_v);
-            }
+        let function;
+        let values = if round == 0 {
+            function = self.initial_interpolation();
+            &function
         } else {
-            let last_folding = &self.foldings.last().unwrap().value;
-            for i in 0..(len / 2) {
-                let x = last_folding[i];
-                let nx = last_folding[i + len / 2];
-                let new_v = (x + nx) + challenge * (x - nx) * coset.element_inv_at(i);
-                res.push(new_v);
-            }
-        }
-        res
+            &self.foldings.last().unwrap().value
+        };
+        let elements_inv = coset.elements_inv();
+        parallel::map(len / 2, |i| {
+            let x = values[i];
+            let nx = values[i + len / 2];
+            (x + nx) + challenge * (x - nx) * elements_inv[i]
+        })
     }
 
     pub fn prove(&mut self) {
+        self.oracle.rlc_challenge();
         for i in 0..self.total_round {
-            let challenge = self.oracle.folding_challenges[i];
+            let challenge = self.oracle.folding_challenge(i);
             let next_evalutation = self.evaluation_next_domain(i, challenge);
             if i < self.total_round - 1 {
                 let interpolate_value = InterpolateValue::new(next_evalutation);
+                self.oracle.absorb_root(&interpolate_value.commit());
                 self.foldings.push(interpolate_value);
             } else {
                 let x = next_evalutation[0];
                 for i in &next_evalutation {
                     assert_eq!(x, *i);
                 }
-                self.final_value = Some(next_evalutation[0]);
+                self.oracle.absorb_field_elements(&[x]);
+                self.final_value = Some(x);
             }
         }
+        self.pow_nonce = self.oracle.grind();
+        self.oracle.sample_queries();
     }
 
     pub fn query(&self) -> (Vec<QueryResult<T>>, Vec<QueryResult<T>>, HashMap<usize, T>) {
@@ -236,4 +254,46 @@ impl<T: Field> FriProver<T> {
         }
         (folding_res, functions_res.unwrap(), v_value.unwrap())
     }
+
+    /// GKR proof that `v_values`, as returned by `query`, are the values of
+    /// the polynomial interpolating the tensor of the open point.
+    pub fn prove_v_values(&self, v_values: &HashMap<usize, T>) -> GkrProof<T>
+    where
+        T: 'static,
+    {
+        let (positions, _) = fft_circuit::sorted_values(v_values);
+        let circuit = v_circuit(
+            &self.vector_interpolation_coset,
+            &self.fri_cosets[0],
+            &positions,
+        );
+        let mut transcript = fft_circuit::transcript(&self.open_point);
+        let input = fft_circuit::tensor(&self.open_point);
+        gkr::prove(&circuit, input, &mut transcript).1
+    }
+
+    /// Opening at `open_point` with the challenges of the prover's oracle.
+    /// It does not change the prover, but must not be mixed with the
+    /// interactive methods above.
+    pub fn open(&self, open_point: &Vec<T>) -> VirgoProof<T, H>
+    where
+        T: 'static,
+    {
+        let mut session = self.clone();
+        session.compute_h(open_point);
+        session.prove();
+        let (folding_proofs, function_proofs, v_values) = session.query();
+        let gkr = session.prove_v_values(&v_values);
+        VirgoProof {
+            params: session.params,
+            h_root: session.function_h.as_ref().unwrap().commit(),
+            folding_roots: session.foldings.iter().map(|x| x.commit()).collect(),
+            final_value: session.final_value.unwrap(),
+            pow_nonce: session.pow_nonce,
+            folding_proofs,
+            function_proofs,
+            v_values,
+            gkr,
+        }
+    }
 }
@@ -1,39 +1,51 @@
 use std::collections::HashMap;
 
+use super::fft_circuit::{self, v_circuit};
+use super::gkr::{self, GkrProof};
+use super::CAPABILITIES;
 use util::algebra::polynomial::VanishingPolynomial;
-use util::merkle_tree::MERKLE_ROOT_SIZE;
+use util::params::{ParamsError, ProtocolParams};
 use util::query_result::QueryResult;
 use util::random_oracle::RandomOracle;
 use util::{
     algebra::{coset::Coset, field::Field},
-    merkle_tree::MerkleTreeVerifier,
+    merkle_tree::{Blake3Algorithm, MerkleHasher, MerkleTreeVerifier},
+    verification_error::VerificationError,
 };
 
 #[derive(Clone)]
-pub struct FriVerifier<T: Field> {
+pub struct FriVerifier<T: Field, H: MerkleHasher = Blake3Algorithm> {
+    params: ProtocolParams,
+    prover_params: Option<ProtocolParams>,
     total_round: usize,
     interpolate_cosets: Vec<Coset<T>>,
     vector_interpolation_coset: Coset<T>,
-    u_root: MerkleTreeVerifier,
-    h_root: Option<MerkleTreeVerifier>,
-    folding_root: Vec<MerkleTreeVerifier>,
+    u_root: MerkleTreeVerifier<H>,
+    h_root: Option<MerkleTreeVerifier<H>>,
+    folding_root: Vec<MerkleTreeVerifier<H>>,
     oracle: RandomOracle<T>,
     vanishing_polynomial: VanishingPolynomial<T>,
     final_value: Option<T>,
     evaluation: Option<T>,
     open_point: Option<Vec<T>>,
+    pow_nonce: u64,
 }
 
-impl<T: Field> FriVerifier<T> {
+impl<T: Field, H: MerkleHasher> FriVerifier<T, H> {
     pub fn new(
-        total_round: usize,
+        params: &ProtocolParams,
+        variable_num: usize,
         coset: &Vec<Coset<T>>,
         vector_interpolation_coset: &Coset<T>,
-        polynomial_commitment: [u8; MERKLE_ROOT_SIZE],
+        polynomial_commitment: H::Hash,
         oracle: &RandomOracle<T>,
-    ) -> Self {
-        FriVerifier {
-            total_round,
+    ) -> Result<Self, ParamsError> {
+        params.check_field::<T>()?;
+        params.require(CAPABILITIES)?;
+        Ok(FriVerifier {
+            params: *params,
+            prover_params: None,
+            total_round: params.total_round(variable_num)?,
             interpolate_cosets: coset.clone(),
             vector_interpolation_coset: vector_interpolation_coset.clone(),
             u_root: MerkleTreeVerifier {
@@ -46,8 +58,9 @@ impl<T: Field> FriVerifier<T> {
             vanishing_polynomial: VanishingPolynomial::new(vector_interpolation_coset),
             final_value: None,
             open_point: None,
+            pow_nonce: 0,
             evaluation: None,
-        }
+        })
     }
 
     pub fn set_evaluation(&mut self, v: T) {
@@ -62,37 +75,95 @@ impl<T: Field> FriVerifier<T> {
         point
     }
 
-    pub fn set_h_root(&mut self, h_root: [u8; MERKLE_ROOT_SIZE]) {
+    pub fn set_open_point(&mut self, open_point: Vec<T>) {
+        self.open_point = Some(open_point);
+    }
+
+    pub fn set_h_root(&mut self, h_root: H::Hash) {
         self.h_root = Some(MerkleTreeVerifier {
             merkle_root: h_root,
             leave_number: self.interpolate_cosets[0].size() / 2,
         });
     }
 
-    pub fn receive_folding_root(
-        &mut self,
-        leave_number: usize,
-        folding_root: [u8; MERKLE_ROOT_SIZE],
-    ) {
+    pub fn receive_folding_root(&mut self, leave_number: usize, folding_root: H::Hash) {
         self.folding_root.push(MerkleTreeVerifier {
             leave_number,
             merkle_root: folding_root,
         });
     }
 
+    /// Parameters the prover claims to have used, checked by `verify`.
+    pub fn receive_params(&mut self, params: &ProtocolParams) {
+        self.prover_params = Some(*params);
+    }
+
     pub fn set_final_value(&mut self, value: T) {
-        assert_ne!(value, T::from_int(0));
         self.final_value = Some(value);
     }
 
+    pub fn set_pow_nonce(&mut self, nonce: u64) {
+        self.pow_nonce = nonce;
+    }
+
+    fn replay_oracle(
+        &self,
+        h_root: &MerkleTreeVerifier<H>,
+        evaluation: T,
+        final_value: T,
+    ) -> Result<RandomOracle<T>, VerificationError> {
+        let mut oracle = self.oracle.clone();
+        oracle.absorb_root(&self.u_root.merkle_root);
+        oracle.absorb_field_elements(
+            self.open_point
+                .as_ref()
+                .ok_or(VerificationError::WrongProofShape)?,
+        );
+        oracle.absorb_root(&h_root.merkle_root);
+        oracle.absorb_field_elements(&[evaluation]);
+        oracle.rlc_challenge();
+        for i in 0..self.total_round {
+            oracle.folding_challenge(i);
+            if i < self.total_round - 1 {
+                oracle.absorb_root(&self.folding_root[i].merkle_root);
+            }
+        }
+        oracle.absorb_field_elements(&[final_value]);
+        if !oracle.check_proof_of_work(self.pow_nonce) {
+            return Err(VerificationError::InvalidProofOfWork);
+        }
+        oracle.sample_queries();
+        Ok(oracle)
+    }
+
     pub fn verify(
         &self,
         folding_proofs: &Vec<QueryResult<T>>,
         v_values: &HashMap<usize, T>,
         function_proofs: &Vec<QueryResult<T>>,
-    ) -> bool {
-        let mut leaf_indices = self.oracle.query_list.clone();
-        let rlc = self.oracle.rlc;
+    ) -> Result<(), VerificationError> {
+        if self.prover_params != Some(self.params) {
+            return Err(VerificationError::ParameterMismatch);
+        }
+        if self.total_round == 0
+            || function_proofs.len() != 2
+            || folding_proofs.len() != self.total_round - 1
+            || self.folding_root.len() != self.total_round - 1
+        {
+            return Err(VerificationError::WrongProofShape);
+        }
+        let h_root = self
+            .h_root
+            .as_ref()
+            .ok_or(VerificationError::WrongProofShape)?;
+        let evaluation = self.evaluation.ok_or(VerificationError::WrongProofShape)?;
+        let final_value = self
+            .final_value
+            .filter(|x| !x.is_zero())
+            .ok_or(VerificationError::WrongProofShape)?;
+        let oracle = self.replay_oracle(h_root, evaluation, final_value)?;
+        let mut leaf_indices = oracle.query_list.clone();
+        let rlc = oracle.rlc;
         let h_size = T::from_int(self.vector_interpolation_coset.size() as u64);
         for i in 0..self.total_round {
             let domain_size = self.interpolate_cosets[i].size();
@@ -104,21 +175,23 @@ impl<T: Field> FriVerifier<T> {
             leaf_indices.dedup();
 
             if i == 0 {
-                assert!(function_proofs[0].verify_merkle_tree(&leaf_indices, &self.u_root));
-                assert!(function_proofs[1]
-                    .verify_merkle_tree(&leaf_indices, &self.h_root.as_ref().unwrap()));
+                function_proofs[0].verify_merkle_tree(&leaf_indices, &self.u_root)?;
+                function_proofs[1].verify_merkle_tree(&leaf_indices, h_root)?;
             } else {
-                folding_proofs[i - 1].verify_merkle_tree(&leaf_indices, &self.folding_root[i - 1]);
+                folding_proofs[i - 1]
+                    .verify_merkle_tree(&leaf_indices, &self.folding_root[i - 1])?;
             }
 
-            let challenge = self.oracle.folding_challenges[i];
-            let get_folding_value = |index: &usize| {
+            let challenge = oracle.folding_challenges[i];
+            let get_folding_value = |index: usize| {
                 if i == 0 {
-                    let u = function_proofs[0].proof_values[index];
-                    let h = function_proofs[1].proof_values[index];
-                    let v = v_values[index];
-                    let x = self.interpolate_cosets[i].element_at(*index);
-                    let x_inv = self.interpolate_cosets[i].element_inv_at(*index);
+                    let u = function_proofs[0].value_at(index)?;
+                    let h = function_proofs[1].value_at(index)?;
+                    let v = *v_values
+                        .get(&index)
+                        .ok_or(VerificationError::MissingQueryValue(index))?;
+                    let x = self.interpolate_cosets[i].compute_element_at(index);
+                    let x_inv = self.interpolate_cosets[i].compute_element_inv_at(index);
 
                     let mut res = u;
                     let mut acc = rlc;
@@ -127,32 +200,66 @@ impl<T: Field> FriVerifier<T> {
                     res += acc
                         * (u * v * h_size
                             - self.vanishing_polynomial.evaluation_at(x) * h * h_size
-                            - self.evaluation.unwrap())
+                            - evaluation)
                         * x_inv;
-                    res
+                    Ok(res)
                 } else {
-                    folding_proofs[i - 1].proof_values[index]
+                    folding_proofs[i - 1].value_at(index)
                 }
             };
 
             for j in &leaf_indices {
-                let x = get_folding_value(j);
-                let nx = get_folding_value(&(j + domain_size / 2));
-                let v =
-                    x + nx + challenge * (x - nx) * self.interpolate_cosets[i].element_inv_at(*j);
-                if i < self.total_round - 1 {
-                    if v != folding_proofs[i].proof_values[j] {
-                        panic!("{}", i);
-                        return false;
-                    }
+                let x = get_folding_value(*j)?;
+                let nx = get_folding_value(j + domain_size / 2)?;
+                let v = x
+                    + nx
+                    + challenge * (x - nx) * self.interpolate_cosets[i].compute_element_inv_at(*j);
+                let next_v = if i < self.total_round - 1 {
+                    folding_proofs[i].value_at(*j)?
                 } else {
-                    if v != self.final_value.unwrap() {
-                        panic!();
-                        return false;
-                    }
+                    final_value
+                };
+                if v != next_v {
+                    return Err(VerificationError::FoldingMismatch(i));
                 }
             }
         }
-        true
+        Ok(())
+    }
+
+    /// Checks the GKR proof that `v_values`, used by `verify`, are the
+    /// values of the polynomial interpolating the tensor of the open point.
+    pub fn verify_v_values(
+        &self,
+        v_values: &HashMap<usize, T>,
+        proof: &GkrProof<T>,
+    ) -> Result<(), VerificationError>
+    where
+        T: 'static,
+    {
+        let open_point = self
+            .open_point
+            .as_ref()
+            .ok_or(VerificationError::WrongProofShape)?;
+        let (positions, values) = fft_circuit::sorted_values(v_values);
+        if positions
+            .iter()
+            .any(|k| *k >= self.interpolate_cosets[0].size())
+        {
+            return Err(VerificationError::WrongProofShape);
+        }
+        let circuit = v_circuit(
+            &self.vector_interpolation_coset,
+            &self.interpolate_cosets[0],
+            &positions,
+        );
+        let mut transcript = fft_circuit::transcript(open_point);
+        gkr::verify(
+            &circuit,
+            &values,
+            |r| fft_circuit::tensor_at(open_point, r),
+            proof,
+            &mut transcript,
+        )
     }
 }
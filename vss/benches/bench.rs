@@ -9,99 +9,92 @@ use vss::avss::dealer::Dealer;
 use vss::avss::party::AvssParty;
 
 use util::algebra::coset::Coset;
-use util::algebra::field::mersenne61_ext::Mersenne61Ext;
+use util::algebra::field::{
+    babybear::BabyBear,
+    extension::{QuadExt, QuarticExt},
+    fp64::Fp64,
+    koalabear::KoalaBear,
+    mersenne31_ext::Mersenne31Ext,
+    mersenne61_ext::Mersenne61Ext,
+};
 use util::split_n;
 
-use util::{CODE_RATE, SECURITY_BITS};
-fn vss_deal(log_n: usize, terminate_round: usize) {
+use util::params::ProtocolParams;
+fn vss_deal<T: Field + 'static>(log_n: usize, terminate_round: usize) {
     let log_t = log_n - 1;
-    let oracle = RandomOracle::new(log_t - terminate_round, SECURITY_BITS / CODE_RATE);
-    let mut interpolate_cosets = vec![Coset::new(
-        1 << (log_t + CODE_RATE),
-        Mersenne61Ext::random_element(),
-    )];
-    for i in 1..log_t {
-        interpolate_cosets.push(interpolate_cosets[i - 1].pow(2));
-    }
+    let params = ProtocolParams::new(3, 100, terminate_round, 0, T::FIELD_CHOICE).unwrap();
+    let interpolate_cosets = params
+        .interpolate_cosets(log_t, T::random_element())
+        .unwrap();
+    let oracle = RandomOracle::from_params(log_t - terminate_round, &params);
     let polynomial = MultilinearPolynomial::random_polynomial(log_t);
 
-    let x_shift = Mersenne61Ext::random_element();
+    let x_shift = T::random_element();
     let coset_x = Coset::new(1 << log_n, x_shift);
     let mut folding_parameter = vec![];
     let v = split_n((1 << log_t) - 1);
     for i in &v {
         folding_parameter.push(coset_x.pow(*i).all_elements());
     }
-    let mut parties = vec![];
+    let mut parties: Vec<AvssParty<_>> = vec![];
     for i in 0..(1 << log_n) {
         let mut open_point = vec![];
         for j in 0..log_t {
             open_point.push(folding_parameter[j][i % folding_parameter[j].len()]);
         }
-        parties.push(AvssParty::new(
-            log_t - terminate_round,
-            &interpolate_cosets,
-            open_point,
-            &oracle,
-        ));
+        parties.push(AvssParty::new(&params, &interpolate_cosets, open_point, &oracle).unwrap());
     }
     let mut dealer = Dealer::new(
-        log_t - terminate_round,
+        &params,
         &polynomial,
         &interpolate_cosets,
         &oracle,
         &folding_parameter,
-    );
+    )
+    .unwrap();
     dealer.send_evaluations(&mut parties);
-    dealer.commit_functions(&parties);
+    dealer.commit_functions(&mut parties);
     dealer.prove();
-    dealer.commit_foldings(&parties);
+    dealer.commit_foldings(&mut parties);
     dealer.query();
 }
 
-fn vss_verify(c: &mut Criterion, log_n: usize, terminate_round: usize) {
+fn vss_verify<T: Field + 'static>(c: &mut Criterion, log_n: usize, terminate_round: usize) {
     let log_t = log_n - 1;
-    let oracle = RandomOracle::new(log_t - terminate_round, SECURITY_BITS / CODE_RATE);
-    let mut interpolate_cosets = vec![Coset::new(
-        1 << (log_t + CODE_RATE),
-        Mersenne61Ext::random_element(),
-    )];
-    for i in 1..log_t {
-        interpolate_cosets.push(interpolate_cosets[i - 1].pow(2));
-    }
+    let params = ProtocolParams::new(3, 100, terminate_round, 0, T::FIELD_CHOICE).unwrap();
+    let interpolate_cosets = params
+        .interpolate_cosets(log_t, T::random_element())
+        .unwrap();
+    let oracle = RandomOracle::from_params(log_t - terminate_round, &params);
     let polynomial = MultilinearPolynomial::random_polynomial(log_t);
 
-    let x_shift = Mersenne61Ext::random_element();
+    let x_shift = T::random_element();
     let coset_x = Coset::new(1 << log_n, x_shift);
     let mut folding_parameter = vec![];
     let v = split_n((1 << log_t) - 1);
     for i in &v {
         folding_parameter.push(coset_x.pow(*i).all_elements());
     }
-    let mut parties = vec![];
+    let mut parties: Vec<AvssParty<_>> = vec![];
     for i in 0..(1 << log_n) {
         let mut open_point = vec![];
         for j in 0..log_t {
             open_point.push(folding_parameter[j][i % folding_parameter[j].len()]);
         }
-        parties.push(AvssParty::new(
-            log_t - terminate_round,
-            &interpolate_cosets,
-            open_point,
-            &oracle,
-        ));
+        parties.push(AvssParty::new(&params, &interpolate_cosets, open_point, &oracle).unwrap());
     }
     let mut dealer = Dealer::new(
-        log_t - terminate_round,
+        &params,
         &polynomial,
         &interpolate_cosets,
         &oracle,
         &folding_parameter,
-    );
+    )
+    .unwrap();
     dealer.send_evaluations(&mut parties);
-    dealer.commit_functions(&parties);
+    dealer.commit_functions(&mut parties);
     dealer.prove();
-    dealer.commit_foldings(&parties);
+    dealer.commit_foldings(&mut parties);
     let (folding, function) = dealer.query();
     let mut folding0 = vec![];
     let mut function0 = vec![];
@@ -113,34 +106,50 @@ fn vss_verify(c: &mut Criterion, log_n: usize, terminate_round: usize) {
     }
     let mut group = c.benchmark_group("verify proof");
     group.sample_size(10);
-    group.bench_function(format!("vss verify {}", log_n), move |b| {
-        b.iter(|| {
-            parties[0].verify(&folding0, &function0);
-        })
-    });
+    group.bench_function(
+        format!("vss verify {:?} {}", T::FIELD_CHOICE, log_n),
+        move |b| {
+            b.iter(|| {
+                parties[0].verify(&folding0, &function0).unwrap();
+            })
+        },
+    );
 }
 
-fn bench_vss_deal(c: &mut Criterion) {
+fn bench_vss_deal<T: Field + 'static>(c: &mut Criterion) {
     for i in 5..21 {
         let terminate_round = 1;
-        c.bench_function(&format!("vss prove {}", i), move |b| {
-            b.iter(|| {
-                vss_deal(i, terminate_round);
-            })
-        });
+        c.bench_function(
+            &format!("vss prove {:?} {}", T::FIELD_CHOICE, i),
+            move |b| {
+                b.iter(|| {
+                    vss_deal::<T>(i, terminate_round);
+                })
+            },
+        );
     }
 }
 
-fn bench_vss_verify(c: &mut Criterion) {
+fn bench_vss_verify<T: Field + 'static>(c: &mut Criterion) {
     for i in 5..21 {
         let terminate_round = 1;
-        vss_verify(c, i, terminate_round);
+        vss_verify::<T>(c, i, terminate_round);
     }
 }
 
 criterion_group!(
     name = benches;
     config = Criterion::default().sample_size(10);
-    targets = bench_vss_deal, bench_vss_verify
+    targets =
+        bench_vss_deal::<Mersenne61Ext>,
+        bench_vss_deal::<QuadExt<Fp64>>,
+        bench_vss_deal::<QuarticExt<BabyBear>>,
+        bench_vss_deal::<QuarticExt<KoalaBear>>,
+        bench_vss_deal::<Mersenne31Ext>,
+        bench_vss_verify::<Mersenne61Ext>,
+        bench_vss_verify::<QuadExt<Fp64>>,
+        bench_vss_verify::<QuarticExt<BabyBear>>,
+        bench_vss_verify::<QuarticExt<KoalaBear>>,
+        bench_vss_verify::<Mersenne31Ext>
 );
 criterion_main!(benches);
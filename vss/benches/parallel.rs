@@ -0,0 +1,74 @@
+extern crate criterion;
+
+use criterion::*;
+
+use util::algebra::coset::Coset;
+use util::algebra::field::mersenne61_ext::Mersenne61Ext;
+use util::algebra::field::Field;
+use util::algebra::polynomial::MultilinearPolynomial;
+use util::parallel::rayon::ThreadPoolBuilder;
+use util::params::{FieldChoice, ProtocolParams};
+use util::random_oracle::RandomOracle;
+use util::split_n;
+use vss::avss::dealer::Dealer;
+use vss::avss::party::AvssParty;
+
+fn vss_deal(log_n: usize, terminate_round: usize) {
+    let log_t = log_n - 1;
+    let params =
+        ProtocolParams::new(3, 100, terminate_round, 0, FieldChoice::Mersenne61Ext).unwrap();
+    let interpolate_cosets = params
+        .interpolate_cosets(log_t, Mersenne61Ext::random_element())
+        .unwrap();
+    let oracle = RandomOracle::from_params(log_t - terminate_round, &params);
+    let polynomial = MultilinearPolynomial::random_polynomial(log_t);
+
+    let x_shift = Mersenne61Ext::random_element();
+    let coset_x = Coset::new(1 << log_n, x_shift);
+    let mut folding_parameter = vec![];
+    let v = split_n((1 << log_t) - 1);
+    for i in &v {
+        folding_parameter.push(coset_x.pow(*i).all_elements());
+    }
+    let mut parties: Vec<AvssParty<_>> = vec![];
+    for i in 0..(1 << log_n) {
+        let mut open_point = vec![];
+        for j in 0..log_t {
+            open_point.push(folding_parameter[j][i % folding_parameter[j].len()]);
+        }
+        parties.push(AvssParty::new(&params, &interpolate_cosets, open_point, &oracle).unwrap());
+    }
+    let mut dealer = Dealer::new(
+        &params,
+        &polynomial,
+        &interpolate_cosets,
+        &oracle,
+        &folding_parameter,
+    )
+    .unwrap();
+    dealer.send_evaluations(&mut parties);
+    dealer.commit_functions(&mut parties);
+    dealer.prove();
+    dealer.commit_foldings(&mut parties);
+    dealer.query();
+}
+
+fn bench_vss_deal_scaling(c: &mut Criterion) {
+    for i in [10, 14] {
+        let mut group = c.benchmark_group(format!("vss prove {}", i));
+        group.sample_size(10);
+        for threads in [1, 2, 4, 8] {
+            let pool = ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            group.bench_function(format!("{} threads", threads), |b| {
+                b.iter(|| pool.install(|| vss_deal(i, 1)))
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_vss_deal_scaling);
+criterion_main!(benches);
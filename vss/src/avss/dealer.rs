@@ -1,27 +1,31 @@
 use super::party::AvssParty;
-use crate::one2many::prover::One2ManyProver;
+use crate::one2many::{prover::One2ManyProver, CAPABILITIES};
 use util::algebra::{coset::Coset, field::Field, polynomial::MultilinearPolynomial};
+use util::dispute::{Complaint, Response, VerifyingKey};
+use util::merkle_tree::{Blake3Algorithm, MerkleHasher};
+use util::parallel;
+use util::params::{ParamsError, ProtocolParams};
 use util::query_result::QueryResult;
 use util::random_oracle::RandomOracle;
 
-pub struct Dealer<T: Field> {
-    prover: One2ManyProver<T>,
+pub struct Dealer<T: Field, H: MerkleHasher = Blake3Algorithm> {
+    prover: One2ManyProver<T, H>,
     evaluations: Vec<MultilinearPolynomial<T>>,
 }
 
-impl<T: Field + 'static> Dealer<T> {
+impl<T: Field + 'static, H: MerkleHasher> Dealer<T, H> {
     fn fold(values: &Vec<T>, parameter: T, coset: &Coset<T>) -> Vec<T> {
+        let elements_inv = coset.elements_inv();
+        let inverse_2 = coset.inverse_2();
         let len = values.len() / 2;
-        let res = (0..len)
-            .into_iter()
+        (0..len)
             .map(|i| {
                 let x = values[i];
                 let nx = values[i + len];
-                let new_v = (x + nx) + parameter * (x - nx) * coset.element_inv_at(i);
-                new_v * T::INVERSE_2
+                let new_v = (x + nx) + parameter * (x - nx) * elements_inv[i];
+                new_v * inverse_2
             })
-            .collect();
-        res
+            .collect()
     }
 
     fn batch_folding(
@@ -35,18 +39,15 @@ impl<T: Field + 'static> Dealer<T> {
         let mut evaluations = vec![];
         for round in 0..total_round {
             let len = res[round].len();
+            // Every party's folding of this round is independent.
+            let foldings = parallel::map(folding_parameter[round].len(), |index| {
+                let j = folding_parameter[round][index];
+                Self::fold(&res[round][index & (len - 1)], j, &coset[round])
+            });
             if round < total_round - 1 {
-                let mut evaluations = vec![];
-                for (index, j) in folding_parameter[round].iter().enumerate() {
-                    let next_evaluation =
-                        Self::fold(&res[round][index & (len - 1)], *j, &coset[round]);
-                    evaluations.push(next_evaluation);
-                }
-                res.push(evaluations);
+                res.push(foldings);
             } else {
-                for (index, j) in folding_parameter[round].iter().enumerate() {
-                    let next_evaluation =
-                        Self::fold(&res[round][index & (len - 1)], *j, &coset[round]);
+                for next_evaluation in foldings {
                     let mut coefficients = coset[round + 1].ifft(next_evaluation);
                     coefficients.truncate(1 << (variable_num - total_round));
                     evaluations.push(MultilinearPolynomial::new(coefficients));
@@ -57,35 +58,83 @@ impl<T: Field + 'static> Dealer<T> {
     }
 
     pub fn new(
-        total_round: usize,
+        params: &ProtocolParams,
         polynomial: &MultilinearPolynomial<T>,
         interpolate_coset: &Vec<Coset<T>>,
         oracle: &RandomOracle<T>,
         folding_parameter: &Vec<Vec<T>>,
-    ) -> Self {
+    ) -> Result<Self, ParamsError> {
+        params.require(CAPABILITIES)?;
+        let total_round = params.total_round(polynomial.variable_num())?;
         let (functions, evaluations) = Self::batch_folding(
             total_round,
             polynomial,
             folding_parameter,
             interpolate_coset,
         );
-        Dealer {
+        Ok(Dealer {
             evaluations,
-            prover: One2ManyProver::new(total_round, interpolate_coset, functions, oracle),
+            prover: One2ManyProver::new(params, interpolate_coset, functions, oracle)?,
+        })
+    }
+
+    /// Shares a random polynomial on `variable_num` variables whose first
+    /// coefficients are `secrets`, so that any `2^variable_num` parties
+    /// recover them with `reconstruction::reconstruct`. Each secret beyond
+    /// the first lowers by one the number of parties learning nothing.
+    pub fn with_secrets(
+        params: &ProtocolParams,
+        variable_num: usize,
+        secrets: &[T],
+        interpolate_coset: &Vec<Coset<T>>,
+        oracle: &RandomOracle<T>,
+        folding_parameter: &Vec<Vec<T>>,
+    ) -> Result<Self, ParamsError> {
+        if secrets.len() > 1 << variable_num {
+            return Err(ParamsError::TooManySecrets);
         }
+        let mut coefficients = MultilinearPolynomial::random_polynomial(variable_num)
+            .coefficients()
+            .clone();
+        coefficients[..secrets.len()].copy_from_slice(secrets);
+        Self::new(
+            params,
+            &MultilinearPolynomial::new(coefficients),
+            interpolate_coset,
+            oracle,
+            folding_parameter,
+        )
     }
 
-    pub fn commit_functions(&self, avss_party: &Vec<AvssParty<T>>) {
-        let verifiers = avss_party.iter().map(|x| x.verifier.clone()).collect();
-        self.prover.commit_functions(&verifiers);
+    pub fn with_secret(
+        params: &ProtocolParams,
+        variable_num: usize,
+        secret: T,
+        interpolate_coset: &Vec<Coset<T>>,
+        oracle: &RandomOracle<T>,
+        folding_parameter: &Vec<Vec<T>>,
+    ) -> Result<Self, ParamsError> {
+        Self::with_secrets(
+            params,
+            variable_num,
+            &[secret],
+            interpolate_coset,
+            oracle,
+            folding_parameter,
+        )
+    }
+
+    pub fn commit_functions(&self, avss_party: &mut Vec<AvssParty<T, H>>) {
+        let verifiers = avss_party.iter_mut().map(|x| &mut x.verifier).collect();
+        self.prover.commit_functions(verifiers);
     }
 
-    pub fn commit_foldings(&self, avss_party: &Vec<AvssParty<T>>) {
-        let verifiers = avss_party.iter().map(|x| x.verifier.clone()).collect();
-        self.prover.commit_foldings(&verifiers);
+    pub fn commit_foldings(&self, avss_party: &mut Vec<AvssParty<T, H>>) {
+        let verifiers = avss_party.iter_mut().map(|x| &mut x.verifier).collect();
+        self.prover.commit_foldings(verifiers);
     }
 
-    pub fn send_evaluations(&self, avss_party: &mut Vec<AvssParty<T>>) {
+    pub fn send_evaluations(&self, avss_party: &mut Vec<AvssParty<T, H>>) {
         for i in 0..avss_party.len() {
             avss_party[i].set_share(&self.evaluations[i % self.evaluations.len()]);
         }
@@ -98,4 +147,33 @@ impl<T: Field + 'static> Dealer<T> {
     pub fn query(&self) -> (Vec<Vec<QueryResult<T>>>, Vec<Vec<QueryResult<T>>>) {
         self.prover.query()
     }
+
+    /// Reveals the share of every complaining party with the proofs of it,
+    /// ignoring the complaints not signed with the party's key in
+    /// `verifying_keys`.
+    pub fn respond(
+        &self,
+        complaints: &[Complaint],
+        verifying_keys: &[VerifyingKey],
+    ) -> Vec<Response<T>> {
+        let (folding, function) = self.query();
+        complaints
+            .iter()
+            .filter(|complaint| {
+                verifying_keys.get(complaint.party).is_some_and(|key| {
+                    let commitment = self.prover.share_commitment(complaint.party);
+                    complaint.verify(key, commitment.as_ref())
+                })
+            })
+            .map(|complaint| {
+                let i = complaint.party;
+                Response {
+                    party: i,
+                    final_poly: self.evaluations[i % self.evaluations.len()].clone(),
+                    folding_proofs: folding.iter().map(|x| x[i % x.len()].clone()).collect(),
+                    function_proofs: function.iter().map(|x| x[i % x.len()].clone()).collect(),
+                }
+            })
+            .collect()
+    }
 }
@@ -1,17 +1,22 @@
 use crate::one2many::verifier::One2ManyVerifier;
-use std::{cell::RefCell, rc::Rc};
 use util::algebra::{coset::Coset, field::Field, polynomial::MultilinearPolynomial};
+use util::dispute::{random_signing_key, DisputeParty, Response, SigningKey};
+use util::merkle_tree::{Blake3Algorithm, MerkleHasher};
+use util::params::{ParamsError, ProtocolParams};
 use util::query_result::QueryResult;
 use util::random_oracle::RandomOracle;
+use util::verification_error::VerificationError;
 
 #[derive(Clone)]
-pub struct AvssParty<T: Field> {
-    pub verifier: Rc<RefCell<One2ManyVerifier<T>>>,
+pub struct AvssParty<T: Field, H: MerkleHasher = Blake3Algorithm> {
+    pub verifier: One2ManyVerifier<T, H>,
     open_point: Vec<T>,
     final_poly: Option<MultilinearPolynomial<T>>,
+    /// Signs the complaints of this party, see `util::dispute`.
+    signing_key: SigningKey,
 }
 
-impl<T: Field + 'static> AvssParty<T> {
+impl<T: Field + 'static, H: MerkleHasher> AvssParty<T, H> {
     pub fn share(&self) -> T {
         let poly = self.final_poly.as_ref().unwrap();
         let variable_num = poly.variable_num();
@@ -28,33 +33,75 @@ impl<T: Field + 'static> AvssParty<T> {
     }
 
     pub fn new(
-        total_round: usize,
+        params: &ProtocolParams,
         interpolate_coset: &Vec<Coset<T>>,
         open_point: Vec<T>,
         oracle: &RandomOracle<T>,
-    ) -> AvssParty<T> {
-        AvssParty {
-            verifier: Rc::new(RefCell::new(One2ManyVerifier::new_with_default_map(
-                total_round,
+    ) -> Result<AvssParty<T, H>, ParamsError> {
+        Ok(AvssParty {
+            verifier: One2ManyVerifier::new_with_default_map(
+                params,
                 open_point.len(),
                 interpolate_coset,
                 oracle,
-            ))),
+            )?,
             open_point,
             final_poly: None,
-        }
+            signing_key: random_signing_key(),
+        })
     }
 
     pub fn verify(
         &self,
         folding_proofs: &Vec<QueryResult<T>>,
         function_proofs: &Vec<QueryResult<T>>,
-    ) -> bool {
-        self.verifier.borrow().verify_with_extra_folding(
+    ) -> Result<(), VerificationError> {
+        self.verifier.verify_with_extra_folding(
             folding_proofs,
             function_proofs,
             &self.open_point,
-            self.final_poly.as_ref().unwrap(),
+            self.final_poly
+                .as_ref()
+                .ok_or(VerificationError::WrongProofShape)?,
         )
     }
+
+    /// Checks the share the dealer revealed for this party after its
+    /// complaint, against the commitments this party received.
+    pub fn verify_response(&self, response: &Response<T>) -> Result<(), VerificationError> {
+        self.verifier.verify_with_extra_folding(
+            &response.folding_proofs,
+            &response.function_proofs,
+            &self.open_point,
+            &response.final_poly,
+        )
+    }
+}
+
+impl<T: Field + 'static, H: MerkleHasher> DisputeParty<T> for AvssParty<T, H> {
+    fn verify_share(
+        &self,
+        folding_proofs: &[QueryResult<T>],
+        function_proofs: &[QueryResult<T>],
+    ) -> Result<(), VerificationError> {
+        self.verify(&folding_proofs.to_vec(), &function_proofs.to_vec())
+    }
+
+    fn verify_response(&self, response: &Response<T>) -> Result<(), VerificationError> {
+        AvssParty::verify_response(self, response)
+    }
+
+    fn set_share(&mut self, final_poly: &MultilinearPolynomial<T>) {
+        AvssParty::set_share(self, final_poly)
+    }
+
+    fn share_commitment(&self) -> Vec<u8> {
+        self.verifier
+            .share_commitment()
+            .map_or(vec![], |x| x.as_ref().to_vec())
+    }
+
+    fn signing_key(&self) -> &SigningKey {
+        &self.signing_key
+    }
 }
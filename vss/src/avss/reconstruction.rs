@@ -0,0 +1,164 @@
+//! Reconstruction of the polynomial shared by a `Dealer`.
+//!
+//! With the folding parameters built from `split_n(2^k - 1)`, party `i` has
+//! the open point `(x_i^(2^(k-1)), ..., x_i^2, x_i)` and receives
+//! `f(x_i^(2^(k-1)), ..., x_i) = F(x_i)` for `F(x) = sum_S c_S x^rev(S)`,
+//! `c_S` being the coefficients of `f` and `rev` reversing the `k` bits of
+//! `S`. `F` has degree `t = 2^k - 1`, so any `t + 1` shares determine it, and
+//! with it the secrets embedded by `Dealer::with_secrets`. Beyond `t + 1`,
+//! the shares form a Reed–Solomon codeword, so that the robust variants
+//! correct wrong shares and point out the parties that sent them.
+
+use util::algebra::{
+    field::Field,
+    polynomial::{MultilinearPolynomial, Polynomial},
+    reed_solomon::{decode, interpolate},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReconstructionError {
+    /// Fewer shares than the `t + 1` needed.
+    NotEnoughShares { needed: usize, given: usize },
+    /// The open point of this share is not of the form
+    /// `(x^(2^(k-1)), ..., x^2, x)`, or repeats the `x` of an earlier share.
+    InvalidPoint(usize),
+    /// The shares beyond the first `t + 1` disagree with them.
+    InconsistentShares,
+    /// More than `(n - t - 1) / 2` of the `n` shares are wrong.
+    TooManyWrongShares,
+    /// More secrets asked for than the `t + 1` coefficients.
+    TooManySecrets { max: usize, given: usize },
+}
+
+impl std::fmt::Display for ReconstructionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ReconstructionError::NotEnoughShares { needed, given } => {
+                write!(f, "{} shares given, {} needed", given, needed)
+            }
+            ReconstructionError::InvalidPoint(index) => {
+                write!(f, "invalid open point of share {}", index)
+            }
+            ReconstructionError::InconsistentShares => write!(f, "inconsistent shares"),
+            ReconstructionError::TooManyWrongShares => write!(f, "too many wrong shares"),
+            ReconstructionError::TooManySecrets { max, given } => {
+                write!(f, "{} secrets asked, at most {}", given, max)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReconstructionError {}
+
+/// The `x` of a party's open point, checking the form of the point.
+fn party_x<T: Field>(point: &[T]) -> Option<T> {
+    let x = *point.last()?;
+    for j in 0..point.len() - 1 {
+        if point[j] != point[j + 1] * point[j + 1] {
+            return None;
+        }
+    }
+    Some(x)
+}
+
+/// The `x` of every party after checking that there are at least `t + 1`
+/// shares, together with the number `k` of variables.
+fn party_xs<T: Field>(shares: &[(Vec<T>, T)]) -> Result<(Vec<T>, usize), ReconstructionError> {
+    let variable_num = shares.first().map_or(0, |(point, _)| point.len());
+    let needed = 1 << variable_num;
+    if variable_num == 0 || shares.len() < needed {
+        return Err(ReconstructionError::NotEnoughShares {
+            needed,
+            given: shares.len(),
+        });
+    }
+    let mut xs = vec![];
+    for (i, (point, _)) in shares.iter().enumerate() {
+        match party_x(point) {
+            Some(x) if point.len() == variable_num && !xs.contains(&x) => xs.push(x),
+            _ => return Err(ReconstructionError::InvalidPoint(i)),
+        }
+    }
+    Ok((xs, variable_num))
+}
+
+/// The multilinear polynomial `f` with `F = univariate`.
+fn to_multilinear<T: Field>(
+    univariate: &Polynomial<T>,
+    variable_num: usize,
+) -> MultilinearPolynomial<T> {
+    let mut coefficients = univariate.coefficients().clone();
+    coefficients.resize(1 << variable_num, T::from_int(0));
+    let shift = usize::BITS as usize - variable_num;
+    MultilinearPolynomial::new(
+        (0..1 << variable_num)
+            .map(|s: usize| coefficients[s.reverse_bits() >> shift])
+            .collect(),
+    )
+}
+
+/// Reconstructs the shared polynomial from at least `t + 1` pairs of a
+/// party's open point and its verified share. The first `t + 1` pairs
+/// determine the polynomial and the others must agree with it.
+pub fn reconstruct_polynomial<T: Field>(
+    shares: &[(Vec<T>, T)],
+) -> Result<MultilinearPolynomial<T>, ReconstructionError> {
+    let (xs, variable_num) = party_xs(shares)?;
+    let needed = 1 << variable_num;
+    let ys = shares.iter().map(|(_, y)| *y).collect::<Vec<_>>();
+    let univariate = interpolate(&xs[..needed], &ys[..needed]);
+    for (x, y) in xs[needed..].iter().zip(&ys[needed..]) {
+        if univariate.evaluation_at(*x) != *y {
+            return Err(ReconstructionError::InconsistentShares);
+        }
+    }
+    Ok(to_multilinear(&univariate, variable_num))
+}
+
+/// Reconstructs the shared polynomial from `n` shares of which at most
+/// `(n - t - 1) / 2` are wrong, for instance sent by malicious parties,
+/// returning it with the indices of the wrong shares.
+pub fn robust_reconstruct_polynomial<T: Field>(
+    shares: &[(Vec<T>, T)],
+) -> Result<(MultilinearPolynomial<T>, Vec<usize>), ReconstructionError> {
+    let (xs, variable_num) = party_xs(shares)?;
+    let ys = shares.iter().map(|(_, y)| *y).collect::<Vec<_>>();
+    let (univariate, wrong) =
+        decode(&xs, &ys, 1 << variable_num).ok_or(ReconstructionError::TooManyWrongShares)?;
+    Ok((to_multilinear(&univariate, variable_num), wrong))
+}
+
+/// The first `secret_num` coefficients of `polynomial`.
+fn secrets<T: Field>(
+    polynomial: &MultilinearPolynomial<T>,
+    secret_num: usize,
+) -> Result<Vec<T>, ReconstructionError> {
+    let coefficients = polynomial.coefficients();
+    if secret_num > coefficients.len() {
+        return Err(ReconstructionError::TooManySecrets {
+            max: coefficients.len(),
+            given: secret_num,
+        });
+    }
+    Ok(coefficients[..secret_num].to_vec())
+}
+
+/// The first `secret_num` coefficients of the shared polynomial, which are
+/// the secrets given to `Dealer::with_secrets`.
+pub fn reconstruct<T: Field>(
+    shares: &[(Vec<T>, T)],
+    secret_num: usize,
+) -> Result<Vec<T>, ReconstructionError> {
+    let polynomial = reconstruct_polynomial(shares)?;
+    secrets(&polynomial, secret_num)
+}
+
+/// `reconstruct` through `robust_reconstruct_polynomial`, also returning the
+/// indices of the wrong shares.
+pub fn robust_reconstruct<T: Field>(
+    shares: &[(Vec<T>, T)],
+    secret_num: usize,
+) -> Result<(Vec<T>, Vec<usize>), ReconstructionError> {
+    let (polynomial, wrong) = robust_reconstruct_polynomial(shares)?;
+    Ok((secrets(&polynomial, secret_num)?, wrong))
+}
@@ -1,37 +1,64 @@
 pub mod one2many {
     pub mod prover;
     pub mod verifier;
+
+    use util::params::Capabilities;
+
+    /// Features of `ProtocolParams` the one-to-many prover and verifiers
+    /// implement.
+    pub const CAPABILITIES: Capabilities = Capabilities {
+        hiding: false,
+        shared_trees: true,
+        max_folding_arity: 2,
+        terminate_round: true,
+    };
 }
 pub mod avss {
     pub mod dealer;
     pub mod party;
+    pub mod reconstruction;
 }
 
 #[cfg(test)]
 mod tests {
     use crate::avss::dealer::Dealer;
     use crate::avss::party::AvssParty;
+    use crate::avss::reconstruction::{reconstruct, robust_reconstruct, ReconstructionError};
     use std::mem::size_of;
     use util::algebra::field::Field;
     use util::algebra::polynomial::MultilinearPolynomial;
-    use util::{merkle_tree::MERKLE_ROOT_SIZE, random_oracle::RandomOracle};
+    use util::dispute::{self, Complaint, DisputeParty, Disqualification, VerifyingKey};
+    use util::merkle_tree::{Blake3Algorithm, Keccak256Algorithm, MerkleHasher, Sha256Algorithm};
+    use util::{poseidon::PoseidonAlgorithm, random_oracle::RandomOracle};
 
     use util::algebra::coset::Coset;
     use util::algebra::field::mersenne61_ext::Mersenne61Ext;
     use util::split_n;
 
-    use util::{CODE_RATE, SECURITY_BITS};
+    use util::params::{FieldChoice, ParamsError, ProtocolParams};
+    use util::verification_error::VerificationError;
 
-    fn output_proof_size(log_n: usize, terminate_round: usize) -> usize {
+    fn output_proof_size<H: MerkleHasher>(
+        log_n: usize,
+        terminate_round: usize,
+        grinding_bits: usize,
+        shared_trees: bool,
+    ) -> usize {
         let log_t = log_n - 1;
-        let oracle = RandomOracle::new(log_t - terminate_round, SECURITY_BITS / CODE_RATE);
-        let mut interpolate_cosets = vec![Coset::new(
-            1 << (log_t + CODE_RATE),
-            Mersenne61Ext::random_element(),
-        )];
-        for i in 1..log_t {
-            interpolate_cosets.push(interpolate_cosets[i - 1].pow(2));
-        }
+        let params = ProtocolParams::new(
+            3,
+            100,
+            terminate_round,
+            grinding_bits,
+            FieldChoice::Mersenne61Ext,
+        )
+        .unwrap()
+        .with_shared_trees(shared_trees)
+        .unwrap();
+        let interpolate_cosets = params
+            .interpolate_cosets(log_t, Mersenne61Ext::random_element())
+            .unwrap();
+        let oracle = RandomOracle::from_params(log_t - terminate_round, &params).with_query_seed(0);
         let polynomial = MultilinearPolynomial::random_polynomial(log_t);
 
         let x_shift = Mersenne61Ext::random_element();
@@ -41,51 +68,443 @@ mod tests {
         for i in &v {
             folding_parameter.push(coset_x.pow(*i).all_elements());
         }
-        let mut parties = vec![];
+        let mut parties: Vec<AvssParty<_, H>> = vec![];
         for i in 0..(1 << log_n) {
             let mut open_point = vec![];
             for j in 0..log_t {
                 open_point.push(folding_parameter[j][i % folding_parameter[j].len()]);
             }
-            parties.push(AvssParty::new(
-                log_t - terminate_round,
-                &interpolate_cosets,
-                open_point,
-                &oracle,
-            ));
+            parties
+                .push(AvssParty::new(&params, &interpolate_cosets, open_point, &oracle).unwrap());
         }
         let mut dealer = Dealer::new(
-            log_t - terminate_round,
+            &params,
             &polynomial,
             &interpolate_cosets,
             &oracle,
             &folding_parameter,
-        );
+        )
+        .unwrap();
         dealer.send_evaluations(&mut parties);
-        dealer.commit_functions(&parties);
+        dealer.commit_functions(&mut parties);
         dealer.prove();
-        dealer.commit_foldings(&parties);
+        dealer.commit_foldings(&mut parties);
         let (folding, function) = dealer.query();
-        let mut folding0 = vec![];
-        let mut function0 = vec![];
-        for i in 0..(log_t - terminate_round) {
-            if i < log_t - terminate_round - 1 {
-                folding0.push(folding[i][0].clone());
-            }
-            function0.push(function[i][0].clone());
-        }
-        assert!(parties[0].verify(&folding0, &function0));
+        let folding0: Vec<_> = folding.iter().map(|x| x[0].clone()).collect();
+        let function0: Vec<_> = function.iter().map(|x| x[0].clone()).collect();
+        assert_eq!(parties[0].verify(&folding0, &function0), Ok(()));
+        let trees_per_round = if shared_trees { 1 } else { 2 };
         folding0.iter().map(|x| x.proof_size()).sum::<usize>()
             + function0.iter().map(|x| x.proof_size()).sum::<usize>()
-            + (log_t - terminate_round) * MERKLE_ROOT_SIZE * 2
+            + (log_t - terminate_round) * H::DIGEST_SIZE * trees_per_round
             + ((1 << terminate_round) + 1) * size_of::<Mersenne61Ext>() * 2
     }
 
     #[test]
     fn test_proof_size() {
-        for i in 5..21 {
-            let proof_size = output_proof_size(i, 1);
-            println!("vss proof size of {} variables is {} bytes", i, proof_size);
+        for shared_trees in [false, true] {
+            for i in 5..21 {
+                let proof_size = output_proof_size::<Blake3Algorithm>(i, 1, 0, shared_trees);
+                println!(
+                    "vss proof size of {} variables with {} trees is {} bytes",
+                    i,
+                    if shared_trees { "shared" } else { "separate" },
+                    proof_size
+                );
+            }
         }
     }
+
+    #[test]
+    fn pluggable_hasher() {
+        output_proof_size::<Sha256Algorithm>(8, 1, 0, false);
+        output_proof_size::<Keccak256Algorithm>(8, 1, 0, false);
+        output_proof_size::<PoseidonAlgorithm<Mersenne61Ext>>(8, 1, 0, false);
+    }
+
+    #[test]
+    fn grinding() {
+        let with_grinding = output_proof_size::<Blake3Algorithm>(10, 1, 12, false);
+        assert!(with_grinding < output_proof_size::<Blake3Algorithm>(10, 1, 0, false));
+    }
+
+    #[test]
+    fn shared_trees() {
+        let shared = output_proof_size::<Blake3Algorithm>(12, 1, 0, true);
+        let separate = output_proof_size::<Blake3Algorithm>(12, 1, 0, false);
+        assert!(shared * 4 < separate * 3);
+    }
+
+    #[test]
+    fn parameter_mismatch() {
+        let log_t = 5;
+        let params = ProtocolParams::default();
+        let interpolate_cosets = params
+            .interpolate_cosets(log_t, Mersenne61Ext::random_element())
+            .unwrap();
+        let oracle = RandomOracle::from_params(log_t - 1, &params);
+        let polynomial = MultilinearPolynomial::random_polynomial(log_t);
+        let coset_x = Coset::new(1 << (log_t + 1), Mersenne61Ext::random_element());
+        let folding_parameter: Vec<_> = split_n((1 << log_t) - 1)
+            .iter()
+            .map(|i| coset_x.pow(*i).all_elements())
+            .collect();
+        let open_point = folding_parameter.iter().map(|x| x[0]).collect();
+        let party_params = params.with_query_num(params.query_num + 1).unwrap();
+        let mut parties: Vec<AvssParty<_>> =
+            vec![AvssParty::new(&party_params, &interpolate_cosets, open_point, &oracle).unwrap()];
+        let mut dealer = Dealer::new(
+            &params,
+            &polynomial,
+            &interpolate_cosets,
+            &oracle,
+            &folding_parameter,
+        )
+        .unwrap();
+        dealer.send_evaluations(&mut parties);
+        dealer.commit_functions(&mut parties);
+        dealer.prove();
+        dealer.commit_foldings(&mut parties);
+        let (folding, function) = dealer.query();
+        let folding0 = folding.iter().map(|x| x[0].clone()).collect();
+        let function0 = function.iter().map(|x| x[0].clone()).collect();
+        assert_eq!(
+            parties[0].verify(&folding0, &function0),
+            Err(VerificationError::ParameterMismatch)
+        );
+    }
+
+    #[test]
+    fn verify_on_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Dealer<Mersenne61Ext>>();
+        assert_send_sync::<AvssParty<Mersenne61Ext>>();
+
+        let log_t = 5;
+        let params = ProtocolParams::default();
+        let interpolate_cosets = params
+            .interpolate_cosets(log_t, Mersenne61Ext::random_element())
+            .unwrap();
+        let oracle = RandomOracle::from_params(log_t - 1, &params);
+        let polynomial = MultilinearPolynomial::random_polynomial(log_t);
+        let coset_x = Coset::new(1 << (log_t + 1), Mersenne61Ext::random_element());
+        let folding_parameter: Vec<_> = split_n((1 << log_t) - 1)
+            .iter()
+            .map(|i| coset_x.pow(*i).all_elements())
+            .collect();
+        let mut parties: Vec<AvssParty<_>> = (0..(1 << (log_t + 1)))
+            .map(|i| {
+                let open_point = folding_parameter.iter().map(|x| x[i % x.len()]).collect();
+                AvssParty::new(&params, &interpolate_cosets, open_point, &oracle).unwrap()
+            })
+            .collect();
+        let mut dealer = Dealer::new(
+            &params,
+            &polynomial,
+            &interpolate_cosets,
+            &oracle,
+            &folding_parameter,
+        )
+        .unwrap();
+        dealer.send_evaluations(&mut parties);
+        dealer.commit_functions(&mut parties);
+        dealer.prove();
+        dealer.commit_foldings(&mut parties);
+        let (folding, function) = dealer.query();
+        std::thread::scope(|s| {
+            for (chunk_index, chunk) in parties.chunks(16).enumerate() {
+                let (folding, function) = (&folding, &function);
+                s.spawn(move || {
+                    for (k, party) in chunk.iter().enumerate() {
+                        let i = chunk_index * 16 + k;
+                        let folding = folding.iter().map(|x| x[i % x.len()].clone()).collect();
+                        let function = function.iter().map(|x| x[i % x.len()].clone()).collect();
+                        assert_eq!(party.verify(&folding, &function), Ok(()));
+                    }
+                });
+            }
+        });
+    }
+
+    /// Shares `secrets` among `2^(log_t + 1)` parties, up to the proofs.
+    fn deal(
+        log_t: usize,
+        secrets: &[Mersenne61Ext],
+    ) -> (Dealer<Mersenne61Ext>, Vec<AvssParty<Mersenne61Ext>>) {
+        let params = ProtocolParams::default();
+        let interpolate_cosets = params
+            .interpolate_cosets(log_t, Mersenne61Ext::random_element())
+            .unwrap();
+        let oracle = RandomOracle::from_params(log_t - 1, &params);
+        let coset_x = Coset::new(1 << (log_t + 1), Mersenne61Ext::random_element());
+        let folding_parameter: Vec<_> = split_n((1 << log_t) - 1)
+            .iter()
+            .map(|i| coset_x.pow(*i).all_elements())
+            .collect();
+        let mut parties: Vec<AvssParty<_>> = (0..(1 << (log_t + 1)))
+            .map(|i| {
+                let open_point = folding_parameter.iter().map(|x| x[i % x.len()]).collect();
+                AvssParty::new(&params, &interpolate_cosets, open_point, &oracle).unwrap()
+            })
+            .collect();
+        let mut dealer = Dealer::with_secrets(
+            &params,
+            log_t,
+            secrets,
+            &interpolate_cosets,
+            &oracle,
+            &folding_parameter,
+        )
+        .unwrap();
+        dealer.send_evaluations(&mut parties);
+        dealer.commit_functions(&mut parties);
+        dealer.prove();
+        dealer.commit_foldings(&mut parties);
+        (dealer, parties)
+    }
+
+    /// The open points and shares of the parties that verified.
+    fn verified_shares(
+        log_t: usize,
+        secrets: &[Mersenne61Ext],
+    ) -> Vec<(Vec<Mersenne61Ext>, Mersenne61Ext)> {
+        let (dealer, parties) = deal(log_t, secrets);
+        let (folding, function) = dealer.query();
+        let complaints = dispute::complaints(&parties, &folding, &function);
+        parties
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| complaints.iter().all(|x| x.party != *i))
+            .map(|(_, party)| (party.open_point().clone(), party.share()))
+            .collect()
+    }
+
+    fn verifying_keys(parties: &[AvssParty<Mersenne61Ext>]) -> Vec<VerifyingKey> {
+        parties.iter().map(|x| x.verifying_key()).collect()
+    }
+
+    /// Replaces the shares of `indices` by random ones, as a faulty dealer.
+    fn send_wrong_shares(
+        log_t: usize,
+        parties: &mut [AvssParty<Mersenne61Ext>],
+        indices: &[usize],
+    ) {
+        let total_round = ProtocolParams::default().total_round(log_t).unwrap();
+        for i in indices {
+            parties[*i].set_share(&MultilinearPolynomial::random_polynomial(
+                log_t - total_round,
+            ));
+        }
+    }
+
+    #[test]
+    fn reconstruction() {
+        let log_t = 5;
+        let secret = Mersenne61Ext::random_element();
+        let shares = verified_shares(log_t, &[secret]);
+        assert_eq!(shares.len(), 1 << (log_t + 1));
+        assert_eq!(reconstruct(&shares[..1 << log_t], 1), Ok(vec![secret]));
+        assert_eq!(
+            reconstruct(&shares[7..7 + (1 << log_t)], 1),
+            Ok(vec![secret])
+        );
+        assert_eq!(reconstruct(&shares, 1), Ok(vec![secret]));
+        assert_eq!(
+            reconstruct(&shares[1..1 << log_t], 1),
+            Err(ReconstructionError::NotEnoughShares {
+                needed: 1 << log_t,
+                given: (1 << log_t) - 1
+            })
+        );
+
+        let mut tampered = shares.clone();
+        tampered[40].1 += Mersenne61Ext::from_int(1);
+        assert_eq!(
+            reconstruct(&tampered, 1),
+            Err(ReconstructionError::InconsistentShares)
+        );
+        tampered[40] = tampered[3].clone();
+        assert_eq!(
+            reconstruct(&tampered, 1),
+            Err(ReconstructionError::InvalidPoint(40))
+        );
+    }
+
+    #[test]
+    fn reconstruction_of_several_secrets() {
+        let log_t = 4;
+        let secrets: Vec<_> = (0..3).map(|_| Mersenne61Ext::random_element()).collect();
+        let shares = verified_shares(log_t, &secrets);
+        assert_eq!(reconstruct(&shares[5..5 + (1 << log_t)], 3), Ok(secrets));
+
+        // At most t + 1 secrets are shared and reconstructed.
+        let too_many = ReconstructionError::TooManySecrets {
+            max: 1 << log_t,
+            given: (1 << log_t) + 1,
+        };
+        assert_eq!(
+            reconstruct(&shares, (1 << log_t) + 1),
+            Err(too_many.clone())
+        );
+        assert_eq!(robust_reconstruct(&shares, (1 << log_t) + 1), Err(too_many));
+        let params = ProtocolParams::default();
+        let interpolate_cosets = params
+            .interpolate_cosets(log_t, Mersenne61Ext::random_element())
+            .unwrap();
+        let dealer = Dealer::<Mersenne61Ext>::with_secrets(
+            &params,
+            log_t,
+            &vec![Mersenne61Ext::from_int(1); (1 << log_t) + 1],
+            &interpolate_cosets,
+            &RandomOracle::from_params(log_t - 1, &params),
+            &vec![],
+        );
+        assert_eq!(dealer.err(), Some(ParamsError::TooManySecrets));
+    }
+
+    #[test]
+    fn robust_reconstruction() {
+        let log_t = 5;
+        let secret = Mersenne61Ext::random_element();
+        let shares = verified_shares(log_t, &[secret]);
+        let n = shares.len();
+        assert_eq!(robust_reconstruct(&shares, 1), Ok((vec![secret], vec![])));
+
+        // Up to (n - t - 1) / 2 lying parties are found out.
+        let mut received = shares.clone();
+        let liars: Vec<_> = (0..n).step_by(2).take((n - (1 << log_t)) / 2).collect();
+        for i in &liars {
+            received[*i].1 = Mersenne61Ext::random_element();
+        }
+        assert_eq!(robust_reconstruct(&received, 1), Ok((vec![secret], liars)));
+        received[1].1 = Mersenne61Ext::random_element();
+        assert_eq!(
+            robust_reconstruct(&received, 1),
+            Err(ReconstructionError::TooManyWrongShares)
+        );
+
+        // With exactly t + 1 shares a wrong one is not even detected.
+        let mut received = shares[..1 << log_t].to_vec();
+        assert_eq!(robust_reconstruct(&received, 1), Ok((vec![secret], vec![])));
+        received[0].1 = Mersenne61Ext::random_element();
+        let (secrets, wrong) = robust_reconstruct(&received, 1).unwrap();
+        assert_ne!(secrets, vec![secret]);
+        assert!(wrong.is_empty());
+    }
+
+    #[test]
+    fn honest_dealer() {
+        let log_t = 4;
+        let (dealer, mut parties) = deal(log_t, &[Mersenne61Ext::random_element()]);
+        let (folding, function) = dealer.query();
+        let complaints = dispute::complaints(&parties, &folding, &function);
+        assert!(complaints.is_empty());
+        let responses = dealer.respond(&complaints, &verifying_keys(&parties));
+        assert_eq!(
+            dispute::resolve(&mut parties, &complaints, &responses, (1 << log_t) - 1),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn faulty_dealer() {
+        let log_t = 4;
+        let secret = Mersenne61Ext::random_element();
+        let (dealer, mut parties) = deal(log_t, &[secret]);
+        send_wrong_shares(log_t, &mut parties, &[3, 10]);
+        let (folding, function) = dealer.query();
+        let complaints = dispute::complaints(&parties, &folding, &function);
+        assert_eq!(
+            complaints.iter().map(|x| x.party).collect::<Vec<_>>(),
+            vec![3, 10]
+        );
+
+        // The revealed shares replace the wrong ones.
+        let responses = dealer.respond(&complaints, &verifying_keys(&parties));
+        assert_eq!(
+            dispute::resolve(&mut parties, &complaints, &responses, (1 << log_t) - 1),
+            Ok(())
+        );
+        assert!(dispute::complaints(&parties, &folding, &function).is_empty());
+        let shares: Vec<_> = parties
+            .iter()
+            .map(|x| (x.open_point().clone(), x.share()))
+            .collect();
+        assert_eq!(reconstruct(&shares, 1), Ok(vec![secret]));
+    }
+
+    #[test]
+    fn malicious_dealer() {
+        let log_t = 4;
+        let threshold = (1 << log_t) - 1;
+        let (dealer, mut parties) = deal(log_t, &[Mersenne61Ext::random_element()]);
+        send_wrong_shares(log_t, &mut parties, &[3, 10]);
+        let (folding, function) = dealer.query();
+        let complaints = dispute::complaints(&parties, &folding, &function);
+
+        assert_eq!(
+            dispute::resolve(&mut parties.clone(), &complaints, &[], threshold),
+            Err(Disqualification::MissingResponse(3))
+        );
+        let mut responses = dealer.respond(&complaints, &verifying_keys(&parties));
+        responses[1].final_poly =
+            MultilinearPolynomial::random_polynomial(responses[1].final_poly.variable_num());
+        assert!(matches!(
+            dispute::resolve(&mut parties.clone(), &complaints, &responses, threshold),
+            Err(Disqualification::InvalidResponse(10, _))
+        ));
+
+        send_wrong_shares(log_t, &mut parties, &(0..threshold + 1).collect::<Vec<_>>());
+        let complaints = dispute::complaints(&parties, &folding, &function);
+        let responses = dealer.respond(&complaints, &verifying_keys(&parties));
+        assert_eq!(
+            dispute::resolve(&mut parties, &complaints, &responses, threshold),
+            Err(Disqualification::TooManyComplaints {
+                complaints: threshold + 1,
+                threshold
+            })
+        );
+    }
+
+    #[test]
+    fn forged_complaint() {
+        let log_t = 4;
+        let threshold = (1 << log_t) - 1;
+        let (dealer, mut parties) = deal(log_t, &[Mersenne61Ext::random_element()]);
+        let share = parties[3].share();
+
+        // Party 6 complains in the name of party 3 to have its share revealed.
+        let forged = vec![Complaint::new(
+            3,
+            parties[6].signing_key(),
+            &parties[3].share_commitment(),
+        )];
+        assert!(dealer
+            .respond(&forged, &verifying_keys(&parties))
+            .is_empty());
+        assert_eq!(
+            dispute::resolve(&mut parties, &forged, &[], threshold),
+            Ok(())
+        );
+
+        // A complaint of party 3 about another dealing is not one about this.
+        let replayed = vec![Complaint::new(3, parties[3].signing_key(), &[0; 32])];
+        assert!(dealer
+            .respond(&replayed, &verifying_keys(&parties))
+            .is_empty());
+        assert_eq!(
+            dispute::resolve(&mut parties, &replayed, &[], threshold),
+            Ok(())
+        );
+        assert_eq!(parties[3].share(), share);
+
+        // Forged complaints do not count towards the threshold either.
+        let forged: Vec<_> = (0..=threshold)
+            .map(|i| Complaint::new(i, parties[i + 1].signing_key(), &[]))
+            .collect();
+        assert_eq!(
+            dispute::resolve(&mut parties, &forged, &[], threshold),
+            Ok(())
+        );
+    }
 }
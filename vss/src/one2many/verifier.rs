@@ -1,70 +1,92 @@
+use super::CAPABILITIES;
 use util::algebra::polynomial::{MultilinearPolynomial, Polynomial};
-use util::merkle_tree::MERKLE_ROOT_SIZE;
+use util::params::{ParamsError, ProtocolParams};
 use util::random_oracle::RandomOracle;
 use util::{
     algebra::{coset::Coset, field::Field},
-    merkle_tree::MerkleTreeVerifier,
+    merkle_tree::{Blake3Algorithm, MerkleHasher, MerkleTreeVerifier},
     query_result::QueryResult,
+    verification_error::VerificationError,
 };
 
 #[derive(Clone)]
-pub struct One2ManyVerifier<T: Field> {
+pub struct One2ManyVerifier<T: Field, H: MerkleHasher = Blake3Algorithm> {
+    params: ProtocolParams,
+    prover_params: Option<ProtocolParams>,
     total_round: usize,
     log_max_degree: usize,
     interpolate_cosets: Vec<Coset<T>>,
-    function_root: Vec<MerkleTreeVerifier>,
-    folding_root: Vec<MerkleTreeVerifier>,
+    function_root: Vec<MerkleTreeVerifier<H>>,
+    folding_root: Vec<MerkleTreeVerifier<H>>,
     oracle: RandomOracle<T>,
     final_value: Option<Polynomial<T>>,
+    pow_nonce: u64,
 }
 
-impl<T: Field> One2ManyVerifier<T> {
+impl<T: Field, H: MerkleHasher> One2ManyVerifier<T, H> {
     pub fn new_with_default_map(
-        total_round: usize,
+        params: &ProtocolParams,
         log_max_degree: usize,
         coset: &Vec<Coset<T>>,
         oracle: &RandomOracle<T>,
-    ) -> Self {
-        One2ManyVerifier {
-            total_round,
+    ) -> Result<Self, ParamsError> {
+        params.check_field::<T>()?;
+        params.require(CAPABILITIES)?;
+        Ok(One2ManyVerifier {
+            params: *params,
+            prover_params: None,
+            total_round: params.total_round(log_max_degree)?,
             log_max_degree,
             interpolate_cosets: coset.clone(),
             function_root: vec![],
             folding_root: vec![],
             oracle: oracle.clone(),
             final_value: None,
-        }
+            pow_nonce: 0,
+        })
     }
 
     pub fn new(
-        total_round: usize,
+        params: &ProtocolParams,
         log_max_degree: usize,
         coset: &Vec<Coset<T>>,
         oracle: &RandomOracle<T>,
-    ) -> Self {
-        One2ManyVerifier {
-            total_round,
+    ) -> Result<Self, ParamsError> {
+        params.check_field::<T>()?;
+        params.require(CAPABILITIES)?;
+        Ok(One2ManyVerifier {
+            params: *params,
+            prover_params: None,
+            total_round: params.total_round(log_max_degree)?,
             log_max_degree,
             interpolate_cosets: coset.clone(),
             function_root: vec![],
             folding_root: vec![],
             oracle: oracle.clone(),
             final_value: None,
-        }
+            pow_nonce: 0,
+        })
+    }
+
+    /// Parameters the prover claims to have used, checked by `verify`.
+    pub fn receive_params(&mut self, params: &ProtocolParams) {
+        self.prover_params = Some(*params);
     }
 
-    pub fn set_function(&mut self, leave_number: usize, function_root: &[u8; MERKLE_ROOT_SIZE]) {
+    pub fn set_function(&mut self, leave_number: usize, function_root: &H::Hash) {
         self.function_root.push(MerkleTreeVerifier {
-            merkle_root: function_root.clone(),
+            merkle_root: *function_root,
             leave_number,
         });
     }
 
-    pub fn receive_folding_root(
-        &mut self,
-        leave_number: usize,
-        folding_root: [u8; MERKLE_ROOT_SIZE],
-    ) {
+    /// Root of the first function received, committing to this verifier's
+    /// share.
+    pub fn share_commitment(&self) -> Option<H::Hash> {
+        self.function_root.first().map(|x| x.merkle_root)
+    }
+
+    pub fn receive_folding_root(&mut self, leave_number: usize, folding_root: H::Hash) {
         self.folding_root.push(MerkleTreeVerifier {
             leave_number,
             merkle_root: folding_root,
@@ -72,17 +94,83 @@ impl<T: Field> One2ManyVerifier<T> {
     }
 
     pub fn set_final_value(&mut self, value: &Polynomial<T>) {
-        assert!(value.degree() <= 1 << (self.log_max_degree - self.total_round));
         self.final_value = Some(value.clone());
     }
 
+    pub fn set_pow_nonce(&mut self, nonce: u64) {
+        self.pow_nonce = nonce;
+    }
+
+    fn check_shape(
+        &self,
+        folding_proofs: &Vec<QueryResult<T>>,
+        function_proofs: &Vec<QueryResult<T>>,
+    ) -> Result<(), VerificationError> {
+        if self.prover_params != Some(self.params) {
+            return Err(VerificationError::ParameterMismatch);
+        }
+        let function_num = if self.params.shared_trees {
+            1
+        } else {
+            self.total_round
+        };
+        if self.total_round == 0
+            || self.function_root.len() != function_num
+            || self.folding_root.len() != self.total_round - 1
+            || function_proofs.len() != function_num
+            || folding_proofs.len() != self.total_round - 1
+        {
+            return Err(VerificationError::WrongProofShape);
+        }
+        match &self.final_value {
+            None => Err(VerificationError::WrongProofShape),
+            Some(value) if value.degree() > 1 << (self.log_max_degree - self.total_round) => {
+                Err(VerificationError::FinalPolynomialDegree)
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Splits the proofs of the shared layout into one proof per folding
+    /// and per function, without Merkle paths.
+    fn split_shared(
+        &self,
+        folding_proofs: &Vec<QueryResult<T>>,
+        function_proofs: &Vec<QueryResult<T>>,
+    ) -> (Vec<QueryResult<T>>, Vec<QueryResult<T>>) {
+        let mut foldings = vec![];
+        let mut functions = vec![function_proofs[0].clone()];
+        for (i, proof) in folding_proofs.iter().enumerate() {
+            let domain_size = self.interpolate_cosets[i + 1].size();
+            foldings.push(proof.column(domain_size, 0));
+            functions.push(proof.column(domain_size, 1));
+        }
+        (foldings, functions)
+    }
+
     pub fn verify_with_extra_folding(
         &self,
         folding_proofs: &Vec<QueryResult<T>>,
         function_proofs: &Vec<QueryResult<T>>,
         extra_folding_param: &Vec<T>,
         extra_final_poly: &MultilinearPolynomial<T>,
-    ) -> bool {
+    ) -> Result<(), VerificationError> {
+        self.check_shape(folding_proofs, function_proofs)?;
+        if extra_folding_param.len() < self.total_round {
+            return Err(VerificationError::WrongProofShape);
+        }
+        let shared = self.params.shared_trees;
+        let split;
+        let (folding_values, function_values) = if shared {
+            split = self.split_shared(folding_proofs, function_proofs);
+            (&split.0, &split.1)
+        } else {
+            (folding_proofs, function_proofs)
+        };
+        let final_value = self.final_value.as_ref().unwrap();
+        if !self.oracle.clone().check_proof_of_work(self.pow_nonce) {
+            return Err(VerificationError::InvalidProofOfWork);
+        }
         let mut leaf_indices = self.oracle.query_list.clone();
         for i in 0..self.total_round {
             let domain_size = self.interpolate_cosets[i].size();
@@ -93,66 +181,65 @@ impl<T: Field> One2ManyVerifier<T> {
             leaf_indices.sort();
             leaf_indices.dedup();
 
-            if i == 0 {
-                function_proofs[i].verify_merkle_tree(&leaf_indices, &self.function_root[0]);
-            } else {
-                folding_proofs[i - 1].verify_merkle_tree(&leaf_indices, &self.folding_root[i - 1]);
+            if i == 0 || !shared {
+                function_proofs[i].verify_merkle_tree(&leaf_indices, &self.function_root[i])?;
+            }
+            if i > 0 {
+                folding_proofs[i - 1].verify_batch_merkle_tree(
+                    &leaf_indices,
+                    &self.folding_root[i - 1],
+                    if shared { 2 } else { 1 },
+                )?;
             }
 
             let challenge = self.oracle.folding_challenges[i];
             let get_folding_value = if i == 0 {
-                &function_proofs[i].proof_values
+                &function_values[i]
             } else {
-                &folding_proofs[i - 1].proof_values
+                &folding_values[i - 1]
             };
 
-            let function_values = if i != 0 {
-                let function_query_result = &function_proofs[i];
-                function_query_result.verify_merkle_tree(&leaf_indices, &self.function_root[i]);
-                Some(&function_query_result.proof_values)
-            } else {
-                None
-            };
             for j in &leaf_indices {
-                let x = get_folding_value[j];
-                let nx = get_folding_value[&(j + domain_size / 2)];
-                let v =
-                    x + nx + challenge * (x - nx) * self.interpolate_cosets[i].element_inv_at(*j);
+                let x = get_folding_value.value_at(*j)?;
+                let nx = get_folding_value.value_at(j + domain_size / 2)?;
+                let mut v = x
+                    + nx
+                    + challenge * (x - nx) * self.interpolate_cosets[i].compute_element_inv_at(*j);
                 if i != 0 {
-                    let x = function_values.as_ref().unwrap()[j];
-                    let nx = function_values.as_ref().unwrap()[&(j + domain_size / 2)];
-                    let v = (v * challenge + (x + nx)) * challenge
-                        + (x - nx) * self.interpolate_cosets[i].element_inv_at(*j);
-                    if i == self.total_round - 1 {
-                        let x = self.interpolate_cosets[i + 1].element_at(*j);
-                        if v != self.final_value.as_ref().unwrap().evaluation_at(x) {
-                            return false;
-                        }
-                    } else if v != folding_proofs[i].proof_values[j] {
-                        return false;
-                    }
+                    let x = function_values[i].value_at(*j)?;
+                    let nx = function_values[i].value_at(j + domain_size / 2)?;
+                    v = (v * challenge + (x + nx)) * challenge
+                        + (x - nx) * self.interpolate_cosets[i].compute_element_inv_at(*j);
+                }
+                let next_v = if i == self.total_round - 1 {
+                    let x = self.interpolate_cosets[i + 1].compute_element_at(*j);
+                    final_value.evaluation_at(x)
                 } else {
-                    if v != folding_proofs[i].proof_values[j] {
-                        return false;
-                    }
+                    folding_values[i].value_at(*j)?
+                };
+                if v != next_v {
+                    return Err(VerificationError::FoldingMismatch(i));
                 }
-                let x = function_proofs[i].proof_values[j];
-                let nx = function_proofs[i].proof_values[&(j + domain_size / 2)];
+
+                let x = function_values[i].value_at(*j)?;
+                let nx = function_values[i].value_at(j + domain_size / 2)?;
                 let v = x
                     + nx
                     + extra_folding_param[i]
                         * (x - nx)
-                        * self.interpolate_cosets[i].element_inv_at(*j);
-                if i < self.total_round - 1 {
-                    assert_eq!(v, function_proofs[i + 1].proof_values[j] * T::from_int(2));
+                        * self.interpolate_cosets[i].compute_element_inv_at(*j);
+                let next_v = if i < self.total_round - 1 {
+                    function_values[i + 1].value_at(*j)?
                 } else {
-                    let x = self.interpolate_cosets[i + 1].element_at(*j);
-                    let poly_v = extra_final_poly.evaluate_as_polynomial(x);
-                    assert_eq!(v, poly_v * T::from_int(2));
+                    let x = self.interpolate_cosets[i + 1].compute_element_at(*j);
+                    extra_final_poly.evaluate_as_polynomial(x)
+                };
+                if v != next_v * T::from_int(2) {
+                    return Err(VerificationError::FoldingMismatch(i));
                 }
             }
         }
-        true
+        Ok(())
     }
 
     // pub fn verify_with_extra_folding(